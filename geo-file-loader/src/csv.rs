@@ -0,0 +1,209 @@
+use geozero::ToGeo;
+
+const X_COLUMN_NAMES: &[&str] = &["x", "lon", "lng", "long", "longitude", "easting"];
+const Y_COLUMN_NAMES: &[&str] = &["y", "lat", "latitude", "northing"];
+const GEOMETRY_COLUMN_NAMES: &[&str] = &["geometry", "geom", "the_geom", "wkt", "wkb", "shape"];
+
+/// The columns of a CSV file that hold each row's geometry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CsvGeometryColumns {
+    /// Point coordinates, e.g. longitude and latitude
+    Coordinates { x: String, y: String },
+    /// WKT or hex encoded WKB/EWKB
+    Geometry(String),
+}
+
+impl CsvGeometryColumns {
+    /// Guess the geometry columns from the column names, e.g. `lng` and `lat`, or `geometry`.
+    pub fn guess(headers: &[String]) -> Option<Self> {
+        let find = |names: &[&str]| {
+            headers
+                .iter()
+                .find(|header| names.contains(&header.trim().to_ascii_lowercase().as_str()))
+                .cloned()
+        };
+        if let (Some(x), Some(y)) = (find(X_COLUMN_NAMES), find(Y_COLUMN_NAMES)) {
+            return Some(CsvGeometryColumns::Coordinates { x, y });
+        }
+        find(GEOMETRY_COLUMN_NAMES).map(CsvGeometryColumns::Geometry)
+    }
+}
+
+/// Read the column names of a CSV file.
+pub fn csv_headers(bytes: &[u8]) -> Result<Vec<String>, crate::Error> {
+    let mut reader = csv::Reader::from_reader(bytes);
+    Ok(reader.headers()?.iter().map(String::from).collect())
+}
+
+pub struct CsvSource {
+    pub bytes: bytes::Bytes,
+    /// Guessed from the column names if `None`
+    pub geometry_columns: Option<CsvGeometryColumns>,
+}
+
+impl crate::FileLoader for CsvSource {
+    fn from_bytes(bytes: bytes::Bytes) -> Self {
+        CsvSource {
+            bytes,
+            geometry_columns: None,
+        }
+    }
+
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let mut reader = csv::Reader::from_reader(&self.bytes[..]);
+        let headers = reader
+            .headers()?
+            .iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        let geometry_columns = match self.geometry_columns {
+            Some(geometry_columns) => geometry_columns,
+            None => CsvGeometryColumns::guess(&headers)
+                .ok_or(crate::Error::CsvGeometryColumnsNotFound)?,
+        };
+        let column_index = |column: &str| {
+            headers
+                .iter()
+                .position(|header| header == column)
+                .ok_or_else(|| crate::Error::CsvColumnNotFound(column.to_owned()))
+        };
+        let geometry_column_indices = match geometry_columns {
+            CsvGeometryColumns::Coordinates { ref x, ref y } => {
+                GeometryColumnIndices::Coordinates(column_index(x)?, column_index(y)?)
+            }
+            CsvGeometryColumns::Geometry(ref column) => {
+                GeometryColumnIndices::Geometry(column_index(column)?)
+            }
+        };
+
+        let records = reader.records().collect::<Result<Vec<_>, _>>()?;
+        // Every value of a column is given the same type, so detect it from all of the rows
+        let column_types = (0..headers.len())
+            .map(|i| ColumnType::detect(records.iter().filter_map(|record| record.get(i))))
+            .collect::<Vec<_>>();
+
+        let mut features = Vec::with_capacity(records.len());
+        for record in &records {
+            let geometry = match geometry_column_indices {
+                GeometryColumnIndices::Coordinates(x, y) => {
+                    match (record.get(x).map(str::trim), record.get(y).map(str::trim)) {
+                        (Some(x), Some(y)) if !x.is_empty() && !y.is_empty() => {
+                            let point = geo::Point::new(parse_coordinate(x)?, parse_coordinate(y)?);
+                            Some(point.into())
+                        }
+                        _ => None,
+                    }
+                }
+                GeometryColumnIndices::Geometry(i) => match record.get(i) {
+                    Some(value) => parse_geometry(value)?,
+                    None => None,
+                },
+            };
+
+            let properties = headers
+                .iter()
+                .zip(record.iter())
+                .zip(&column_types)
+                .enumerate()
+                .filter(|(i, _)| geometry_column_indices != GeometryColumnIndices::Geometry(*i))
+                .map(|(_, ((header, value), column_type))| {
+                    (header.clone(), column_type.parse(value))
+                })
+                .collect();
+
+            let mut builder = geo_features::FeatureBuilder::new().with_properties(properties);
+            if let Some(geometry) = geometry {
+                builder = builder.with_geometry(geometry);
+            }
+            features.push(builder.build());
+        }
+
+        if features.iter().all(|feature| feature.geometry.is_none()) {
+            return Err(crate::Error::NoGeometry);
+        }
+        Ok(geo_features::FeatureCollection::from_features(features).into())
+    }
+}
+
+#[derive(PartialEq, Eq)]
+enum GeometryColumnIndices {
+    Coordinates(usize, usize),
+    Geometry(usize),
+}
+
+#[derive(Clone, Copy)]
+enum ColumnType {
+    Number,
+    Boolean,
+    String,
+}
+
+impl ColumnType {
+    fn detect<'a>(values: impl Iterator<Item = &'a str>) -> Self {
+        let (mut is_number, mut is_boolean) = (true, true);
+        for value in values.map(str::trim).filter(|value| !value.is_empty()) {
+            is_number &= value.parse::<f64>().is_ok();
+            is_boolean &= parse_boolean(value).is_some();
+        }
+        if is_number {
+            ColumnType::Number
+        } else if is_boolean {
+            ColumnType::Boolean
+        } else {
+            ColumnType::String
+        }
+    }
+
+    fn parse(self, value: &str) -> geo_features::Value {
+        let trimmed = value.trim();
+        if trimmed.is_empty() {
+            return geo_features::Value::Null;
+        }
+        match self {
+            ColumnType::Number => trimmed
+                .parse()
+                .map(geo_features::Value::Number)
+                .unwrap_or(geo_features::Value::Null),
+            ColumnType::Boolean => parse_boolean(trimmed)
+                .map(geo_features::Value::Boolean)
+                .unwrap_or(geo_features::Value::Null),
+            ColumnType::String => geo_features::Value::String(value.to_owned()),
+        }
+    }
+}
+
+fn parse_boolean(value: &str) -> Option<bool> {
+    if value.eq_ignore_ascii_case("true") {
+        Some(true)
+    } else if value.eq_ignore_ascii_case("false") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn parse_coordinate(value: &str) -> Result<f64, crate::Error> {
+    value
+        .parse()
+        .map_err(|_| crate::Error::InvalidCsvGeometry(value.to_owned()))
+}
+
+/// Parse WKT, EWKT, or hex encoded WKB/EWKB. Empty values have no geometry.
+fn parse_geometry(value: &str) -> Result<Option<geo::Geometry>, crate::Error> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let geometry = if value.bytes().all(|b| b.is_ascii_hexdigit()) {
+        // EWKB is a superset of WKB, so this handles both
+        geozero::wkb::Ewkb(hex::decode(value)?).to_geo()?
+    } else {
+        // Drop the EWKT SRID prefix, e.g. `SRID=4326;POINT(1 2)`
+        let wkt = match value.split_once(';') {
+            Some((srid, wkt)) if srid.trim().to_ascii_uppercase().starts_with("SRID=") => wkt,
+            _ => value,
+        };
+        geozero::wkt::WktStr(wkt).to_geo()?
+    };
+    Ok(Some(geometry))
+}
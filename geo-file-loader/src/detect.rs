@@ -0,0 +1,249 @@
+use crate::FileFormat;
+use std::io;
+
+const SHAPEFILE_MAGIC: &[u8] = &[0x00, 0x00, 0x27, 0x0A];
+const SQLITE_MAGIC: &[u8] = b"SQLite format 3\0";
+const FLATGEOBUF_MAGIC: &[u8] = b"fgb";
+const PARQUET_MAGIC: &[u8] = b"PAR1";
+/// Little and big endian TIFF, and little endian BigTIFF
+const TIFF_MAGICS: &[&[u8]] = &[b"II*\0", b"MM\0*", b"II+\0"];
+/// The first blob header of an OSM PBF file, after its 4 byte length
+const OSM_PBF_MAGIC: &[u8] = b"\x0a\x09OSMHeader";
+/// Starts every record of an RFC 8142 GeoJSON text sequence
+const RECORD_SEPARATOR: u8 = 0x1E;
+const WKT_GEOMETRY_TYPES: &[&str] = &[
+    "POINT",
+    "LINESTRING",
+    "POLYGON",
+    "MULTIPOINT",
+    "MULTILINESTRING",
+    "MULTIPOLYGON",
+    "GEOMETRYCOLLECTION",
+];
+/// How much of an XML file is searched for the GML namespace
+const GML_NAMESPACE_SEARCH_LENGTH: usize = 4096;
+/// How much of a JSON file is searched for its `type`, so large files aren't parsed to detect them
+const JSON_TYPE_SEARCH_LENGTH: usize = 64 * 1024;
+/// The sidecar files of a Shapefile, only loaded along with its `.shp`. Their contents can look
+/// like other formats, e.g. a `.cpg` containing `1252` like hex encoded WKB.
+const SHAPEFILE_COMPANION_EXTENSIONS: &[&str] = &["cpg", "dbf", "prj", "shx", "qix"];
+
+/// Guess the format of a file from its contents, falling back to its file extension.
+pub fn detect_format(file_name: &str, bytes: &[u8]) -> Option<FileFormat> {
+    if is_shapefile_companion(file_name) {
+        return None;
+    }
+    detect_binary_format(bytes)
+        .or_else(|| detect_text_format(bytes))
+        .or_else(|| detect_format_from_extension(file_name))
+        .or_else(|| detect_geometry_text_format(bytes))
+}
+
+fn detect_binary_format(bytes: &[u8]) -> Option<FileFormat> {
+    if bytes.starts_with(SHAPEFILE_MAGIC) {
+        Some(FileFormat::Shapefile)
+    } else if bytes.starts_with(SQLITE_MAGIC) {
+        Some(FileFormat::GeoPackage)
+    } else if bytes.starts_with(FLATGEOBUF_MAGIC) {
+        Some(FileFormat::FlatGeobuf)
+    } else if bytes.starts_with(PARQUET_MAGIC) {
+        Some(FileFormat::GeoParquet)
+    } else if TIFF_MAGICS.iter().any(|magic| bytes.starts_with(magic)) {
+        Some(FileFormat::GeoTiff)
+    } else if bytes
+        .get(4..)
+        .is_some_and(|bytes| bytes.starts_with(OSM_PBF_MAGIC))
+    {
+        Some(FileFormat::Osm)
+    } else if crate::is_zip(bytes) {
+        detect_zip_format(bytes)
+    } else {
+        None
+    }
+}
+
+/// Zipped Shapefiles and KMZ files are told apart by the files in the archive.
+fn detect_zip_format(bytes: &[u8]) -> Option<FileFormat> {
+    let archive = zip::ZipArchive::new(io::Cursor::new(bytes)).ok()?;
+    let has_extension = |extension: &str| {
+        archive
+            .file_names()
+            .any(|name| name.to_ascii_lowercase().ends_with(extension))
+    };
+    if has_extension(".shp") {
+        Some(FileFormat::Shapefile)
+    } else if has_extension(".kml") {
+        Some(FileFormat::Kml)
+    } else {
+        None
+    }
+}
+
+fn detect_text_format(bytes: &[u8]) -> Option<FileFormat> {
+    let text = trim_start(bytes);
+    match text.first()? {
+        b'<' => detect_xml_format(text),
+        b'{' => detect_json_format(text),
+        RECORD_SEPARATOR => Some(FileFormat::GeoJsonSeq),
+        _ => None,
+    }
+}
+
+/// Use the root element, or the GML namespace for GML documents, whose root element can be
+/// anything, e.g. a WFS `FeatureCollection`.
+fn detect_xml_format(bytes: &[u8]) -> Option<FileFormat> {
+    use quick_xml::events::Event;
+
+    let mut reader = quick_xml::Reader::from_reader(bytes);
+    let root = loop {
+        match reader.read_event().ok()? {
+            Event::Start(start) | Event::Empty(start) => break start,
+            Event::Eof => return None,
+            _ => (),
+        }
+    };
+    match root.local_name().as_ref() {
+        b"gpx" => Some(FileFormat::Gpx),
+        b"kml" => Some(FileFormat::Kml),
+        b"osm" => Some(FileFormat::Osm),
+        _ => {
+            let head = bytes.get(..GML_NAMESPACE_SEARCH_LENGTH).unwrap_or(bytes);
+            head.windows(b"opengis.net/gml".len())
+                .any(|window| window == b"opengis.net/gml")
+                .then_some(FileFormat::Gml)
+        }
+    }
+}
+
+fn detect_json_format(bytes: &[u8]) -> Option<FileFormat> {
+    let head = bytes.get(..JSON_TYPE_SEARCH_LENGTH).unwrap_or(bytes);
+    let (json_type, rest) = scan_json_type(head)?;
+    match std::str::from_utf8(json_type).ok()? {
+        "Topology" => Some(FileFormat::TopoJson),
+        "FeatureCollection" | "Feature" | "Point" | "MultiPoint" | "LineString"
+        | "MultiLineString" | "Polygon" | "MultiPolygon" | "GeometryCollection" => {
+            // Newline delimited GeoJSON has more than one value
+            if rest.is_some_and(|rest| trim_start(rest).first() == Some(&b'{')) {
+                Some(FileFormat::GeoJsonSeq)
+            } else {
+                Some(FileFormat::GeoJson)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Find the `type` member of the JSON object at the start of `bytes` without parsing the whole
+/// object, along with what follows the object if it ends within `bytes`.
+fn scan_json_type(bytes: &[u8]) -> Option<(&[u8], Option<&[u8]>)> {
+    let mut json_type = None;
+    let mut depth = 0usize;
+    // Whether the next string in the object is a member's value rather than its name
+    let mut in_value = false;
+    let mut name_is_type = false;
+    let mut i = 0;
+    while let Some(byte) = bytes.get(i) {
+        match byte {
+            b'"' => {
+                let Some(end) = string_end(bytes, i + 1) else {
+                    break;
+                };
+                let string = bytes.get(i + 1..end)?;
+                if depth == 1 && !in_value {
+                    name_is_type = string == b"type";
+                } else if depth == 1 && name_is_type && json_type.is_none() {
+                    json_type = Some(string);
+                }
+                i = end;
+            }
+            b':' if depth == 1 => in_value = true,
+            b',' if depth == 1 => in_value = false,
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some((json_type?, bytes.get(i + 1..)));
+                }
+            }
+            _ => (),
+        }
+        i += 1;
+    }
+    Some((json_type?, None))
+}
+
+/// The index of the quote ending the JSON string starting at `start`
+fn string_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut escaped = false;
+    for (i, byte) in bytes.iter().enumerate().skip(start) {
+        match byte {
+            _ if escaped => escaped = false,
+            b'\\' => escaped = true,
+            b'"' => return Some(i),
+            _ => (),
+        }
+    }
+    None
+}
+
+fn is_shapefile_companion(file_name: &str) -> bool {
+    file_name.rsplit_once('.').is_some_and(|(_, extension)| {
+        SHAPEFILE_COMPANION_EXTENSIONS
+            .iter()
+            .any(|companion| extension.eq_ignore_ascii_case(companion))
+    })
+}
+
+fn detect_format_from_extension(file_name: &str) -> Option<FileFormat> {
+    let (_, extension) = file_name.rsplit_once('.')?;
+    match extension.to_ascii_lowercase().as_str() {
+        "csv" => Some(FileFormat::Csv),
+        "fgb" => Some(FileFormat::FlatGeobuf),
+        "geojson" | "json" => Some(FileFormat::GeoJson),
+        "geojsonl" | "geojsons" | "geojsonseq" | "ndjson" | "jsonl" => Some(FileFormat::GeoJsonSeq),
+        "gpkg" => Some(FileFormat::GeoPackage),
+        "parquet" | "geoparquet" => Some(FileFormat::GeoParquet),
+        "tif" | "tiff" => Some(FileFormat::GeoTiff),
+        "gml" => Some(FileFormat::Gml),
+        "shp" => Some(FileFormat::Shapefile),
+        "topojson" => Some(FileFormat::TopoJson),
+        "wkb" => Some(FileFormat::Wkb),
+        "wkt" => Some(FileFormat::Wkt),
+        "gpx" => Some(FileFormat::Gpx),
+        "kml" | "kmz" => Some(FileFormat::Kml),
+        // OSM PBF files are recognized by their contents
+        "mvt" | "pbf" => Some(FileFormat::Mvt),
+        "osm" => Some(FileFormat::Osm),
+        _ => None,
+    }
+}
+
+/// WKT and hex encoded WKB, e.g. pasted from a database query result
+fn detect_geometry_text_format(bytes: &[u8]) -> Option<FileFormat> {
+    let text = trim_start(bytes);
+    let starts_with_wkt_type = |geometry_type: &&str| {
+        text.get(..geometry_type.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(geometry_type.as_bytes()))
+    };
+    if WKT_GEOMETRY_TYPES.iter().any(starts_with_wkt_type) {
+        Some(FileFormat::Wkt)
+    } else if !text.is_empty()
+        && text
+            .iter()
+            .all(|b| b.is_ascii_hexdigit() || b.is_ascii_whitespace())
+    {
+        Some(FileFormat::Wkb)
+    } else {
+        None
+    }
+}
+
+/// Skip leading whitespace and a UTF-8 byte order mark
+fn trim_start(bytes: &[u8]) -> &[u8] {
+    let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+    let start = bytes
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    bytes.get(start..).unwrap_or_default()
+}
@@ -0,0 +1,59 @@
+use std::io;
+
+pub struct FlatGeobufSource {
+    pub bytes: bytes::Bytes,
+    /// Only load the features intersecting this rectangle
+    pub bbox: Option<crate::Bbox>,
+    /// The CRS of the file if its header doesn't declare one, needed to reproject `bbox`
+    pub default_crs: Option<transform::Crs>,
+}
+
+impl crate::FileLoader for FlatGeobufSource {
+    fn from_bytes(bytes: bytes::Bytes) -> Self {
+        FlatGeobufSource {
+            bytes,
+            bbox: None,
+            default_crs: None,
+        }
+    }
+
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let reader = flatgeobuf::FgbReader::open(io::Cursor::new(&self.bytes))?;
+        let crs = reader.header().crs().and_then(|crs| {
+            // Prefer the EPSG code over the WKT when the file has one
+            match (crs.org(), u16::try_from(crs.code())) {
+                (None | Some("EPSG"), Ok(code)) if code != 0 => {
+                    Some(crate::DetectedCrs::EpsgCode(code))
+                }
+                _ => crs.wkt().map(|wkt| crate::DetectedCrs::Wkt(wkt.to_owned())),
+            }
+        });
+        // Files without a spatial index can't be queried by bounding box, so load everything
+        let has_index = reader.header().index_node_size() > 0;
+        // The header's CRS takes precedence over the default one, like when the layer is added
+        let file_crs = crs
+            .as_ref()
+            .map(crate::DetectedCrs::to_crs)
+            .or(self.default_crs);
+        let bbox = match (self.bbox, file_crs) {
+            (Some(bbox), Some(file_crs)) if has_index => bbox.in_crs(&file_crs)?,
+            _ => None,
+        };
+        let mut features = match bbox {
+            Some(bbox) => {
+                reader.select_bbox(bbox.min().x, bbox.min().y, bbox.max().x, bbox.max().y)?
+            }
+            _ => reader.select_all()?,
+        };
+
+        let mut processor = crate::processor::FeatureCollectionProcessor::new();
+        features.process_features(&mut processor)?;
+
+        Ok(crate::LoadedLayer {
+            name: None,
+            feature_collection: processor.into_feature_collection()?,
+            crs,
+        }
+        .into())
+    }
+}
@@ -1,6 +1,4 @@
-use std::io;
-
-use geozero::GeozeroDatasource;
+use std::str;
 
 pub struct GeoJsonSource {
     pub bytes: bytes::Bytes,
@@ -11,12 +9,71 @@ impl crate::FileLoader for GeoJsonSource {
         GeoJsonSource { bytes }
     }
 
-    fn load(self) -> Result<geo_features::FeatureCollection, crate::Error> {
-        let bytes_cursor = io::Cursor::new(&self.bytes);
-        let mut geojson_reader = geozero::geojson::GeoJsonReader(bytes_cursor);
-        let mut geo_writer = geozero::geo_types::GeoWriter::new();
-        geojson_reader.process(&mut geo_writer)?;
-        let geometry = geo_writer.take_geometry().ok_or(crate::Error::NoGeometry)?;
-        Ok(geo_features::FeatureCollection::from_geometry(geometry))
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let geojson = str::from_utf8(&self.bytes)?.parse::<geojson::GeoJson>()?;
+        let features = match geojson {
+            geojson::GeoJson::FeatureCollection(feature_collection) => feature_collection
+                .features
+                .into_iter()
+                .map(feature_from_geojson)
+                .collect::<Result<Vec<_>, _>>()?,
+            geojson::GeoJson::Feature(feature) => vec![feature_from_geojson(feature)?],
+            geojson::GeoJson::Geometry(geometry) => {
+                vec![geo_features::FeatureBuilder::new()
+                    .with_geometry(geometry.value.try_into()?)
+                    .build()]
+            }
+        };
+        if features.iter().all(|feature| feature.geometry.is_none()) {
+            return Err(crate::Error::NoGeometry);
+        }
+        Ok(geo_features::FeatureCollection::from_features(features).into())
+    }
+}
+
+pub(crate) fn feature_from_geojson(
+    feature: geojson::Feature,
+) -> Result<geo_features::Feature, crate::Error> {
+    let mut properties = feature
+        .properties
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(key, value)| (key, json_value_to_value(value)))
+        .collect::<geo_features::Properties>();
+
+    // GeoJSON keeps the feature identifier outside of `properties`. Surface it as the `@id`
+    // property so it isn't lost, without shadowing an `id` property of the feature's own.
+    if let Some(id) = feature.id {
+        let id = match id {
+            geojson::feature::Id::String(s) => geo_features::Value::String(s),
+            geojson::feature::Id::Number(n) => json_number_to_value(&n),
+        };
+        properties.entry("@id".into()).or_insert(id);
+    }
+
+    let mut builder = geo_features::FeatureBuilder::new().with_properties(properties);
+    if let Some(geometry) = feature.geometry {
+        builder = builder.with_geometry(geometry.value.try_into()?);
+    }
+    Ok(builder.build())
+}
+
+pub(crate) fn json_value_to_value(value: serde_json::Value) -> geo_features::Value {
+    match value {
+        serde_json::Value::Null => geo_features::Value::Null,
+        serde_json::Value::Bool(b) => geo_features::Value::Boolean(b),
+        serde_json::Value::Number(n) => json_number_to_value(&n),
+        serde_json::Value::String(s) => geo_features::Value::String(s),
+        // Nested values don't have an equivalent in `geo_features::Value`, so keep the raw JSON
+        value @ (serde_json::Value::Array(_) | serde_json::Value::Object(_)) => {
+            geo_features::Value::String(value.to_string())
+        }
+    }
+}
+
+fn json_number_to_value(number: &serde_json::Number) -> geo_features::Value {
+    match number.as_f64() {
+        Some(n) => geo_features::Value::Number(n),
+        None => geo_features::Value::String(number.to_string()),
     }
 }
@@ -0,0 +1,110 @@
+/// Record separator that starts every record of an RFC 8142 GeoJSON text sequence
+const RECORD_SEPARATOR: u8 = 0x1E;
+
+/// Newline delimited GeoJSON (`.geojsonl`, `.ndjson`) or an RFC 8142 GeoJSON text sequence, with
+/// one feature or geometry per record.
+pub struct GeoJsonSeqSource {
+    pub bytes: bytes::Bytes,
+}
+
+impl crate::FileLoader for GeoJsonSeqSource {
+    fn from_bytes(bytes: bytes::Bytes) -> Self {
+        GeoJsonSeqSource { bytes }
+    }
+
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let mut reader = GeoJsonSeqReader::new(self.bytes);
+        let mut features = vec![];
+        while let Some(feature) = reader.read_feature() {
+            features.push(feature?);
+        }
+        reader.finish(features)
+    }
+}
+
+/// Reads the features of a GeoJSON sequence one record at a time, so progress can be reported
+/// while loading large files.
+pub struct GeoJsonSeqReader {
+    bytes: bytes::Bytes,
+    position: usize,
+    /// RFC 8142 records start with a record separator and may span several lines, e.g. when
+    /// pretty-printed. Newline delimited records are a line each.
+    separator: u8,
+}
+
+impl GeoJsonSeqReader {
+    pub fn new(bytes: bytes::Bytes) -> Self {
+        let starts_with_record_separator = bytes
+            .iter()
+            .find(|b| !b.is_ascii_whitespace())
+            .is_some_and(|b| *b == RECORD_SEPARATOR);
+        GeoJsonSeqReader {
+            bytes,
+            position: 0,
+            separator: if starts_with_record_separator {
+                RECORD_SEPARATOR
+            } else {
+                b'\n'
+            },
+        }
+    }
+
+    /// Read the next feature. Returns `None` once all the records have been read.
+    pub fn read_feature(&mut self) -> Option<Result<geo_features::Feature, crate::Error>> {
+        loop {
+            let remaining = self.bytes.get(self.position..)?;
+            if remaining.is_empty() {
+                return None;
+            }
+            // The record runs up to the next separator, after its own leading one
+            let record_length = remaining
+                .iter()
+                .skip(1)
+                .position(|b| *b == self.separator)
+                .map_or(remaining.len(), |i| i + 1);
+            let record = remaining.get(..record_length)?;
+            self.position += record_length;
+
+            let record = record
+                .iter()
+                .position(|b| !b.is_ascii_whitespace() && *b != RECORD_SEPARATOR)
+                .and_then(|start| record.get(start..))
+                .unwrap_or_default();
+            if !record.is_empty() {
+                return Some(parse_record(record));
+            }
+        }
+    }
+
+    /// How much of the file has been read, from 0 to 100
+    pub fn progress_percent(&self) -> u8 {
+        if self.bytes.is_empty() {
+            return 100;
+        }
+        // In u64, as 100 times the position of a large file overflows a 32 bit usize
+        u8::try_from(self.position as u64 * 100 / self.bytes.len() as u64).unwrap_or(100)
+    }
+
+    /// Build the loaded file from the features that were read.
+    pub fn finish(
+        self,
+        features: Vec<geo_features::Feature>,
+    ) -> Result<crate::LoadedFile, crate::Error> {
+        if features.iter().all(|feature| feature.geometry.is_none()) {
+            return Err(crate::Error::NoGeometry);
+        }
+        Ok(geo_features::FeatureCollection::from_features(features).into())
+    }
+}
+
+fn parse_record(record: &[u8]) -> Result<geo_features::Feature, crate::Error> {
+    match serde_json::from_slice::<geojson::GeoJson>(record)? {
+        geojson::GeoJson::Feature(feature) => crate::geojson::feature_from_geojson(feature),
+        geojson::GeoJson::Geometry(geometry) => Ok(geo_features::FeatureBuilder::new()
+            .with_geometry(geometry.value.try_into()?)
+            .build()),
+        geojson::GeoJson::FeatureCollection(_) => Err(crate::Error::InvalidGeoJsonSeq(
+            "records must be features or geometries",
+        )),
+    }
+}
@@ -0,0 +1,142 @@
+use geozero::ToGeo;
+
+pub struct GeoPackageSource {
+    pub bytes: bytes::Bytes,
+    /// Names of the feature tables to load. All feature tables are loaded if `None`.
+    pub tables: Option<Vec<String>>,
+}
+
+impl crate::FileLoader for GeoPackageSource {
+    fn from_bytes(bytes: bytes::Bytes) -> Self {
+        GeoPackageSource {
+            bytes,
+            tables: None,
+        }
+    }
+
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let connection = open(&self.bytes)?;
+        let layers = feature_tables(&connection)?
+            .into_iter()
+            .filter(|table| match self.tables {
+                Some(ref tables) => tables.contains(&table.name),
+                None => true,
+            })
+            .map(|table| load_table(&connection, table))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(crate::LoadedFile { layers })
+    }
+}
+
+/// List the names of the feature tables in a GeoPackage, e.g. to let the user choose which ones to
+/// load.
+pub fn geopackage_feature_tables(bytes: &[u8]) -> Result<Vec<String>, crate::Error> {
+    let connection = open(bytes)?;
+    Ok(feature_tables(&connection)?
+        .into_iter()
+        .map(|table| table.name)
+        .collect())
+}
+
+struct FeatureTable {
+    name: String,
+    geometry_column: String,
+    crs: Option<crate::DetectedCrs>,
+}
+
+fn open(bytes: &[u8]) -> Result<rusqlite::Connection, rusqlite::Error> {
+    let mut connection = rusqlite::Connection::open_in_memory()?;
+    connection.deserialize_read_exact(rusqlite::DatabaseName::Main, bytes, bytes.len(), true)?;
+    Ok(connection)
+}
+
+fn feature_tables(connection: &rusqlite::Connection) -> Result<Vec<FeatureTable>, rusqlite::Error> {
+    let mut statement = connection.prepare(
+        "SELECT c.table_name, g.column_name,
+                s.organization, s.organization_coordsys_id, s.definition
+         FROM gpkg_contents c
+         JOIN gpkg_geometry_columns g ON g.table_name = c.table_name
+         LEFT JOIN gpkg_spatial_ref_sys s ON s.srs_id = c.srs_id
+         WHERE c.data_type = 'features'
+         ORDER BY c.table_name",
+    )?;
+    let tables = statement
+        .query_map([], |row| {
+            Ok(FeatureTable {
+                name: row.get(0)?,
+                geometry_column: row.get(1)?,
+                crs: crs_from_spatial_ref_sys(row.get(2)?, row.get(3)?, row.get(4)?),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(tables)
+}
+
+fn crs_from_spatial_ref_sys(
+    organization: Option<String>,
+    organization_coordsys_id: Option<i64>,
+    definition: Option<String>,
+) -> Option<crate::DetectedCrs> {
+    let epsg_code = organization
+        .filter(|organization| organization.eq_ignore_ascii_case("EPSG"))
+        .and(organization_coordsys_id)
+        .and_then(|code| u16::try_from(code).ok());
+    match epsg_code {
+        Some(epsg_code) => Some(crate::DetectedCrs::EpsgCode(epsg_code)),
+        // The spec's placeholder Cartesian and geographic systems are defined as "undefined"
+        None => definition
+            .filter(|definition| definition != "undefined")
+            .map(crate::DetectedCrs::Wkt),
+    }
+}
+
+fn load_table(
+    connection: &rusqlite::Connection,
+    table: FeatureTable,
+) -> Result<crate::LoadedLayer, crate::Error> {
+    let mut statement = connection.prepare(&format!(
+        "SELECT * FROM \"{}\"",
+        table.name.replace('"', "\"\"")
+    ))?;
+    let column_names = statement
+        .column_names()
+        .into_iter()
+        .map(String::from)
+        .collect::<Vec<_>>();
+
+    let mut features = vec![];
+    let mut rows = statement.query([])?;
+    while let Some(row) = rows.next()? {
+        let mut builder = geo_features::FeatureBuilder::new();
+        let mut properties = geo_features::Properties::default();
+        for (i, column_name) in column_names.iter().enumerate() {
+            let value = row.get_ref(i)?;
+            if *column_name == table.geometry_column {
+                if let rusqlite::types::ValueRef::Blob(blob) = value {
+                    builder = builder.with_geometry(geozero::wkb::GpkgWkb(blob).to_geo()?);
+                }
+            } else {
+                properties.insert(column_name.clone(), value_ref_to_value(value));
+            }
+        }
+        features.push(builder.with_properties(properties).build());
+    }
+
+    Ok(crate::LoadedLayer {
+        name: Some(table.name),
+        feature_collection: geo_features::FeatureCollection::from_features(features),
+        crs: table.crs,
+    })
+}
+
+fn value_ref_to_value(value: rusqlite::types::ValueRef) -> geo_features::Value {
+    match value {
+        rusqlite::types::ValueRef::Null => geo_features::Value::Null,
+        rusqlite::types::ValueRef::Integer(n) => geo_features::Value::Number(n as f64),
+        rusqlite::types::ValueRef::Real(n) => geo_features::Value::Number(n),
+        rusqlite::types::ValueRef::Text(s) => {
+            geo_features::Value::String(String::from_utf8_lossy(s).into_owned())
+        }
+        rusqlite::types::ValueRef::Blob(_) => geo_features::Value::Null,
+    }
+}
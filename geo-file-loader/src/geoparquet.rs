@@ -0,0 +1,183 @@
+use arrow_array::{cast::AsArray, Array};
+use geozero::ToGeo;
+use std::collections;
+
+pub struct GeoParquetSource {
+    pub bytes: bytes::Bytes,
+}
+
+impl crate::FileLoader for GeoParquetSource {
+    fn from_bytes(bytes: bytes::Bytes) -> Self {
+        GeoParquetSource { bytes }
+    }
+
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let reader_builder =
+            parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(self.bytes)?;
+        let metadata = reader_builder
+            .metadata()
+            .file_metadata()
+            .key_value_metadata()
+            .and_then(|key_values| key_values.iter().find(|key_value| key_value.key == "geo"))
+            .and_then(|key_value| key_value.value.as_deref())
+            .ok_or(crate::Error::MissingGeoParquetMetadata)?;
+        let metadata = serde_json::from_str::<GeoMetadata>(metadata)?;
+        let column = metadata
+            .columns
+            .get(&metadata.primary_column)
+            .ok_or(crate::Error::MissingGeoParquetMetadata)?;
+        if !column.encoding.eq_ignore_ascii_case("WKB") {
+            return Err(crate::Error::UnsupportedGeoParquetEncoding(
+                column.encoding.clone(),
+            ));
+        }
+        let crs = column.crs.as_ref().and_then(crs_from_projjson);
+
+        let mut features = vec![];
+        for batch in reader_builder.build()? {
+            let batch = batch?;
+            let geometry_index = batch.schema().index_of(&metadata.primary_column)?;
+            let mut columns = vec![];
+            for (i, field) in batch.schema().fields().iter().enumerate() {
+                if i != geometry_index {
+                    let values = column_values(batch.column(i).as_ref())?;
+                    columns.push((field.name().clone(), values.into_iter()));
+                }
+            }
+
+            for wkb in wkb_values(batch.column(geometry_index).as_ref())? {
+                let properties = columns
+                    .iter_mut()
+                    .map(|(name, values)| {
+                        (
+                            name.clone(),
+                            values.next().unwrap_or(geo_features::Value::Null),
+                        )
+                    })
+                    .collect();
+                let mut builder = geo_features::FeatureBuilder::new().with_properties(properties);
+                if let Some(wkb) = wkb {
+                    builder = builder.with_geometry(geozero::wkb::Wkb(wkb).to_geo()?);
+                }
+                features.push(builder.build());
+            }
+        }
+
+        Ok(crate::LoadedLayer {
+            name: None,
+            feature_collection: geo_features::FeatureCollection::from_features(features),
+            crs,
+        }
+        .into())
+    }
+}
+
+/// The `geo` key of the Parquet file metadata
+#[derive(serde::Deserialize)]
+struct GeoMetadata {
+    primary_column: String,
+    columns: collections::HashMap<String, GeoColumn>,
+}
+
+#[derive(serde::Deserialize)]
+struct GeoColumn {
+    encoding: String,
+    /// PROJJSON. A missing CRS means OGC:CRS84, an explicit `null` means the CRS is unknown.
+    #[serde(default = "crs84")]
+    crs: Option<serde_json::Value>,
+}
+
+fn crs84() -> Option<serde_json::Value> {
+    Some(serde_json::json!({ "id": { "authority": "OGC", "code": "CRS84" } }))
+}
+
+/// CRSs without an EPSG code are kept as PROJJSON
+fn crs_from_projjson(projjson: &serde_json::Value) -> Option<crate::DetectedCrs> {
+    let id = projjson.get("id");
+    let authority = id
+        .and_then(|id| id.get("authority")?.as_str())
+        .unwrap_or_default();
+    let code = id.and_then(|id| id.get("code"));
+    if authority.eq_ignore_ascii_case("EPSG") {
+        let epsg_code = match code {
+            Some(serde_json::Value::Number(code)) => code.as_u64(),
+            Some(serde_json::Value::String(code)) => code.parse().ok(),
+            _ => None,
+        }
+        .and_then(|code| u16::try_from(code).ok());
+        if let Some(epsg_code) = epsg_code {
+            return Some(crate::DetectedCrs::EpsgCode(epsg_code));
+        }
+    } else if authority.eq_ignore_ascii_case("OGC")
+        && code.and_then(serde_json::Value::as_str) == Some("CRS84")
+    {
+        // Same datum and longitude/latitude axis order as rgis uses for EPSG:4326
+        return Some(crate::DetectedCrs::EpsgCode(4326));
+    }
+    // The default CRS84 is only an id, which can't be used as PROJJSON
+    projjson
+        .get("type")
+        .map(|_| crate::DetectedCrs::ProjJson(projjson.to_string()))
+}
+
+fn wkb_values(array: &dyn Array) -> Result<Vec<Option<&[u8]>>, crate::Error> {
+    match array.data_type() {
+        arrow_schema::DataType::Binary => Ok(array.as_binary::<i32>().iter().collect()),
+        arrow_schema::DataType::LargeBinary => Ok(array.as_binary::<i64>().iter().collect()),
+        data_type => Err(crate::Error::UnsupportedGeoParquetEncoding(
+            data_type.to_string(),
+        )),
+    }
+}
+
+fn column_values(array: &dyn Array) -> Result<Vec<geo_features::Value>, arrow_schema::ArrowError> {
+    Ok(match array.data_type() {
+        arrow_schema::DataType::Boolean => array
+            .as_boolean()
+            .iter()
+            .map(|value| value.map_or(geo_features::Value::Null, geo_features::Value::Boolean))
+            .collect(),
+        data_type if data_type.is_numeric() => {
+            arrow_cast::cast(array, &arrow_schema::DataType::Float64)?
+                .as_primitive::<arrow_array::types::Float64Type>()
+                .iter()
+                .map(|value| value.map_or(geo_features::Value::Null, geo_features::Value::Number))
+                .collect()
+        }
+        arrow_schema::DataType::Utf8 => array
+            .as_string::<i32>()
+            .iter()
+            .map(|value| {
+                value.map_or(geo_features::Value::Null, |value| {
+                    geo_features::Value::String(value.to_owned())
+                })
+            })
+            .collect(),
+        arrow_schema::DataType::LargeUtf8 => array
+            .as_string::<i64>()
+            .iter()
+            .map(|value| {
+                value.map_or(geo_features::Value::Null, |value| {
+                    geo_features::Value::String(value.to_owned())
+                })
+            })
+            .collect(),
+        // Dates, lists, structs, etc. don't have an equivalent in `geo_features::Value`, so
+        // display them as text
+        _ => {
+            let formatter = arrow_cast::display::ArrayFormatter::try_new(
+                array,
+                &arrow_cast::display::FormatOptions::default(),
+            )?;
+            (0..array.len())
+                .map(|i| {
+                    if array.is_null(i) {
+                        geo_features::Value::Null
+                    } else {
+                        geo_features::Value::String(formatter.value(i).to_string())
+                    }
+                })
+                .collect()
+        }
+    })
+}
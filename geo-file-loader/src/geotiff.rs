@@ -0,0 +1,362 @@
+use std::io;
+use tiff::{decoder::DecodingResult, tags::Tag};
+
+// GeoTIFF tags
+const MODEL_PIXEL_SCALE_TAG: u16 = 33550;
+const MODEL_TIEPOINT_TAG: u16 = 33922;
+const MODEL_TRANSFORMATION_TAG: u16 = 34264;
+const GEO_KEY_DIRECTORY_TAG: u16 = 34735;
+/// Set by GDAL, the sample value of pixels without data
+const GDAL_NODATA_TAG: u16 = 42113;
+/// The `NewSubfileType` bit of transparency masks
+const SUBFILE_TYPE_MASK: u32 = 4;
+
+// GeoKeys of the GeoKeyDirectory
+const GT_RASTER_TYPE_GEO_KEY: u16 = 1025;
+const GEOGRAPHIC_TYPE_GEO_KEY: u16 = 2048;
+const PROJECTED_CS_TYPE_GEO_KEY: u16 = 3072;
+const RASTER_PIXEL_IS_POINT: u16 = 2;
+const USER_DEFINED: u16 = 32767;
+
+/// A GeoTIFF, or a Cloud Optimized GeoTIFF, of which the overview closest to `max_size` is read
+pub struct GeoTiffSource {
+    pub bytes: bytes::Bytes,
+    /// Neither side of the loaded image is larger than this, e.g. to fit in a GPU texture. Images
+    /// without a small enough overview are downsampled while they're decoded.
+    pub max_size: u32,
+}
+
+impl GeoTiffSource {
+    pub fn load(self) -> Result<Raster, crate::Error> {
+        let mut decoder = tiff::decoder::Decoder::new(io::Cursor::new(&self.bytes))?;
+        let (full_width, full_height) = decoder.dimensions()?;
+
+        // The georeferencing is only stored with the full resolution image
+        let geo_keys = decoder
+            .find_tag(Tag::Unknown(GEO_KEY_DIRECTORY_TAG))?
+            .map(|value| value.into_u16_vec())
+            .transpose()?
+            .unwrap_or_default();
+        let mut geo_transform = read_geo_transform(&mut decoder)?;
+        if geo_key(&geo_keys, GT_RASTER_TYPE_GEO_KEY) == Some(RASTER_PIXEL_IS_POINT) {
+            // The tiepoint is the center of the top left pixel rather than its corner
+            geo_transform.origin =
+                geo_transform.origin - (geo_transform.column + geo_transform.row) * 0.5;
+        }
+        let crs = geo_key(&geo_keys, PROJECTED_CS_TYPE_GEO_KEY)
+            .or_else(|| geo_key(&geo_keys, GEOGRAPHIC_TYPE_GEO_KEY))
+            .filter(|code| *code != USER_DEFINED)
+            .map(crate::DetectedCrs::EpsgCode);
+
+        let nodata = decoder
+            .find_tag(Tag::Unknown(GDAL_NODATA_TAG))?
+            .map(|value| value.into_string())
+            .transpose()?
+            .and_then(|nodata| nodata.trim_end_matches('\0').trim().parse::<f64>().ok());
+
+        let image_index = closest_overview(&mut decoder, self.max_size)?;
+        decoder.seek_to_image(image_index)?;
+        let (overview_width, overview_height) = decoder.dimensions()?;
+        let channels = match decoder.colortype()? {
+            tiff::ColorType::Gray(_) => 1,
+            tiff::ColorType::GrayA(_) => 2,
+            tiff::ColorType::RGB(_) => 3,
+            tiff::ColorType::RGBA(_) => 4,
+            color_type => {
+                return Err(crate::Error::UnsupportedTiffColorType(format!(
+                    "{color_type:?}"
+                )))
+            }
+        };
+        let factor = overview_width
+            .max(overview_height)
+            .div_ceil(self.max_size.max(1))
+            .max(1);
+        let samples = read_downsampled(
+            &mut decoder,
+            (overview_width, overview_height),
+            channels,
+            factor,
+        )?;
+        let column_scale = f64::from(full_width) / f64::from(overview_width) * f64::from(factor);
+        let row_scale = f64::from(full_height) / f64::from(overview_height) * f64::from(factor);
+
+        Ok(Raster {
+            width: overview_width.div_ceil(factor),
+            height: overview_height.div_ceil(factor),
+            rgba: samples.to_rgba(channels, nodata),
+            geo_transform: GeoTransform {
+                column: geo_transform.column * column_scale,
+                row: geo_transform.row * row_scale,
+                ..geo_transform
+            },
+            crs,
+        })
+    }
+}
+
+/// An RGBA image placed on the map
+pub struct Raster {
+    pub width: u32,
+    pub height: u32,
+    /// 8 bit RGBA pixels, row by row from the top left corner
+    pub rgba: Vec<u8>,
+    pub geo_transform: GeoTransform,
+    pub crs: Option<crate::DetectedCrs>,
+}
+
+/// Maps pixel positions to coordinates in the raster's CRS
+#[derive(Clone, Copy, Debug)]
+pub struct GeoTransform {
+    /// The top left corner of the image
+    pub origin: geo::Coord,
+    /// The change in coordinates from one column to the next
+    pub column: geo::Coord,
+    /// The change in coordinates from one row to the next
+    pub row: geo::Coord,
+}
+
+impl GeoTransform {
+    pub fn apply(&self, column: f64, row: f64) -> geo::Coord {
+        self.origin + self.column * column + self.row * row
+    }
+}
+
+/// Read the affine `ModelTransformationTag`, or a `ModelTiepointTag` along with a
+/// `ModelPixelScaleTag`.
+fn read_geo_transform(
+    decoder: &mut tiff::decoder::Decoder<io::Cursor<&bytes::Bytes>>,
+) -> Result<GeoTransform, crate::Error> {
+    let mut f64_tag = |tag| {
+        decoder
+            .find_tag(Tag::Unknown(tag))?
+            .map(|value| value.into_f64_vec())
+            .transpose()
+    };
+    if let Some([m0, m1, _, m3, m4, m5, _, m7, ..]) = f64_tag(MODEL_TRANSFORMATION_TAG)?.as_deref()
+    {
+        return Ok(GeoTransform {
+            origin: geo::coord! { x: *m3, y: *m7 },
+            column: geo::coord! { x: *m0, y: *m4 },
+            row: geo::coord! { x: *m1, y: *m5 },
+        });
+    }
+    let tiepoint = f64_tag(MODEL_TIEPOINT_TAG)?;
+    let pixel_scale = f64_tag(MODEL_PIXEL_SCALE_TAG)?;
+    match (tiepoint.as_deref(), pixel_scale.as_deref()) {
+        (Some([i, j, _, x, y, ..]), Some([scale_x, scale_y, ..])) => Ok(GeoTransform {
+            origin: geo::coord! { x: x - i * scale_x, y: y + j * scale_y },
+            column: geo::coord! { x: *scale_x, y: 0. },
+            // Rows go down, so towards lower y values
+            row: geo::coord! { x: 0., y: -scale_y },
+        }),
+        _ => Err(crate::Error::MissingGeoreferencing),
+    }
+}
+
+/// The value of a GeoKey stored directly in the GeoKeyDirectory. The directory starts with a four
+/// value header, followed by a `(key, location, count, value)` entry per key.
+fn geo_key(directory: &[u16], key: u16) -> Option<u16> {
+    directory
+        .get(4..)?
+        .chunks_exact(4)
+        .find_map(|entry| match entry {
+            [entry_key, 0, _, value] if *entry_key == key => Some(*value),
+            _ => None,
+        })
+}
+
+/// The index of the smallest image, i.e. the full resolution image or one of its overviews, whose
+/// larger side is at least `max_size`, or of the full resolution image if it's smaller than that.
+/// Transparency masks are skipped.
+fn closest_overview(
+    decoder: &mut tiff::decoder::Decoder<io::Cursor<&bytes::Bytes>>,
+    max_size: u32,
+) -> Result<usize, crate::Error> {
+    let mut closest = (0, u32::MAX);
+    let mut index = 0;
+    loop {
+        let size = {
+            let (width, height) = decoder.dimensions()?;
+            width.max(height)
+        };
+        let subfile_type = decoder
+            .find_tag(Tag::NewSubfileType)?
+            .map(|value| value.into_u32())
+            .transpose()?
+            .unwrap_or(0);
+        let is_mask = subfile_type & SUBFILE_TYPE_MASK != 0;
+        if !is_mask && size >= max_size && size < closest.1 {
+            closest = (index, size);
+        }
+        if !decoder.more_images() {
+            break;
+        }
+        decoder.next_image()?;
+        index += 1;
+    }
+    Ok(closest.0)
+}
+
+/// Read every `factor`th column of every `factor`th row of the current image. Only the strips or
+/// tiles holding such pixels are decoded, so the full image is never held in memory.
+fn read_downsampled(
+    decoder: &mut tiff::decoder::Decoder<io::Cursor<&bytes::Bytes>>,
+    (width, height): (u32, u32),
+    channels: usize,
+    factor: u32,
+) -> Result<Samples, crate::Error> {
+    let downsampled_width = width.div_ceil(factor);
+    let len = downsampled_width as usize * height.div_ceil(factor) as usize * channels;
+    let (chunk_width, chunk_height) = decoder.chunk_dimensions();
+    let chunks_across = width.div_ceil(chunk_width.max(1));
+    let chunks_down = height.div_ceil(chunk_height.max(1));
+
+    let mut samples = None;
+    for chunk_index in 0..chunks_across * chunks_down {
+        let left = chunk_index % chunks_across * chunk_width;
+        let top = chunk_index / chunks_across * chunk_height;
+        let (data_width, data_height) = decoder.chunk_dimensions_of(chunk_index);
+        let columns = left.next_multiple_of(factor)..left + data_width;
+        let rows = top.next_multiple_of(factor)..top + data_height;
+        if columns.is_empty() || rows.is_empty() {
+            continue;
+        }
+        let chunk = Samples::from(decoder.read_chunk(chunk_index)?);
+        let samples = samples.get_or_insert_with(|| chunk.empty(len));
+        let pixel_ranges = |from: usize, to: usize| {
+            (
+                from * channels..(from + 1) * channels,
+                to * channels..(to + 1) * channels,
+            )
+        };
+        for row in rows.step_by(factor as usize) {
+            for column in columns.clone().step_by(factor as usize) {
+                let (from, to) = pixel_ranges(
+                    (row - top) as usize * data_width as usize + (column - left) as usize,
+                    (row / factor) as usize * downsampled_width as usize
+                        + (column / factor) as usize,
+                );
+                // All chunks of an image have the same sample type
+                match (&mut *samples, &chunk) {
+                    (Samples::U8(samples), Samples::U8(chunk)) => {
+                        copy_pixel(chunk, from, samples, to)
+                    }
+                    (Samples::F32(samples), Samples::F32(chunk)) => {
+                        copy_pixel(chunk, from, samples, to)
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    Ok(samples.unwrap_or(Samples::U8(vec![0; len])))
+}
+
+fn copy_pixel<T: Copy>(
+    from: &[T],
+    from_range: std::ops::Range<usize>,
+    to: &mut [T],
+    to_range: std::ops::Range<usize>,
+) {
+    if let (Some(from), Some(to)) = (from.get(from_range), to.get_mut(to_range)) {
+        to.copy_from_slice(from);
+    }
+}
+
+/// The samples of an image, pixel by pixel. 8 bit images, e.g. orthophotos, are used as is. Other
+/// images, e.g. elevation models, are stretched between their lowest and highest values.
+enum Samples {
+    U8(Vec<u8>),
+    F32(Vec<f32>),
+}
+
+impl From<DecodingResult> for Samples {
+    fn from(image: DecodingResult) -> Self {
+        match image {
+            DecodingResult::U8(samples) => Samples::U8(samples),
+            DecodingResult::U16(samples) => {
+                Samples::F32(samples.into_iter().map(f32::from).collect())
+            }
+            DecodingResult::U32(samples) => {
+                Samples::F32(samples.into_iter().map(|s| s as f32).collect())
+            }
+            DecodingResult::U64(samples) => {
+                Samples::F32(samples.into_iter().map(|s| s as f32).collect())
+            }
+            DecodingResult::I8(samples) => {
+                Samples::F32(samples.into_iter().map(f32::from).collect())
+            }
+            DecodingResult::I16(samples) => {
+                Samples::F32(samples.into_iter().map(f32::from).collect())
+            }
+            DecodingResult::I32(samples) => {
+                Samples::F32(samples.into_iter().map(|s| s as f32).collect())
+            }
+            DecodingResult::I64(samples) => {
+                Samples::F32(samples.into_iter().map(|s| s as f32).collect())
+            }
+            DecodingResult::F32(samples) => Samples::F32(samples),
+            DecodingResult::F64(samples) => {
+                Samples::F32(samples.into_iter().map(|s| s as f32).collect())
+            }
+        }
+    }
+}
+
+impl Samples {
+    /// Samples of the same type for an image with `len` samples
+    fn empty(&self, len: usize) -> Self {
+        match self {
+            Samples::U8(_) => Samples::U8(vec![0; len]),
+            Samples::F32(_) => Samples::F32(vec![f32::NAN; len]),
+        }
+    }
+
+    fn to_rgba(&self, channels: usize, nodata: Option<f64>) -> Vec<u8> {
+        match self {
+            Samples::U8(samples) => to_rgba(
+                samples,
+                channels,
+                |sample| *sample,
+                |sample| Some(f64::from(*sample)) == nodata,
+            ),
+            Samples::F32(samples) => {
+                let nodata = nodata.map(|nodata| nodata as f32);
+                let is_nodata = |sample: &f32| sample.is_nan() || Some(*sample) == nodata;
+                let (min, max) = samples
+                    .iter()
+                    .filter(|sample| !is_nodata(sample))
+                    .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), sample| {
+                        (min.min(*sample), max.max(*sample))
+                    });
+                let range = if max > min { max - min } else { 1. };
+                let scale = |sample: &f32| (255. * (sample - min) / range).clamp(0., 255.) as u8;
+                to_rgba(samples, channels, scale, is_nodata)
+            }
+        }
+    }
+}
+
+fn to_rgba<T>(
+    samples: &[T],
+    channels: usize,
+    scale: impl Fn(&T) -> u8,
+    is_nodata: impl Fn(&T) -> bool,
+) -> Vec<u8> {
+    let mut rgba = Vec::with_capacity(samples.len() / channels * 4);
+    for pixel in samples.chunks_exact(channels) {
+        if pixel.first().is_some_and(&is_nodata) {
+            rgba.extend([0, 0, 0, 0]);
+            continue;
+        }
+        rgba.extend(match pixel {
+            [gray] => [scale(gray), scale(gray), scale(gray), 255],
+            [gray, alpha] => [scale(gray), scale(gray), scale(gray), scale(alpha)],
+            [red, green, blue] => [scale(red), scale(green), scale(blue), 255],
+            [red, green, blue, alpha, ..] => [scale(red), scale(green), scale(blue), scale(alpha)],
+            [] => [0, 0, 0, 0],
+        });
+    }
+    rgba
+}
@@ -0,0 +1,322 @@
+use crate::xml::XmlElement;
+
+/// A GML document, e.g. a WFS `GetFeature` response.
+pub struct GmlSource {
+    pub bytes: bytes::Bytes,
+}
+
+impl crate::FileLoader for GmlSource {
+    fn from_bytes(bytes: bytes::Bytes) -> Self {
+        GmlSource { bytes }
+    }
+
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let document = crate::xml::parse_xml(&self.bytes)?;
+        // A file is expected to use a single CRS, so use the first one declared
+        let srs = document
+            .find_attribute("srsName")
+            .map(|srs_name| Srs::parse(&srs_name));
+        let axes = Axes {
+            swap: srs.as_ref().is_some_and(|srs| srs.northing_first),
+            dimension: 2,
+        };
+
+        let mut feature_elements = vec![];
+        collect_feature_elements(&document, &mut feature_elements);
+        let features = if feature_elements.is_empty() {
+            // Allow loading a bare geometry, e.g. pasted into the text input
+            let geometry = document
+                .children
+                .iter()
+                .find(|element| is_geometry(&element.name))
+                .ok_or(crate::Error::NoGeometry)?;
+            vec![geo_features::FeatureBuilder::new()
+                .with_geometry(parse_geometry(geometry, axes)?)
+                .build()]
+        } else {
+            feature_elements
+                .into_iter()
+                .map(|element| parse_feature(element, axes))
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        if features.iter().all(|feature| feature.geometry.is_none()) {
+            return Err(crate::Error::NoGeometry);
+        }
+
+        Ok(crate::LoadedLayer {
+            name: None,
+            feature_collection: geo_features::FeatureCollection::from_features(features),
+            crs: srs
+                .and_then(|srs| srs.epsg_code)
+                .map(crate::DetectedCrs::EpsgCode),
+        }
+        .into())
+    }
+}
+
+/// The CRS named by a `srsName` attribute
+struct Srs {
+    epsg_code: Option<u16>,
+    /// GML 3.2 follows the CRS's official axis order, e.g. latitude first for EPSG:4326, when the
+    /// CRS is given as a URN or an `http://www.opengis.net/def/crs/` URI
+    northing_first: bool,
+}
+
+impl Srs {
+    /// Handles `EPSG:4326`, `urn:ogc:def:crs:EPSG::4326`,
+    /// `http://www.opengis.net/def/crs/EPSG/0/4326`, `http://www.opengis.net/gml/srs/epsg.xml#4326`
+    /// and CRS84.
+    fn parse(srs_name: &str) -> Self {
+        if srs_name.to_ascii_uppercase().ends_with("CRS84") {
+            return Srs {
+                epsg_code: Some(4326),
+                northing_first: false,
+            };
+        }
+        let epsg_code = srs_name
+            .rsplit([':', '/', '#'])
+            .next()
+            .and_then(|code| code.parse::<u16>().ok());
+        // The older `EPSG:4326` and `…/epsg.xml#4326` forms are longitude first by convention
+        let uses_official_axis_order =
+            srs_name.starts_with("urn:") || srs_name.contains("opengis.net/def/crs/");
+        Srs {
+            epsg_code,
+            northing_first: uses_official_axis_order
+                && epsg_code.is_some_and(|code| transform::Crs::Epsg(code).is_northing_first()),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Axes {
+    swap: bool,
+    /// Number of values per position, from `srsDimension`
+    dimension: usize,
+}
+
+impl Axes {
+    fn for_element(self, element: &XmlElement) -> Self {
+        match element
+            .attribute("srsDimension")
+            .and_then(|dimension| dimension.parse().ok())
+        {
+            Some(dimension) if dimension >= 2 => Axes { dimension, ..self },
+            _ => self,
+        }
+    }
+
+    fn coord(self, a: f64, b: f64) -> geo::Coord {
+        if self.swap {
+            geo::coord! { x: b, y: a }
+        } else {
+            geo::coord! { x: a, y: b }
+        }
+    }
+}
+
+/// Find the features of `gml:featureMember`, `gml:featureMembers` and WFS 2.0 `wfs:member`
+/// elements.
+fn collect_feature_elements<'a>(element: &'a XmlElement, features: &mut Vec<&'a XmlElement>) {
+    for child in &element.children {
+        match child.name.as_str() {
+            "featureMember" | "featureMembers" | "member" => features.extend(&child.children),
+            _ => collect_feature_elements(child, features),
+        }
+    }
+}
+
+fn is_geometry(name: &str) -> bool {
+    matches!(
+        name,
+        "Point"
+            | "LineString"
+            | "Curve"
+            | "Polygon"
+            | "Surface"
+            | "MultiPoint"
+            | "MultiLineString"
+            | "MultiCurve"
+            | "MultiPolygon"
+            | "MultiSurface"
+            | "MultiGeometry"
+    )
+}
+
+/// Child elements holding a geometry become the feature's geometry, child elements holding text
+/// become properties.
+fn parse_feature(element: &XmlElement, axes: Axes) -> Result<geo_features::Feature, crate::Error> {
+    let mut properties = geo_features::Properties::default();
+    if let Some(id) = element.attribute("id").or_else(|| element.attribute("fid")) {
+        properties.insert("gml_id".into(), geo_features::Value::String(id.to_owned()));
+    }
+    let mut geometry = None;
+    for child in &element.children {
+        if child.name == "boundedBy" {
+            continue;
+        }
+        match child
+            .children
+            .iter()
+            .find(|grandchild| is_geometry(&grandchild.name))
+        {
+            // Only the first geometry property is used
+            Some(geometry_element) => {
+                if geometry.is_none() {
+                    geometry = Some(parse_geometry(geometry_element, axes)?);
+                }
+            }
+            None if child.children.is_empty() => {
+                let text = child.text.trim();
+                properties.insert(
+                    child.name.clone(),
+                    if text.is_empty() {
+                        geo_features::Value::Null
+                    } else {
+                        geo_features::Value::String(text.to_owned())
+                    },
+                );
+            }
+            None => (),
+        }
+    }
+
+    let mut builder = geo_features::FeatureBuilder::new().with_properties(properties);
+    if let Some(geometry) = geometry {
+        builder = builder.with_geometry(geometry);
+    }
+    Ok(builder.build())
+}
+
+fn parse_geometry(element: &XmlElement, axes: Axes) -> Result<geo::Geometry, crate::Error> {
+    let axes = axes.for_element(element);
+    Ok(match element.name.as_str() {
+        "Point" => geo::Point::from(parse_position(element, axes)?).into(),
+        "LineString" | "Curve" => parse_line_string(element, axes)?.into(),
+        "Polygon" | "Surface" => parse_polygon(element, axes)?.into(),
+        "MultiPoint" => geo::MultiPoint::new(
+            member_geometries(element)
+                .map(|point| Ok(geo::Point::from(parse_position(point, axes)?)))
+                .collect::<Result<_, crate::Error>>()?,
+        )
+        .into(),
+        "MultiLineString" | "MultiCurve" => geo::MultiLineString::new(
+            member_geometries(element)
+                .map(|line_string| parse_line_string(line_string, axes.for_element(line_string)))
+                .collect::<Result<_, _>>()?,
+        )
+        .into(),
+        "MultiPolygon" | "MultiSurface" => geo::MultiPolygon::new(
+            member_geometries(element)
+                .map(|polygon| parse_polygon(polygon, axes.for_element(polygon)))
+                .collect::<Result<_, _>>()?,
+        )
+        .into(),
+        "MultiGeometry" => geo::GeometryCollection::new_from(
+            member_geometries(element)
+                .map(|geometry| parse_geometry(geometry, axes))
+                .collect::<Result<_, _>>()?,
+        )
+        .into(),
+        _ => return Err(crate::Error::InvalidGml("unsupported geometry type")),
+    })
+}
+
+/// The geometries of a multi geometry's member elements, e.g. `gml:surfaceMember` or
+/// `gml:surfaceMembers`
+fn member_geometries(element: &XmlElement) -> impl Iterator<Item = &XmlElement> {
+    element
+        .children
+        .iter()
+        .flat_map(|member| &member.children)
+        .filter(|geometry| is_geometry(&geometry.name))
+}
+
+fn parse_position(element: &XmlElement, axes: Axes) -> Result<geo::Coord, crate::Error> {
+    parse_coords(element, axes)?
+        .into_iter()
+        .next()
+        .ok_or(crate::Error::InvalidGml("point without a position"))
+}
+
+fn parse_line_string(element: &XmlElement, axes: Axes) -> Result<geo::LineString, crate::Error> {
+    Ok(geo::LineString::new(parse_coords(element, axes)?))
+}
+
+/// Handles GML 3 `exterior`/`interior` and GML 2 `outerBoundaryIs`/`innerBoundaryIs` rings, as well
+/// as surfaces made of polygon patches.
+fn parse_polygon(element: &XmlElement, axes: Axes) -> Result<geo::Polygon, crate::Error> {
+    if let Some(patch) = element
+        .child("patches")
+        .and_then(|patches| patches.child("PolygonPatch"))
+    {
+        return parse_polygon(patch, axes);
+    }
+    let mut exterior = geo::LineString::new(vec![]);
+    let mut interiors = vec![];
+    for boundary in &element.children {
+        match boundary.name.as_str() {
+            "exterior" | "outerBoundaryIs" => exterior = parse_line_string(boundary, axes)?,
+            "interior" | "innerBoundaryIs" => interiors.push(parse_line_string(boundary, axes)?),
+            _ => (),
+        }
+    }
+    Ok(geo::Polygon::new(exterior, interiors))
+}
+
+/// Read the positions of all the `gml:pos`, `gml:posList` and GML 2 `gml:coordinates` elements
+/// within `element`, in document order.
+fn parse_coords(element: &XmlElement, axes: Axes) -> Result<Vec<geo::Coord>, crate::Error> {
+    let mut coords = vec![];
+    collect_coords(element, axes, &mut coords)?;
+    Ok(coords)
+}
+
+fn collect_coords(
+    element: &XmlElement,
+    axes: Axes,
+    coords: &mut Vec<geo::Coord>,
+) -> Result<(), crate::Error> {
+    let axes = axes.for_element(element);
+    match element.name.as_str() {
+        "pos" | "posList" => {
+            let values = parse_numbers(element.text.split_ascii_whitespace())?;
+            for position in values.chunks(axes.dimension) {
+                if let [a, b, ..] = position {
+                    coords.push(axes.coord(*a, *b));
+                }
+            }
+        }
+        "coordinates" => {
+            let decimal = element.attribute("decimal").unwrap_or(".");
+            let cs = element.attribute("cs").unwrap_or(",");
+            let ts = element.attribute("ts").unwrap_or(" ");
+            let text = element.text.trim().replace(decimal, ".");
+            for tuple in text
+                .split(|c: char| ts.contains(c) || c.is_ascii_whitespace())
+                .filter(|tuple| !tuple.is_empty())
+            {
+                if let [a, b, ..] = parse_numbers(tuple.split(cs))?.as_slice() {
+                    coords.push(axes.coord(*a, *b));
+                }
+            }
+        }
+        _ => {
+            for child in &element.children {
+                collect_coords(child, axes, coords)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_numbers<'a>(values: impl Iterator<Item = &'a str>) -> Result<Vec<f64>, crate::Error> {
+    values
+        .map(|value| {
+            value
+                .trim()
+                .parse()
+                .map_err(|_| crate::Error::InvalidGml("invalid coordinate"))
+        })
+        .collect()
+}
@@ -1,9 +1,22 @@
-use std::io;
+use crate::xml::XmlElement;
 
-use geozero::GeozeroDatasource;
-
-// TOOD: create generic file loader for geozero
+/// Child elements whose values are numbers. Other child elements, like `time` or `name`, are kept
+/// as text.
+const NUMERIC_ELEMENTS: &[&str] = &[
+    "ele",
+    "magvar",
+    "geoidheight",
+    "sat",
+    "hdop",
+    "vdop",
+    "pdop",
+    "ageofdgpsdata",
+    "dgpsid",
+    "number",
+];
 
+/// A GPX file. Waypoints, routes, tracks and the points of the tracks are loaded as separate
+/// layers.
 pub struct GpxSource {
     pub bytes: bytes::Bytes,
 }
@@ -13,12 +26,184 @@ impl crate::FileLoader for GpxSource {
         GpxSource { bytes }
     }
 
-    fn load(self) -> Result<geo_features::FeatureCollection, crate::Error> {
-        let bytes_cursor = io::Cursor::new(&self.bytes);
-        let mut gpx_reader = geozero::gpx::GpxReader(bytes_cursor);
-        let mut geo_writer = geozero::geo_types::GeoWriter::new();
-        gpx_reader.process(&mut geo_writer)?;
-        let geometry = geo_writer.take_geometry().ok_or(crate::Error::NoGeometry)?;
-        Ok(geo_features::FeatureCollection::from_geometry(geometry))
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let document = crate::xml::parse_xml(&self.bytes)?;
+        let gpx = document
+            .child("gpx")
+            .ok_or(crate::Error::InvalidGpx("missing gpx element"))?;
+
+        let (mut waypoints, mut routes, mut tracks, mut track_points) =
+            (vec![], vec![], vec![], vec![]);
+        for element in &gpx.children {
+            match element.name.as_str() {
+                "wpt" => waypoints.push(
+                    geo_features::FeatureBuilder::new()
+                        .with_properties(properties(element))
+                        .with_geometry(geo::Point::from(read_coord(element)?).into())
+                        .build(),
+                ),
+                "rte" => {
+                    let coords = element
+                        .children
+                        .iter()
+                        .filter(|child| child.name == "rtept")
+                        .map(read_coord)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    routes.push(
+                        geo_features::FeatureBuilder::new()
+                            .with_properties(properties(element))
+                            .with_geometry(geo::LineString::new(coords).into())
+                            .build(),
+                    );
+                }
+                "trk" => {
+                    let track_number = tracks.len();
+                    tracks.push(read_track(element, track_number, &mut track_points)?);
+                }
+                _ => (),
+            }
+        }
+
+        let layers = [
+            ("Waypoints", waypoints),
+            ("Routes", routes),
+            ("Tracks", tracks),
+            ("Track points", track_points),
+        ]
+        .into_iter()
+        .filter(|(_, features)| !features.is_empty())
+        .map(|(name, features)| crate::LoadedLayer {
+            name: Some(name.to_owned()),
+            feature_collection: geo_features::FeatureCollection::from_features(features),
+            // GPX coordinates are always WGS 84 longitudes and latitudes
+            crs: Some(crate::DetectedCrs::EpsgCode(4326)),
+        })
+        .collect::<Vec<_>>();
+        if layers.is_empty() {
+            return Err(crate::Error::NoGeometry);
+        }
+        Ok(crate::LoadedFile { layers })
+    }
+}
+
+/// Read a track as a multi line string with one line per segment. Each of the track's points is
+/// added to `track_points` along with its elevation, time and extensions, and the track and
+/// segment it belongs to.
+fn read_track(
+    track: &XmlElement,
+    track_number: usize,
+    track_points: &mut Vec<geo_features::Feature>,
+) -> Result<geo_features::Feature, crate::Error> {
+    let track_name = track.child_text("name");
+    let mut segments = vec![];
+    let mut times = vec![];
+    for (segment_number, segment) in track
+        .children
+        .iter()
+        .filter(|child| child.name == "trkseg")
+        .enumerate()
+    {
+        let mut coords = vec![];
+        for point in segment
+            .children
+            .iter()
+            .filter(|child| child.name == "trkpt")
+        {
+            let coord = read_coord(point)?;
+            coords.push(coord);
+            times.extend(point.child_text("time").map(String::from));
+
+            let mut properties = properties(point);
+            properties.insert("track".into(), number_value(track_number));
+            if let Some(track_name) = track_name {
+                properties.insert(
+                    "track_name".into(),
+                    geo_features::Value::String(track_name.to_owned()),
+                );
+            }
+            properties.insert("segment".into(), number_value(segment_number));
+            track_points.push(
+                geo_features::FeatureBuilder::new()
+                    .with_properties(properties)
+                    .with_geometry(geo::Point::from(coord).into())
+                    .build(),
+            );
+        }
+        segments.push(geo::LineString::new(coords));
     }
+
+    let mut properties = properties(track);
+    properties.insert("track".into(), number_value(track_number));
+    // GPX times are ISO 8601 in UTC, so they sort chronologically as text
+    if let (Some(start_time), Some(end_time)) = (times.iter().min(), times.iter().max()) {
+        properties.insert(
+            "start_time".into(),
+            geo_features::Value::String(start_time.clone()),
+        );
+        properties.insert(
+            "end_time".into(),
+            geo_features::Value::String(end_time.clone()),
+        );
+    }
+    Ok(geo_features::FeatureBuilder::new()
+        .with_properties(properties)
+        .with_geometry(geo::MultiLineString::new(segments).into())
+        .build())
+}
+
+fn read_coord(element: &XmlElement) -> Result<geo::Coord, crate::Error> {
+    let read = |name| {
+        element
+            .attribute(name)
+            .and_then(|value| value.trim().parse::<f64>().ok())
+            .ok_or(crate::Error::InvalidGpx("invalid or missing coordinate"))
+    };
+    Ok(geo::coord! { x: read("lon")?, y: read("lat")? })
+}
+
+/// The text child elements of a waypoint, route, track or track point, e.g. `name`, `desc` or
+/// `time`, and the elements within `<extensions>`, e.g. a Garmin `<gpxtpx:hr>` heart rate.
+fn properties(element: &XmlElement) -> geo_features::Properties {
+    let mut properties = geo_features::Properties::default();
+    for child in &element.children {
+        match child.name.as_str() {
+            "extensions" => insert_extensions(child, &mut properties),
+            _ if child.children.is_empty() && !child.text.trim().is_empty() => {
+                let text = child.text.trim();
+                let value = match text.parse() {
+                    Ok(number) if NUMERIC_ELEMENTS.contains(&child.name.as_str()) => {
+                        geo_features::Value::Number(number)
+                    }
+                    _ => geo_features::Value::String(text.to_owned()),
+                };
+                properties.insert(child.name.clone(), value);
+            }
+            _ => (),
+        }
+    }
+    properties
+}
+
+/// Extensions are usually nested, e.g. `<gpxtpx:TrackPointExtension><gpxtpx:hr>`, so every leaf
+/// element becomes a property named after the element.
+fn insert_extensions(element: &XmlElement, properties: &mut geo_features::Properties) {
+    for child in &element.children {
+        if !child.children.is_empty() {
+            insert_extensions(child, properties);
+            continue;
+        }
+        let text = child.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let value = match text.parse() {
+            Ok(number) => geo_features::Value::Number(number),
+            Err(_) => geo_features::Value::String(text.to_owned()),
+        };
+        properties.insert(child.name.clone(), value);
+    }
+}
+
+fn number_value(number: usize) -> geo_features::Value {
+    geo_features::Value::Number(number as f64)
 }
@@ -0,0 +1,191 @@
+use std::{io, io::Read, str};
+
+/// A KML file, or a KMZ archive containing one.
+pub struct KmlSource {
+    pub bytes: bytes::Bytes,
+    /// Load each `<Folder>` as its own layer
+    pub split_folders: bool,
+}
+
+impl crate::FileLoader for KmlSource {
+    fn from_bytes(bytes: bytes::Bytes) -> Self {
+        KmlSource {
+            bytes,
+            split_folders: false,
+        }
+    }
+
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let kml = if crate::is_zip(&self.bytes) {
+            read_kmz(&self.bytes)?.parse::<kml::Kml>()?
+        } else {
+            str::from_utf8(&self.bytes)?.parse::<kml::Kml>()?
+        };
+
+        let mut layers = vec![];
+        collect_placemarks(kml, None, self.split_folders, &mut layers)?;
+        if layers
+            .iter()
+            .flat_map(|(_, features)| features)
+            .all(|feature| feature.geometry.is_none())
+        {
+            return Err(crate::Error::NoGeometry);
+        }
+
+        Ok(crate::LoadedFile {
+            layers: layers
+                .into_iter()
+                .map(|(name, features)| crate::LoadedLayer {
+                    name,
+                    feature_collection: geo_features::FeatureCollection::from_features(features),
+                    // KML coordinates are always WGS 84 longitudes and latitudes
+                    crs: Some(crate::DetectedCrs::EpsgCode(4326)),
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Extract the main KML file from a KMZ archive. By convention it's `doc.kml`, otherwise use the
+/// first `.kml` file.
+fn read_kmz(bytes: &bytes::Bytes) -> Result<String, crate::Error> {
+    let mut archive = zip::ZipArchive::new(io::Cursor::new(bytes))?;
+    let kml_file_names = archive
+        .file_names()
+        .filter(|name| name.to_ascii_lowercase().ends_with(".kml"))
+        .map(String::from)
+        .collect::<Vec<_>>();
+    let file_name = kml_file_names
+        .iter()
+        .find(|name| name.eq_ignore_ascii_case("doc.kml"))
+        .or_else(|| kml_file_names.first())
+        .ok_or(crate::Error::MissingKml)?;
+    let mut contents = String::new();
+    archive.by_name(file_name)?.read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+type Layers = Vec<(Option<String>, Vec<geo_features::Feature>)>;
+
+fn collect_placemarks(
+    kml: kml::Kml,
+    folder: Option<&str>,
+    split_folders: bool,
+    layers: &mut Layers,
+) -> Result<(), crate::Error> {
+    match kml {
+        kml::Kml::KmlDocument(kml::KmlDocument { elements, .. })
+        | kml::Kml::Document { elements, .. } => {
+            for element in elements {
+                collect_placemarks(element, folder, split_folders, layers)?;
+            }
+        }
+        kml::Kml::Folder { elements, .. } => {
+            // Nested folders are named after their path, e.g. `Trips/2024`
+            let folder = split_folders.then(|| {
+                let name = folder_name(&elements).unwrap_or("Folder");
+                match folder {
+                    Some(parent) => format!("{parent}/{name}"),
+                    None => name.to_owned(),
+                }
+            });
+            for element in elements {
+                collect_placemarks(element, folder.as_deref(), split_folders, layers)?;
+            }
+        }
+        kml::Kml::Placemark(placemark) => {
+            let feature = feature_from_placemark(placemark)?;
+            match layers
+                .iter_mut()
+                .find(|(name, _)| name.as_deref() == folder)
+            {
+                Some((_, features)) => features.push(feature),
+                None => layers.push((folder.map(String::from), vec![feature])),
+            }
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+fn folder_name(elements: &[kml::Kml]) -> Option<&str> {
+    elements.iter().find_map(|element| match element {
+        kml::Kml::Element(element) if element.name == "name" => element.content.as_deref(),
+        _ => None,
+    })
+}
+
+fn feature_from_placemark(
+    placemark: kml::types::Placemark,
+) -> Result<geo_features::Feature, crate::Error> {
+    let mut properties = geo_features::Properties::default();
+    if let Some(name) = placemark.name {
+        properties.insert("name".into(), geo_features::Value::String(name));
+    }
+    if let Some(description) = placemark.description {
+        properties.insert(
+            "description".into(),
+            geo_features::Value::String(description),
+        );
+    }
+    for extended_data in placemark
+        .children
+        .iter()
+        .filter(|child| child.name == "ExtendedData")
+    {
+        insert_extended_data(extended_data, &mut properties);
+    }
+
+    let mut builder = geo_features::FeatureBuilder::new().with_properties(properties);
+    if let Some(geometry) = placemark.geometry {
+        builder = builder.with_geometry(geo::Geometry::try_from(geometry)?);
+    }
+    Ok(builder.build())
+}
+
+/// Read both untyped `<Data name="…"><value>…</value></Data>` and typed
+/// `<SchemaData><SimpleData name="…">…</SimpleData></SchemaData>` values.
+fn insert_extended_data(
+    extended_data: &kml::types::Element,
+    properties: &mut geo_features::Properties,
+) {
+    for child in &extended_data.children {
+        match child.name.as_str() {
+            "Data" => {
+                let value = child
+                    .children
+                    .iter()
+                    .find(|data_child| data_child.name == "value")
+                    .and_then(|value| value.content.clone());
+                insert_data_value(child, value, properties);
+            }
+            "SchemaData" => {
+                for simple_data in child
+                    .children
+                    .iter()
+                    .filter(|simple_data| simple_data.name == "SimpleData")
+                {
+                    insert_data_value(simple_data, simple_data.content.clone(), properties);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+fn insert_data_value(
+    data: &kml::types::Element,
+    value: Option<String>,
+    properties: &mut geo_features::Properties,
+) {
+    let Some(name) = data.attrs.get("name") else {
+        return;
+    };
+    properties.insert(
+        name.clone(),
+        match value {
+            Some(value) => geo_features::Value::String(value),
+            None => geo_features::Value::Null,
+        },
+    );
+}
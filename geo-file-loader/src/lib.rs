@@ -6,22 +6,70 @@
     clippy::expect_used
 )]
 
+mod csv;
+mod detect;
+mod flatgeobuf;
 mod geojson;
+mod geojsonseq;
+#[cfg(not(target_arch = "wasm32"))]
+mod geopackage;
+mod geoparquet;
+mod geotiff;
+mod gml;
 mod gpx;
+mod kml;
+mod mvt;
+mod osm;
+#[cfg(not(target_arch = "wasm32"))]
+mod pmtiles;
+mod processor;
 mod shapefile;
+#[cfg(not(target_arch = "wasm32"))]
+mod tileset;
+mod topojson;
+mod wkb;
 mod wkt;
+mod xml;
 
+pub use crate::csv::{csv_headers, CsvGeometryColumns, CsvSource};
+pub use crate::detect::detect_format;
+pub use crate::flatgeobuf::FlatGeobufSource;
 pub use crate::geojson::GeoJsonSource;
+pub use crate::geojsonseq::{GeoJsonSeqReader, GeoJsonSeqSource};
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::geopackage::{geopackage_feature_tables, GeoPackageSource};
+pub use crate::geoparquet::GeoParquetSource;
+pub use crate::geotiff::{GeoTiffSource, GeoTransform, Raster};
+pub use crate::gml::GmlSource;
 pub use crate::gpx::GpxSource;
-pub use crate::shapefile::ShapefileSource;
+pub use crate::kml::KmlSource;
+pub use crate::mvt::{MvtSource, MvtTiles, TileId};
+pub use crate::osm::OsmSource;
+pub use crate::shapefile::{ShapefileParts, ShapefileSource};
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::tileset::Tileset;
+pub use crate::topojson::TopoJsonSource;
+pub use crate::wkb::{wkb_srid, WkbSource};
 pub use crate::wkt::WktSource;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum FileFormat {
+    Csv,
+    FlatGeobuf,
     GeoJson,
+    GeoJsonSeq,
+    GeoPackage,
+    GeoParquet,
+    GeoTiff,
+    Gml,
     Shapefile,
+    TopoJson,
+    Wkb,
     Wkt,
     Gpx,
+    Kml,
+    Mvt,
+    Osm,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -30,25 +78,233 @@ pub enum Error {
     Geozero(#[from] geozero::error::GeozeroError),
     #[error("{0}")]
     Shapefile(#[from] geozero_shp::Error),
-    #[error("No geometry found in GeoJSON file")]
+    #[error("{0}")]
+    GeoJson(#[from] ::geojson::Error),
+    #[error("{0}")]
+    FlatGeobuf(#[from] ::flatgeobuf::Error),
+    #[error("{0}")]
+    Utf8(#[from] std::str::Utf8Error),
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+    #[error("{0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+    #[error("{0}")]
+    Arrow(#[from] arrow_schema::ArrowError),
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("{0}")]
+    Dbase(#[from] dbase::Error),
+    #[error("{0}")]
+    Csv(#[from] ::csv::Error),
+    #[error("{0}")]
+    Hex(#[from] hex::FromHexError),
+    #[error("{0}")]
+    Kml(#[from] ::kml::Error),
+    #[error("{0}")]
+    Xml(#[from] quick_xml::Error),
+    #[error("{0}")]
+    OsmPbf(#[from] osmpbf::Error),
+    #[error("{0}")]
+    Tiff(#[from] tiff::TiffError),
+    #[error("{0}")]
+    Transform(#[from] transform::Error),
+    #[cfg(not(target_arch = "wasm32"))]
+    #[error("{0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("No geometry found in file")]
     NoGeometry,
+    #[error("Could not determine which CSV columns contain the geometry")]
+    CsvGeometryColumnsNotFound,
+    #[error("No CSV column named {0}")]
+    CsvColumnNotFound(String),
+    #[error("Invalid geometry in CSV file: {0}")]
+    InvalidCsvGeometry(String),
+    #[error("No .shp file found")]
+    MissingShp,
+    #[error("No .kml file found in KMZ archive")]
+    MissingKml,
+    #[error("No GeoParquet metadata found")]
+    MissingGeoParquetMetadata,
+    #[error("Unsupported GeoParquet geometry encoding: {0}")]
+    UnsupportedGeoParquetEncoding(String),
+    #[error("Invalid GeoJSON sequence: {0}")]
+    InvalidGeoJsonSeq(&'static str),
+    #[error("Invalid GML: {0}")]
+    InvalidGml(&'static str),
+    #[error("Invalid GPX: {0}")]
+    InvalidGpx(&'static str),
+    #[error("Invalid vector tile: {0}")]
+    InvalidMvt(String),
+    #[error("The tile's z/x/y position is needed to place it on the map")]
+    MissingTileId,
+    #[error("Could not read the tile's z/x/y position from its path: {0}")]
+    InvalidTilePath(String),
+    #[error("Invalid PMTiles archive: {0}")]
+    InvalidPmTiles(&'static str),
+    #[error("Only vector tiles are supported, not {0} tiles")]
+    UnsupportedTileFormat(String),
+    #[error("The GeoTIFF has no georeferencing tags")]
+    MissingGeoreferencing,
+    #[error("Unsupported TIFF color type: {0}")]
+    UnsupportedTiffColorType(String),
+    #[error("{0} files are loaded as rasters, not as vector layers")]
+    RasterFormat(&'static str),
+    #[error("Invalid OSM element attribute: {0}")]
+    InvalidOsm(&'static str),
+    #[error("Invalid TopoJSON: {0}")]
+    InvalidTopoJson(&'static str),
+    #[error("{0} files can't be loaded from multiple files")]
+    MultipleFilesUnsupported(&'static str),
+    #[error("{0} files can't be loaded on the web")]
+    UnsupportedOnWeb(&'static str),
+}
+
+/// A file handed to the loader, along with the name it was opened with.
+#[derive(Debug)]
+pub struct InputFile {
+    pub name: String,
+    pub bytes: bytes::Bytes,
+}
+
+/// A coordinate reference system declared by the loaded file itself.
+#[derive(Clone, Debug)]
+pub enum DetectedCrs {
+    EpsgCode(u16),
+    /// OGC or ESRI WKT, e.g. from a Shapefile's `.prj`
+    Wkt(String),
+    /// PROJJSON, e.g. from GeoParquet metadata
+    ProjJson(String),
+}
+
+impl DetectedCrs {
+    /// WKT with a known EPSG code uses that code's definition, otherwise the WKT or PROJJSON
+    /// itself is used
+    pub fn to_crs(&self) -> transform::Crs {
+        match self {
+            DetectedCrs::EpsgCode(epsg_code) => transform::Crs::Epsg(*epsg_code),
+            DetectedCrs::Wkt(wkt) => match transform::epsg_code_from_wkt(wkt) {
+                Some(epsg_code) => transform::Crs::Epsg(epsg_code),
+                None => transform::Crs::Wkt(wkt.clone()),
+            },
+            DetectedCrs::ProjJson(projjson) => transform::Crs::ProjJson(projjson.clone()),
+        }
+    }
+}
+
+/// A rectangle to only load the features intersecting, e.g. the map's extent
+#[derive(Clone, Debug)]
+pub struct Bbox {
+    pub rect: geo::Rect,
+    /// The CRS of `rect`, e.g. the map's CRS
+    pub crs: transform::Crs,
+}
+
+impl Bbox {
+    /// The bounding rectangle of `rect` reprojected to `crs`.
+    ///
+    /// Only the corners are reprojected, so the result is an approximation for CRSs that bend
+    /// straight lines.
+    fn in_crs(&self, crs: &transform::Crs) -> Result<Option<geo::Rect>, Error> {
+        let mut geometry = geo::Geometry::from(self.rect.to_polygon());
+        transform::Transformer::setup(&self.crs, crs)?
+            .transform(&mut geometry)
+            .map_err(transform::Error::from)?;
+        Ok(geo::BoundingRect::bounding_rect(&geometry))
+    }
+}
+
+pub struct LoadedFile {
+    pub layers: Vec<LoadedLayer>,
+}
+
+/// One of the layers contained in a loaded file. Most formats produce a single layer.
+pub struct LoadedLayer {
+    /// The layer's name within the file, e.g. a GeoPackage table name
+    pub name: Option<String>,
+    pub feature_collection: geo_features::FeatureCollection,
+    pub crs: Option<DetectedCrs>,
+}
+
+impl From<LoadedLayer> for LoadedFile {
+    fn from(layer: LoadedLayer) -> Self {
+        LoadedFile {
+            layers: vec![layer],
+        }
+    }
+}
+
+impl From<geo_features::FeatureCollection> for LoadedFile {
+    fn from(feature_collection: geo_features::FeatureCollection) -> Self {
+        LoadedLayer {
+            name: None,
+            feature_collection,
+            crs: None,
+        }
+        .into()
+    }
+}
+
+/// Options that only some file formats make use of.
+#[derive(Clone, Debug, Default)]
+pub struct LoadOptions {
+    /// Only load the features intersecting this rectangle. Ignored by formats without a spatial
+    /// index.
+    pub bbox: Option<Bbox>,
+    /// The CRS of files that don't declare theirs, e.g. the one typed in the add layer window
+    pub default_crs: Option<transform::Crs>,
+    /// The GeoPackage feature tables to load. All feature tables are loaded if `None`.
+    pub geopackage_tables: Option<Vec<String>>,
+    /// The CSV columns holding the geometry. Guessed from the column names if `None`.
+    pub csv_geometry_columns: Option<CsvGeometryColumns>,
+    /// Load each KML `<Folder>` as a separate layer
+    pub split_kml_folders: bool,
+    /// Load the OSM features into one layer per value of this tag key, e.g. `highway`
+    pub osm_split_key: Option<String>,
+    /// The position of a single vector tile. Read from the file name if `None`.
+    pub mvt_tile: Option<TileId>,
 }
 
 impl FileFormat {
     pub const fn is_plaintext(self) -> bool {
         match self {
+            Self::Csv => true,
+            Self::FlatGeobuf => false,
             Self::GeoJson => true,
+            Self::GeoJsonSeq => true,
+            Self::GeoPackage => false,
+            Self::GeoParquet => false,
+            Self::GeoTiff => false,
+            Self::Gml => true,
             Self::Gpx => true,
+            Self::Kml => true,
+            Self::Mvt => false,
+            Self::Osm => false,
             Self::Shapefile => false,
+            Self::TopoJson => true,
+            Self::Wkb => true,
             Self::Wkt => true,
         }
     }
 
     pub const fn display_name(self) -> &'static str {
         match self {
+            Self::Csv => "CSV",
+            Self::FlatGeobuf => "FlatGeobuf",
             Self::GeoJson => "GeoJSON",
+            Self::GeoJsonSeq => "GeoJSONSeq",
+            Self::GeoPackage => "GeoPackage",
+            Self::GeoParquet => "GeoParquet",
+            Self::GeoTiff => "GeoTIFF",
+            Self::Gml => "GML",
             Self::Gpx => "GPX",
+            Self::Kml => "KML",
+            Self::Mvt => "MVT",
+            Self::Osm => "OpenStreetMap",
             Self::Shapefile => "Shapefile",
+            Self::TopoJson => "TopoJSON",
+            Self::Wkb => "WKB",
             Self::Wkt => "WKT",
         }
     }
@@ -57,16 +313,90 @@ impl FileFormat {
 pub fn load_file(
     file_format: FileFormat,
     bytes: bytes::Bytes,
-) -> Result<geo_features::FeatureCollection, Error> {
+    options: &LoadOptions,
+) -> Result<LoadedFile, Error> {
     match file_format {
+        FileFormat::Csv => Ok(CsvSource {
+            bytes,
+            geometry_columns: options.csv_geometry_columns.clone(),
+        }
+        .load()?),
+        FileFormat::FlatGeobuf => Ok(FlatGeobufSource {
+            bytes,
+            bbox: options.bbox.clone(),
+            default_crs: options.default_crs.clone(),
+        }
+        .load()?),
         FileFormat::GeoJson => Ok(GeoJsonSource::from_bytes(bytes).load()?),
+        FileFormat::GeoJsonSeq => Ok(GeoJsonSeqSource::from_bytes(bytes).load()?),
+        #[cfg(not(target_arch = "wasm32"))]
+        FileFormat::GeoPackage => Ok(GeoPackageSource {
+            bytes,
+            tables: options.geopackage_tables.clone(),
+        }
+        .load()?),
+        #[cfg(target_arch = "wasm32")]
+        FileFormat::GeoPackage => Err(Error::UnsupportedOnWeb(file_format.display_name())),
+        FileFormat::GeoParquet => Ok(GeoParquetSource::from_bytes(bytes).load()?),
+        FileFormat::GeoTiff => Err(Error::RasterFormat(file_format.display_name())),
+        FileFormat::Gml => Ok(GmlSource::from_bytes(bytes).load()?),
         FileFormat::Gpx => Ok(GpxSource::from_bytes(bytes).load()?),
+        FileFormat::Kml => Ok(KmlSource {
+            bytes,
+            split_folders: options.split_kml_folders,
+        }
+        .load()?),
+        FileFormat::Mvt => Ok(MvtSource {
+            bytes,
+            tile: options.mvt_tile,
+        }
+        .load()?),
+        FileFormat::Osm => Ok(OsmSource {
+            bytes,
+            split_key: options.osm_split_key.clone(),
+        }
+        .load()?),
         FileFormat::Shapefile => Ok(ShapefileSource::from_bytes(bytes).load()?),
+        FileFormat::TopoJson => Ok(TopoJsonSource::from_bytes(bytes).load()?),
+        FileFormat::Wkb => Ok(WkbSource::from_bytes(bytes).load()?),
         FileFormat::Wkt => Ok(WktSource::from_bytes(bytes).load()?),
     }
 }
 
+/// Load a file that was opened together with other files, e.g. a Shapefile's `.shp`, `.dbf` and
+/// `.prj`.
+pub fn load_files(
+    file_format: FileFormat,
+    files: Vec<InputFile>,
+    options: &LoadOptions,
+) -> Result<LoadedFile, Error> {
+    match <[InputFile; 1]>::try_from(files) {
+        Ok([file]) if file_format == FileFormat::Mvt => MvtSource {
+            tile: options.mvt_tile.or_else(|| TileId::from_path(&file.name)),
+            bytes: file.bytes,
+        }
+        .load(),
+        Ok([file]) => load_file(file_format, file.bytes, options),
+        Err(files) if file_format == FileFormat::Shapefile => {
+            ShapefileParts::from_files(files).load()
+        }
+        Err(files) if file_format == FileFormat::Mvt => MvtTiles { files }.load(),
+        Err(_) => Err(Error::MultipleFilesUnsupported(file_format.display_name())),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn geopackage_feature_tables(_bytes: &[u8]) -> Result<Vec<String>, Error> {
+    Err(Error::UnsupportedOnWeb(
+        FileFormat::GeoPackage.display_name(),
+    ))
+}
+
+fn is_zip(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"PK\x03\x04")
+}
+
 trait FileLoader {
     fn from_bytes(bytes: bytes::Bytes) -> Self;
-    fn load(self) -> Result<geo_features::FeatureCollection, Error>;
+    fn load(self) -> Result<LoadedFile, Error>;
 }
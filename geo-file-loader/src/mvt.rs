@@ -0,0 +1,228 @@
+use geozero::{mvt::Message, ToGeo};
+use std::{fmt, io::Read};
+
+/// Half the width of the Web Mercator (EPSG:3857) world, in meters
+const WEB_MERCATOR_HALF_WIDTH: f64 = 20_037_508.342_789_244;
+const DEFAULT_EXTENT: u32 = 4096;
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+/// The position of a tile in the XYZ tiling scheme
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TileId {
+    pub z: u8,
+    pub x: u32,
+    pub y: u32,
+}
+
+impl TileId {
+    /// Read the tile position from the end of a path, e.g. `tiles/14/8192/5461.pbf` or
+    /// `14/8192/5461`.
+    pub fn from_path(path: &str) -> Option<Self> {
+        let mut components = path.rsplit(['/', '\\']);
+        let file_name = components.next()?;
+        let y = file_name.split('.').next()?.parse().ok()?;
+        let x = components.next()?.parse().ok()?;
+        let z = components.next()?.parse().ok()?;
+        Some(TileId { z, x, y })
+    }
+
+    /// Convert tile coordinates, where `(0, 0)` is the top left corner and `(extent, extent)` the
+    /// bottom right corner, into Web Mercator coordinates.
+    fn to_web_mercator(self, extent: u32) -> impl Fn(geo::Coord) -> geo::Coord {
+        let tile_width = Self::width(self.z);
+        let left = -WEB_MERCATOR_HALF_WIDTH + f64::from(self.x) * tile_width;
+        let top = WEB_MERCATOR_HALF_WIDTH - f64::from(self.y) * tile_width;
+        let scale = tile_width / f64::from(extent);
+        move |coord| geo::coord! { x: left + coord.x * scale, y: top - coord.y * scale }
+    }
+
+    fn width(z: u8) -> f64 {
+        2. * WEB_MERCATOR_HALF_WIDTH / f64::from(1u32 << z.min(31))
+    }
+
+    /// The zoom level whose 256 pixel wide tiles are closest to `meters_per_pixel`
+    pub fn zoom_for_resolution(meters_per_pixel: f64) -> f64 {
+        (2. * WEB_MERCATOR_HALF_WIDTH / (256. * meters_per_pixel)).log2()
+    }
+
+    /// The tiles at zoom level `z` that intersect `rect`, which is in Web Mercator coordinates.
+    pub fn covering(rect: geo::Rect, z: u8) -> impl Iterator<Item = TileId> {
+        let tile_width = Self::width(z);
+        let max_index = (1u32 << z.min(31)) - 1;
+        // Saturating float to int casts clamp coordinates beyond the edges of the world
+        let index = |meters: f64| ((meters / tile_width).floor() as u32).min(max_index);
+        let (min_x, max_x) = (
+            index(rect.min().x + WEB_MERCATOR_HALF_WIDTH),
+            index(rect.max().x + WEB_MERCATOR_HALF_WIDTH),
+        );
+        let (min_y, max_y) = (
+            index(WEB_MERCATOR_HALF_WIDTH - rect.max().y),
+            index(WEB_MERCATOR_HALF_WIDTH - rect.min().y),
+        );
+        (min_x..=max_x).flat_map(move |x| (min_y..=max_y).map(move |y| TileId { z, x, y }))
+    }
+}
+
+impl fmt::Display for TileId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.z, self.x, self.y)
+    }
+}
+
+/// A single Mapbox Vector Tile, optionally gzip compressed.
+pub struct MvtSource {
+    pub bytes: bytes::Bytes,
+    /// Needed to place the tile's features on the map
+    pub tile: Option<TileId>,
+}
+
+impl crate::FileLoader for MvtSource {
+    fn from_bytes(bytes: bytes::Bytes) -> Self {
+        MvtSource { bytes, tile: None }
+    }
+
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let tile = self.tile.ok_or(crate::Error::MissingTileId)?;
+        let mut layers = MvtLayers::default();
+        layers.add_tile(&self.bytes, tile)?;
+        layers.finish()
+    }
+}
+
+/// Decode the layers of a single tile. Unlike [`MvtSource`], a tile without features isn't an
+/// error, which is common for tiles of a tileset.
+pub(crate) fn decode_tile(
+    bytes: &[u8],
+    tile: TileId,
+) -> Result<Vec<crate::LoadedLayer>, crate::Error> {
+    let mut layers = MvtLayers::default();
+    layers.add_tile(bytes, tile)?;
+    Ok(layers.into_layers())
+}
+
+/// A directory of vector tiles named after their position, e.g. `14/8192/5461.pbf`. Layers with
+/// the same name in different tiles are merged.
+pub struct MvtTiles {
+    pub files: Vec<crate::InputFile>,
+}
+
+impl MvtTiles {
+    /// Directories of tiles often hold several zoom levels, whose tiles overlap, so only the tiles
+    /// of the deepest zoom level are loaded.
+    pub fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let tiles = self
+            .files
+            .into_iter()
+            .map(|file| match TileId::from_path(&file.name) {
+                Some(tile) => Ok((tile, file)),
+                None => Err(crate::Error::InvalidTilePath(file.name)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let max_z = tiles.iter().map(|(tile, _)| tile.z).max();
+        let mut layers = MvtLayers::default();
+        for (tile, file) in tiles {
+            if Some(tile.z) == max_z {
+                layers.add_tile(&file.bytes, tile)?;
+            }
+        }
+        layers.finish()
+    }
+}
+
+/// Features of each MVT layer, in the order the layers were first seen
+#[derive(Default)]
+struct MvtLayers(Vec<(String, Vec<geo_features::Feature>)>);
+
+impl MvtLayers {
+    fn add_tile(&mut self, bytes: &[u8], tile_id: TileId) -> Result<(), crate::Error> {
+        let tile = if bytes.starts_with(GZIP_MAGIC) {
+            let mut decompressed = vec![];
+            flate2::read::GzDecoder::new(bytes).read_to_end(&mut decompressed)?;
+            geozero::mvt::Tile::decode(decompressed.as_slice())
+        } else {
+            geozero::mvt::Tile::decode(bytes)
+        }
+        .map_err(|e| crate::Error::InvalidMvt(e.to_string()))?;
+
+        for layer in tile.layers {
+            let to_web_mercator = tile_id.to_web_mercator(layer.extent.unwrap_or(DEFAULT_EXTENT));
+            let mut features = Vec::with_capacity(layer.features.len());
+            for feature in &layer.features {
+                let mut geometry = feature.to_geo()?;
+                geo::MapCoordsInPlace::map_coords_in_place(&mut geometry, &to_web_mercator);
+                features.push(
+                    geo_features::FeatureBuilder::new()
+                        .with_properties(properties(&layer, feature))
+                        .with_geometry(geometry)
+                        .build(),
+                );
+            }
+            match self.0.iter_mut().find(|(name, _)| *name == layer.name) {
+                Some((_, layer_features)) => layer_features.extend(features),
+                None => self.0.push((layer.name, features)),
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<crate::LoadedFile, crate::Error> {
+        if self.0.iter().all(|(_, features)| features.is_empty()) {
+            return Err(crate::Error::NoGeometry);
+        }
+        Ok(crate::LoadedFile {
+            layers: self.into_layers(),
+        })
+    }
+
+    fn into_layers(self) -> Vec<crate::LoadedLayer> {
+        self.0
+            .into_iter()
+            .map(|(name, features)| crate::LoadedLayer {
+                name: Some(name),
+                feature_collection: geo_features::FeatureCollection::from_features(features),
+                crs: Some(crate::DetectedCrs::EpsgCode(3857)),
+            })
+            .collect()
+    }
+}
+
+/// Feature tags are pairs of indices into the layer's keys and values.
+fn properties(
+    layer: &geozero::mvt::tile::Layer,
+    feature: &geozero::mvt::tile::Feature,
+) -> geo_features::Properties {
+    let mut properties = geo_features::Properties::default();
+    for pair in feature.tags.chunks_exact(2) {
+        let [key_index, value_index] = pair else {
+            continue;
+        };
+        let key = usize::try_from(*key_index)
+            .ok()
+            .and_then(|i| layer.keys.get(i));
+        let value = usize::try_from(*value_index)
+            .ok()
+            .and_then(|i| layer.values.get(i));
+        if let (Some(key), Some(value)) = (key, value) {
+            properties.insert(key.clone(), tile_value_to_value(value));
+        }
+    }
+    properties
+}
+
+fn tile_value_to_value(value: &geozero::mvt::tile::Value) -> geo_features::Value {
+    if let Some(ref string) = value.string_value {
+        geo_features::Value::String(string.clone())
+    } else if let Some(float) = value.float_value {
+        geo_features::Value::Number(f64::from(float))
+    } else if let Some(double) = value.double_value {
+        geo_features::Value::Number(double)
+    } else if let Some(int) = value.int_value.or(value.sint_value) {
+        geo_features::Value::Number(int as f64)
+    } else if let Some(uint) = value.uint_value {
+        geo_features::Value::Number(uint as f64)
+    } else if let Some(boolean) = value.bool_value {
+        geo_features::Value::Boolean(boolean)
+    } else {
+        geo_features::Value::Null
+    }
+}
@@ -0,0 +1,446 @@
+use geo::Contains;
+use std::collections;
+
+/// Keys of ways that are lines even when they're closed, unless tagged `area=yes`
+const LINEAR_KEYS: &[&str] = &["highway", "barrier", "railway", "waterway", "power"];
+/// When splitting by a key, values other than the most common ones share a layer, so keys with
+/// many values, e.g. `name`, don't produce thousands of layers
+const MAX_SPLIT_LAYERS: usize = 16;
+
+/// An OpenStreetMap `.osm` XML or `.osm.pbf` file. Its coordinates are WGS 84 longitudes and
+/// latitudes.
+pub struct OsmSource {
+    pub bytes: bytes::Bytes,
+    /// Create one layer for every value of this tag key, e.g. `highway` or `building`
+    pub split_key: Option<String>,
+}
+
+impl crate::FileLoader for OsmSource {
+    fn from_bytes(bytes: bytes::Bytes) -> Self {
+        OsmSource {
+            bytes,
+            split_key: None,
+        }
+    }
+
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let is_xml = self
+            .bytes
+            .iter()
+            .find(|b| !b.is_ascii_whitespace())
+            .is_some_and(|b| *b == b'<');
+        let data = if is_xml {
+            read_xml(&self.bytes)?
+        } else {
+            read_pbf(&self.bytes)?
+        };
+        let features = data.into_features();
+        if features.is_empty() {
+            return Err(crate::Error::NoGeometry);
+        }
+        Ok(match self.split_key {
+            Some(split_key) => split_by_key(features, &split_key),
+            None => crate::LoadedLayer {
+                name: None,
+                feature_collection: geo_features::FeatureCollection::from_features(features),
+                crs: Some(crate::DetectedCrs::EpsgCode(4326)),
+            }
+            .into(),
+        })
+    }
+}
+
+type Tags = Vec<(String, String)>;
+
+#[derive(Default)]
+struct OsmData {
+    node_coords: collections::HashMap<i64, geo::Coord>,
+    tagged_nodes: Vec<(i64, Tags)>,
+    ways: Vec<Way>,
+    relations: Vec<Relation>,
+}
+
+struct Way {
+    id: i64,
+    node_ids: Vec<i64>,
+    tags: Tags,
+}
+
+struct Relation {
+    id: i64,
+    members: Vec<Member>,
+    tags: Tags,
+}
+
+struct Member {
+    is_way: bool,
+    id: i64,
+    role: String,
+}
+
+impl OsmData {
+    fn add_node(&mut self, id: i64, coord: geo::Coord, tags: Tags) {
+        self.node_coords.insert(id, coord);
+        if !tags.is_empty() {
+            self.tagged_nodes.push((id, tags));
+        }
+    }
+
+    fn way_coords(&self, node_ids: &[i64]) -> Vec<geo::Coord> {
+        // Extracts can reference nodes outside of their bounds, so skip the ones that are missing
+        node_ids
+            .iter()
+            .filter_map(|node_id| self.node_coords.get(node_id).copied())
+            .collect()
+    }
+
+    fn into_features(self) -> Vec<geo_features::Feature> {
+        let mut features = vec![];
+
+        for (id, tags) in &self.tagged_nodes {
+            if let Some(coord) = self.node_coords.get(id) {
+                features.push(build_feature(
+                    "node",
+                    *id,
+                    tags,
+                    geo::Point::from(*coord).into(),
+                ));
+            }
+        }
+
+        let ways_by_id = self
+            .ways
+            .iter()
+            .map(|way| (way.id, way))
+            .collect::<collections::HashMap<_, _>>();
+
+        // Untagged ways are usually only there to make up relations
+        for way in self.ways.iter().filter(|way| !way.tags.is_empty()) {
+            let coords = self.way_coords(&way.node_ids);
+            if coords.len() < 2 {
+                continue;
+            }
+            let line_string = geo::LineString::new(coords);
+            let geometry = if is_area(&line_string, &way.tags) {
+                geo::Polygon::new(line_string, vec![]).into()
+            } else {
+                line_string.into()
+            };
+            features.push(build_feature("way", way.id, &way.tags, geometry));
+        }
+
+        for relation in &self.relations {
+            let is_multipolygon = relation.tags.iter().any(|(key, value)| {
+                key == "type" && (value == "multipolygon" || value == "boundary")
+            });
+            if !is_multipolygon {
+                continue;
+            }
+            let (mut outer_segments, mut inner_segments) = (vec![], vec![]);
+            for member in relation.members.iter().filter(|member| member.is_way) {
+                let Some(way) = ways_by_id.get(&member.id) else {
+                    continue;
+                };
+                let coords = self.way_coords(&way.node_ids);
+                if member.role == "inner" {
+                    inner_segments.push(coords);
+                } else {
+                    outer_segments.push(coords);
+                }
+            }
+            let multi_polygon = assemble_multi_polygon(outer_segments, inner_segments);
+            if !multi_polygon.0.is_empty() {
+                features.push(build_feature(
+                    "relation",
+                    relation.id,
+                    &relation.tags,
+                    multi_polygon.into(),
+                ));
+            }
+        }
+
+        features
+    }
+}
+
+fn build_feature(
+    element_type: &str,
+    id: i64,
+    tags: &Tags,
+    geometry: geo::Geometry,
+) -> geo_features::Feature {
+    let mut properties = tags
+        .iter()
+        .map(|(key, value)| (key.clone(), geo_features::Value::String(value.clone())))
+        .collect::<geo_features::Properties>();
+    properties.insert(
+        "osm_id".into(),
+        geo_features::Value::String(format!("{element_type}/{id}")),
+    );
+    geo_features::FeatureBuilder::new()
+        .with_properties(properties)
+        .with_geometry(geometry)
+        .build()
+}
+
+fn is_area(line_string: &geo::LineString, tags: &Tags) -> bool {
+    let tag = |key: &str| {
+        tags.iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    };
+    if !line_string.is_closed() || line_string.0.len() < 4 || tag("area") == Some("no") {
+        return false;
+    }
+    tag("area") == Some("yes") || !LINEAR_KEYS.iter().any(|key| tag(key).is_some())
+}
+
+/// Build a multipolygon from the ways of a multipolygon relation. Ways are joined end to end into
+/// rings, and inner rings become holes of the outer ring containing them.
+fn assemble_multi_polygon(
+    outer_segments: Vec<Vec<geo::Coord>>,
+    inner_segments: Vec<Vec<geo::Coord>>,
+) -> geo::MultiPolygon {
+    let mut polygons = assemble_rings(outer_segments)
+        .into_iter()
+        .map(|ring| geo::Polygon::new(ring, vec![]))
+        .collect::<Vec<_>>();
+    for ring in assemble_rings(inner_segments) {
+        if let Some(polygon) = polygons.iter_mut().find(|polygon| polygon.contains(&ring)) {
+            polygon.interiors_push(ring);
+        }
+    }
+    geo::MultiPolygon::new(polygons)
+}
+
+fn assemble_rings(mut segments: Vec<Vec<geo::Coord>>) -> Vec<geo::LineString> {
+    let mut rings = vec![];
+    while let Some(mut ring) = segments.pop() {
+        while let (Some(first), Some(last)) = (ring.first().copied(), ring.last().copied()) {
+            if first == last {
+                break;
+            }
+            let Some(i) = segments.iter().position(|segment| {
+                segment.first() == Some(&last) || segment.last() == Some(&last)
+            }) else {
+                break;
+            };
+            let mut segment = segments.swap_remove(i);
+            if segment.first() != Some(&last) {
+                segment.reverse();
+            }
+            ring.extend(segment.into_iter().skip(1));
+        }
+        // Rings that can't be closed, e.g. because the extract cuts through them, are dropped
+        let ring = geo::LineString::new(ring);
+        if ring.is_closed() && ring.0.len() >= 4 {
+            rings.push(ring);
+        }
+    }
+    rings
+}
+
+/// One layer per value of `key`, for its most common values, then one layer for the features with
+/// any other value and one for the features without the key.
+fn split_by_key(features: Vec<geo_features::Feature>, key: &str) -> crate::LoadedFile {
+    let mut by_value = collections::BTreeMap::<String, Vec<geo_features::Feature>>::new();
+    let mut without_key = vec![];
+    for feature in features {
+        match feature.properties.get(key) {
+            Some(geo_features::Value::String(value)) => {
+                by_value.entry(value.clone()).or_default().push(feature)
+            }
+            _ => without_key.push(feature),
+        }
+    }
+    let mut by_value = by_value.into_iter().collect::<Vec<_>>();
+    // Stable, so values with as many features stay sorted by name
+    by_value.sort_by_key(|(_, features)| std::cmp::Reverse(features.len()));
+    let other_values = if by_value.len() > MAX_SPLIT_LAYERS {
+        by_value
+            .split_off(MAX_SPLIT_LAYERS)
+            .into_iter()
+            .flat_map(|(_, features)| features)
+            .collect()
+    } else {
+        vec![]
+    };
+
+    let layer = |name: String, features: Vec<geo_features::Feature>| crate::LoadedLayer {
+        name: Some(name),
+        feature_collection: geo_features::FeatureCollection::from_features(features),
+        crs: Some(crate::DetectedCrs::EpsgCode(4326)),
+    };
+    let mut layers = by_value
+        .into_iter()
+        .map(|(value, features)| layer(format!("{key}={value}"), features))
+        .collect::<Vec<_>>();
+    if !other_values.is_empty() {
+        layers.push(layer(format!("other {key}"), other_values));
+    }
+    if !without_key.is_empty() {
+        layers.push(layer(format!("no {key}"), without_key));
+    }
+    crate::LoadedFile { layers }
+}
+
+fn read_pbf(bytes: &bytes::Bytes) -> Result<OsmData, crate::Error> {
+    let mut data = OsmData::default();
+    osmpbf::ElementReader::new(&bytes[..]).for_each(|element| match element {
+        osmpbf::Element::Node(node) => data.add_node(
+            node.id(),
+            geo::coord! { x: node.lon(), y: node.lat() },
+            collect_tags(node.tags()),
+        ),
+        osmpbf::Element::DenseNode(node) => data.add_node(
+            node.id(),
+            geo::coord! { x: node.lon(), y: node.lat() },
+            collect_tags(node.tags()),
+        ),
+        osmpbf::Element::Way(way) => data.ways.push(Way {
+            id: way.id(),
+            node_ids: way.refs().collect(),
+            tags: collect_tags(way.tags()),
+        }),
+        osmpbf::Element::Relation(relation) => data.relations.push(Relation {
+            id: relation.id(),
+            members: relation
+                .members()
+                .map(|member| Member {
+                    is_way: member.member_type == osmpbf::RelMemberType::Way,
+                    id: member.member_id,
+                    role: member.role().unwrap_or_default().to_owned(),
+                })
+                .collect(),
+            tags: collect_tags(relation.tags()),
+        }),
+    })?;
+    Ok(data)
+}
+
+fn collect_tags<'a>(tags: impl Iterator<Item = (&'a str, &'a str)>) -> Tags {
+    tags.map(|(key, value)| (key.to_owned(), value.to_owned()))
+        .collect()
+}
+
+fn read_xml(bytes: &bytes::Bytes) -> Result<OsmData, crate::Error> {
+    use quick_xml::events::Event;
+
+    let mut data = OsmData::default();
+    let mut reader = quick_xml::Reader::from_reader(&bytes[..]);
+    // The node, way or relation whose children are being read
+    let mut current: Option<Element> = None;
+    loop {
+        let (element, is_empty) = match reader.read_event()? {
+            Event::Start(element) => (element, false),
+            Event::Empty(element) => (element, true),
+            Event::End(element) => {
+                if matches!(element.name().as_ref(), b"node" | b"way" | b"relation") {
+                    if let Some(element) = current.take() {
+                        element.finish(&mut data);
+                    }
+                }
+                continue;
+            }
+            Event::Eof => break,
+            _ => continue,
+        };
+        match element.name().as_ref() {
+            b"node" => {
+                current = Some(Element::Node {
+                    id: parse_attribute(&element, "id")?,
+                    coord: geo::coord! {
+                        x: parse_attribute(&element, "lon")?,
+                        y: parse_attribute(&element, "lat")?,
+                    },
+                    tags: vec![],
+                });
+            }
+            b"way" => {
+                current = Some(Element::Way(Way {
+                    id: parse_attribute(&element, "id")?,
+                    node_ids: vec![],
+                    tags: vec![],
+                }));
+            }
+            b"relation" => {
+                current = Some(Element::Relation(Relation {
+                    id: parse_attribute(&element, "id")?,
+                    members: vec![],
+                    tags: vec![],
+                }));
+            }
+            b"tag" => {
+                let tag = (attribute(&element, "k")?, attribute(&element, "v")?);
+                match current {
+                    Some(Element::Node { ref mut tags, .. })
+                    | Some(Element::Way(Way { ref mut tags, .. }))
+                    | Some(Element::Relation(Relation { ref mut tags, .. })) => tags.push(tag),
+                    None => (),
+                }
+            }
+            b"nd" => {
+                if let Some(Element::Way(ref mut way)) = current {
+                    way.node_ids.push(parse_attribute(&element, "ref")?);
+                }
+            }
+            b"member" => {
+                if let Some(Element::Relation(ref mut relation)) = current {
+                    relation.members.push(Member {
+                        is_way: attribute(&element, "type")? == "way",
+                        id: parse_attribute(&element, "ref")?,
+                        role: attribute(&element, "role")?,
+                    });
+                }
+            }
+            _ => (),
+        }
+        // Self-closing elements don't have an end event
+        if is_empty && matches!(element.name().as_ref(), b"node" | b"way" | b"relation") {
+            if let Some(element) = current.take() {
+                element.finish(&mut data);
+            }
+        }
+    }
+    Ok(data)
+}
+
+enum Element {
+    Node {
+        id: i64,
+        coord: geo::Coord,
+        tags: Tags,
+    },
+    Way(Way),
+    Relation(Relation),
+}
+
+impl Element {
+    fn finish(self, data: &mut OsmData) {
+        match self {
+            Element::Node { id, coord, tags } => data.add_node(id, coord, tags),
+            Element::Way(way) => data.ways.push(way),
+            Element::Relation(relation) => data.relations.push(relation),
+        }
+    }
+}
+
+fn attribute(
+    element: &quick_xml::events::BytesStart,
+    name: &'static str,
+) -> Result<String, crate::Error> {
+    Ok(element
+        .try_get_attribute(name)?
+        .ok_or(crate::Error::InvalidOsm(name))?
+        .unescape_value()?
+        .into_owned())
+}
+
+fn parse_attribute<T: std::str::FromStr>(
+    element: &quick_xml::events::BytesStart,
+    name: &'static str,
+) -> Result<T, crate::Error> {
+    attribute(element, name)?
+        .parse()
+        .map_err(|_| crate::Error::InvalidOsm(name))
+}
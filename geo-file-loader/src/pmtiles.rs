@@ -0,0 +1,367 @@
+use crate::TileId;
+use std::{
+    fs,
+    io::{self, Read, Seek},
+    path, sync,
+};
+
+pub(crate) const PMTILES_MAGIC: &[u8] = b"PMTiles";
+const HEADER_LENGTH: usize = 127;
+const VERSION: u8 = 3;
+/// The root directory points to leaf directories, which may point to further leaf directories
+const MAX_DIRECTORY_DEPTH: usize = 4;
+
+const COMPRESSION_UNKNOWN: u8 = 0;
+const COMPRESSION_NONE: u8 = 1;
+const COMPRESSION_GZIP: u8 = 2;
+const TILE_TYPE_UNKNOWN: u8 = 0;
+const TILE_TYPE_MVT: u8 = 1;
+
+/// A version 3 PMTiles archive. Only the header and root directory are kept in memory, tiles and
+/// leaf directories are read from the file when needed.
+pub(crate) struct PmTiles {
+    file: sync::Mutex<fs::File>,
+    header: Header,
+    root_directory: Vec<Entry>,
+    pub name: Option<String>,
+}
+
+struct Header {
+    root_directory_offset: u64,
+    root_directory_length: u64,
+    metadata_offset: u64,
+    metadata_length: u64,
+    leaf_directories_offset: u64,
+    tile_data_offset: u64,
+    internal_compression: u8,
+    tile_compression: u8,
+    tile_type: u8,
+    min_zoom: u8,
+    max_zoom: u8,
+}
+
+impl Header {
+    fn parse(bytes: &[u8]) -> Result<Self, crate::Error> {
+        if !bytes.starts_with(PMTILES_MAGIC) {
+            return Err(crate::Error::InvalidPmTiles("missing PMTiles magic number"));
+        }
+        if bytes.get(7) != Some(&VERSION) {
+            return Err(crate::Error::InvalidPmTiles("only version 3 is supported"));
+        }
+        let byte = |offset: usize| bytes.get(offset).copied().unwrap_or_default();
+        let header = Header {
+            root_directory_offset: read_u64(bytes, 8),
+            root_directory_length: read_u64(bytes, 16),
+            metadata_offset: read_u64(bytes, 24),
+            metadata_length: read_u64(bytes, 32),
+            leaf_directories_offset: read_u64(bytes, 40),
+            tile_data_offset: read_u64(bytes, 56),
+            internal_compression: byte(97),
+            tile_compression: byte(98),
+            tile_type: byte(99),
+            min_zoom: byte(100),
+            max_zoom: byte(101),
+        };
+        // Tiles are decompressed when decoded, see `crate::mvt`
+        for compression in [header.internal_compression, header.tile_compression] {
+            if !matches!(
+                compression,
+                COMPRESSION_UNKNOWN | COMPRESSION_NONE | COMPRESSION_GZIP
+            ) {
+                return Err(crate::Error::InvalidPmTiles(
+                    "only uncompressed and gzip compressed archives are supported",
+                ));
+            }
+        }
+        if !matches!(header.tile_type, TILE_TYPE_UNKNOWN | TILE_TYPE_MVT) {
+            return Err(crate::Error::UnsupportedTileFormat(
+                tile_type_name(header.tile_type).into(),
+            ));
+        }
+        Ok(header)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Entry {
+    tile_id: u64,
+    offset: u64,
+    length: u64,
+    /// Number of consecutive tile IDs with the same contents, or `0` for a leaf directory
+    run_length: u64,
+}
+
+impl PmTiles {
+    pub fn open(path: &path::Path) -> Result<Self, crate::Error> {
+        let mut file = fs::File::open(path)?;
+        let mut header_bytes = [0; HEADER_LENGTH];
+        file.read_exact(&mut header_bytes)?;
+        let header = Header::parse(&header_bytes)?;
+
+        let mut pm_tiles = PmTiles {
+            file: sync::Mutex::new(file),
+            root_directory: vec![],
+            header,
+            name: None,
+        };
+        pm_tiles.root_directory = pm_tiles.read_directory(
+            pm_tiles.header.root_directory_offset,
+            pm_tiles.header.root_directory_length,
+        )?;
+        pm_tiles.name = pm_tiles.read_name()?;
+        Ok(pm_tiles)
+    }
+
+    pub fn min_zoom(&self) -> u8 {
+        self.header.min_zoom
+    }
+
+    pub fn max_zoom(&self) -> u8 {
+        self.header.max_zoom
+    }
+
+    pub fn read_tile(&self, tile: TileId) -> Result<Option<Vec<u8>>, crate::Error> {
+        let tile_id = tile_id(tile);
+        let mut leaf_directory;
+        let mut directory = self.root_directory.as_slice();
+        for _ in 0..MAX_DIRECTORY_DEPTH {
+            let Some(entry) = find_entry(directory, tile_id) else {
+                return Ok(None);
+            };
+            if entry.run_length > 0 {
+                return self
+                    .read(
+                        self.header.tile_data_offset.saturating_add(entry.offset),
+                        entry.length,
+                    )
+                    .map(Some);
+            }
+            leaf_directory = self.read_directory(
+                self.header
+                    .leaf_directories_offset
+                    .saturating_add(entry.offset),
+                entry.length,
+            )?;
+            directory = leaf_directory.as_slice();
+        }
+        Err(crate::Error::InvalidPmTiles("too many nested directories"))
+    }
+
+    /// The `name` of the JSON metadata
+    fn read_name(&self) -> Result<Option<String>, crate::Error> {
+        if self.header.metadata_length == 0 {
+            return Ok(None);
+        }
+        let metadata =
+            self.decompress(self.read(self.header.metadata_offset, self.header.metadata_length)?)?;
+        let metadata = serde_json::from_slice::<serde_json::Value>(&metadata)?;
+        Ok(metadata
+            .get("name")
+            .and_then(|name| name.as_str())
+            .map(String::from))
+    }
+
+    fn read(&self, offset: u64, length: u64) -> Result<Vec<u8>, crate::Error> {
+        let length =
+            usize::try_from(length).map_err(|_| crate::Error::InvalidPmTiles("invalid length"))?;
+        let mut bytes = vec![0; length];
+        // Every read seeks first, so a panic during another read leaves nothing to clean up
+        let mut file = self
+            .file
+            .lock()
+            .unwrap_or_else(sync::PoisonError::into_inner);
+        file.seek(io::SeekFrom::Start(offset))?;
+        file.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn read_directory(&self, offset: u64, length: u64) -> Result<Vec<Entry>, crate::Error> {
+        parse_directory(&self.decompress(self.read(offset, length)?)?)
+    }
+
+    fn decompress(&self, bytes: Vec<u8>) -> Result<Vec<u8>, crate::Error> {
+        if self.header.internal_compression != COMPRESSION_GZIP {
+            return Ok(bytes);
+        }
+        let mut decompressed = vec![];
+        flate2::read::GzDecoder::new(bytes.as_slice()).read_to_end(&mut decompressed)?;
+        Ok(decompressed)
+    }
+}
+
+/// Directories store the tile IDs, run lengths, lengths and offsets of the entries as separate
+/// columns of varints. Tile IDs are delta encoded, and an offset of `0` means the entry directly
+/// follows the previous one.
+fn parse_directory(bytes: &[u8]) -> Result<Vec<Entry>, crate::Error> {
+    let mut varints = Varints(bytes);
+    let count = usize::try_from(varints.read()?)
+        .map_err(|_| crate::Error::InvalidPmTiles("invalid directory length"))?;
+    // Every entry takes at least four bytes, so don't trust a larger count
+    let mut entries = Vec::with_capacity(count.min(bytes.len() / 4));
+
+    let mut tile_id = 0u64;
+    for _ in 0..count {
+        tile_id = tile_id.wrapping_add(varints.read()?);
+        entries.push(Entry {
+            tile_id,
+            offset: 0,
+            length: 0,
+            run_length: 0,
+        });
+    }
+    for entry in &mut entries {
+        entry.run_length = varints.read()?;
+    }
+    for entry in &mut entries {
+        entry.length = varints.read()?;
+    }
+    let mut next_offset = None;
+    for entry in &mut entries {
+        entry.offset = match (varints.read()?, next_offset) {
+            (0, Some(next_offset)) => next_offset,
+            (offset, _) => offset.saturating_sub(1),
+        };
+        next_offset = Some(entry.offset.saturating_add(entry.length));
+    }
+    Ok(entries)
+}
+
+struct Varints<'a>(&'a [u8]);
+
+impl Varints<'_> {
+    fn read(&mut self) -> Result<u64, crate::Error> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let (byte, rest) = self
+                .0
+                .split_first()
+                .ok_or(crate::Error::InvalidPmTiles("truncated directory"))?;
+            self.0 = rest;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(crate::Error::InvalidPmTiles("invalid varint"))
+    }
+}
+
+/// The entry with the largest tile ID that isn't larger than `tile_id`, if it contains `tile_id`
+fn find_entry(entries: &[Entry], tile_id: u64) -> Option<Entry> {
+    let index = entries
+        .partition_point(|entry| entry.tile_id <= tile_id)
+        .checked_sub(1)?;
+    let entry = *entries.get(index)?;
+    (entry.run_length == 0 || tile_id < entry.tile_id.saturating_add(entry.run_length))
+        .then_some(entry)
+}
+
+/// Tiles are numbered along a Hilbert curve, starting after the tiles of all lower zoom levels.
+fn tile_id(tile: TileId) -> u64 {
+    let z = u32::from(tile.z.min(31));
+    let tiles_before_zoom_level = ((1u64 << (2 * z)) - 1) / 3;
+    let n = 1u64 << z;
+    let (mut x, mut y) = (u64::from(tile.x), u64::from(tile.y));
+    let mut distance = 0;
+    let mut s = n / 2;
+    while s > 0 {
+        let rx = u64::from(x & s > 0);
+        let ry = u64::from(y & s > 0);
+        distance += s * s * ((3 * rx) ^ ry);
+        if ry == 0 {
+            if rx == 1 {
+                x = n - 1 - x;
+                y = n - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    tiles_before_zoom_level + distance
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    bytes
+        .get(offset..offset + 8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map_or(0, u64::from_le_bytes)
+}
+
+fn tile_type_name(tile_type: u8) -> &'static str {
+    match tile_type {
+        2 => "PNG",
+        3 => "JPEG",
+        4 => "WebP",
+        5 => "AVIF",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tile_ids_follow_hilbert_curve() {
+        // From the PMTiles specification
+        for (z, x, y, expected) in [
+            (0, 0, 0, 0),
+            (1, 0, 0, 1),
+            (1, 0, 1, 2),
+            (1, 1, 1, 3),
+            (1, 1, 0, 4),
+            (2, 0, 0, 5),
+            (3, 0, 0, 21),
+            (12, 3423, 1763, 19_078_479),
+        ] {
+            assert_eq!(tile_id(TileId { z, x, y }), expected, "tile {z}/{x}/{y}");
+        }
+    }
+
+    #[test]
+    fn varints_span_several_bytes() -> Result<(), crate::Error> {
+        let mut varints = Varints(&[0xAC, 0x02, 0x01]);
+        assert_eq!(varints.read()?, 300);
+        assert_eq!(varints.read()?, 1);
+        assert!(varints.read().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn varints_longer_than_64_bits_are_invalid() {
+        assert!(Varints(&[0xFF; 11]).read().is_err());
+    }
+
+    #[test]
+    fn directory_entries_are_decoded_column_by_column() -> Result<(), crate::Error> {
+        let entries = parse_directory(&[
+            3, // Entries
+            0, 1, 4, // Tile ID deltas
+            1, 1, 0, // Run lengths, the last entry is a leaf directory
+            10, 20, 30, // Lengths
+            1, 0, 101, // Offsets plus one, `0` follows the previous entry
+        ])?;
+        let entries = entries
+            .iter()
+            .map(|entry| (entry.tile_id, entry.offset, entry.length, entry.run_length))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            vec![(0, 0, 10, 1), (1, 10, 20, 1), (5, 100, 30, 0)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn entries_are_found_by_tile_id() -> Result<(), crate::Error> {
+        let entries = parse_directory(&[3, 0, 1, 4, 1, 1, 0, 10, 20, 30, 1, 0, 101])?;
+        let found = |tile_id| find_entry(&entries, tile_id).map(|entry| entry.tile_id);
+        assert_eq!(found(0), Some(0));
+        assert_eq!(found(1), Some(1));
+        // Past the run of the entry for tile 1
+        assert_eq!(found(2), None);
+        // Leaf directories hold every tile from theirs on
+        assert_eq!(found(7), Some(5));
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+use geozero::{ColumnValue, FeatureProcessor, GeomProcessor, PropertyProcessor};
+use std::mem;
+
+/// A geozero processor that builds one `geo_features::Feature` for every feature it is given,
+/// including the feature's properties.
+#[derive(Default)]
+pub(crate) struct FeatureCollectionProcessor {
+    geo_writer: geozero::geo_types::GeoWriter,
+    properties: geo_features::Properties,
+    features: Vec<geo_features::Feature>,
+}
+
+impl FeatureCollectionProcessor {
+    pub(crate) fn new() -> Self {
+        FeatureCollectionProcessor::default()
+    }
+
+    /// Wrap everything processed since the last feature boundary into a feature. Used for
+    /// sources that only emit geometry events.
+    pub(crate) fn finish_feature(&mut self) -> geozero::error::Result<()> {
+        self.feature_end(0)
+    }
+
+    pub(crate) fn into_feature_collection(
+        self,
+    ) -> Result<geo_features::FeatureCollection, crate::Error> {
+        let features = self.features;
+        if features.iter().all(|feature| feature.geometry.is_none()) {
+            return Err(crate::Error::NoGeometry);
+        }
+        Ok(geo_features::FeatureCollection::from_features(features))
+    }
+}
+
+pub(crate) fn column_value_to_value(value: &ColumnValue) -> geo_features::Value {
+    match *value {
+        ColumnValue::Byte(n) => geo_features::Value::Number(f64::from(n)),
+        ColumnValue::UByte(n) => geo_features::Value::Number(f64::from(n)),
+        ColumnValue::Bool(b) => geo_features::Value::Boolean(b),
+        ColumnValue::Short(n) => geo_features::Value::Number(f64::from(n)),
+        ColumnValue::UShort(n) => geo_features::Value::Number(f64::from(n)),
+        ColumnValue::Int(n) => geo_features::Value::Number(f64::from(n)),
+        ColumnValue::UInt(n) => geo_features::Value::Number(f64::from(n)),
+        ColumnValue::Long(n) => geo_features::Value::Number(n as f64),
+        ColumnValue::ULong(n) => geo_features::Value::Number(n as f64),
+        ColumnValue::Float(n) => geo_features::Value::Number(f64::from(n)),
+        ColumnValue::Double(n) => geo_features::Value::Number(n),
+        ColumnValue::String(s) | ColumnValue::Json(s) | ColumnValue::DateTime(s) => {
+            geo_features::Value::String(s.to_owned())
+        }
+        ColumnValue::Binary(_) => geo_features::Value::Null,
+    }
+}
+
+impl PropertyProcessor for FeatureCollectionProcessor {
+    fn property(
+        &mut self,
+        _idx: usize,
+        name: &str,
+        value: &ColumnValue,
+    ) -> geozero::error::Result<bool> {
+        self.properties
+            .insert(name.to_owned(), column_value_to_value(value));
+        // Don't abort processing
+        Ok(false)
+    }
+}
+
+impl FeatureProcessor for FeatureCollectionProcessor {
+    fn feature_begin(&mut self, _idx: u64) -> geozero::error::Result<()> {
+        self.properties = geo_features::Properties::default();
+        Ok(())
+    }
+
+    fn feature_end(&mut self, _idx: u64) -> geozero::error::Result<()> {
+        let mut builder =
+            geo_features::FeatureBuilder::new().with_properties(mem::take(&mut self.properties));
+        if let Some(geometry) = self.geo_writer.take_geometry() {
+            builder = builder.with_geometry(geometry);
+        }
+        self.features.push(builder.build());
+        Ok(())
+    }
+}
+
+// Geometry events are forwarded to geozero's own `geo` writer, which we drain at the end of every
+// feature.
+impl GeomProcessor for FeatureCollectionProcessor {
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.xy(x, y, idx)
+    }
+
+    fn point_begin(&mut self, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.point_begin(idx)
+    }
+
+    fn point_end(&mut self, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.point_end(idx)
+    }
+
+    fn multipoint_begin(&mut self, size: usize, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.multipoint_begin(size, idx)
+    }
+
+    fn multipoint_end(&mut self, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.multipoint_end(idx)
+    }
+
+    fn linestring_begin(
+        &mut self,
+        tagged: bool,
+        size: usize,
+        idx: usize,
+    ) -> geozero::error::Result<()> {
+        self.geo_writer.linestring_begin(tagged, size, idx)
+    }
+
+    fn linestring_end(&mut self, tagged: bool, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.linestring_end(tagged, idx)
+    }
+
+    fn multilinestring_begin(&mut self, size: usize, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.multilinestring_begin(size, idx)
+    }
+
+    fn multilinestring_end(&mut self, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.multilinestring_end(idx)
+    }
+
+    fn polygon_begin(
+        &mut self,
+        tagged: bool,
+        size: usize,
+        idx: usize,
+    ) -> geozero::error::Result<()> {
+        self.geo_writer.polygon_begin(tagged, size, idx)
+    }
+
+    fn polygon_end(&mut self, tagged: bool, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.polygon_end(tagged, idx)
+    }
+
+    fn multipolygon_begin(&mut self, size: usize, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.multipolygon_begin(size, idx)
+    }
+
+    fn multipolygon_end(&mut self, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.multipolygon_end(idx)
+    }
+
+    fn geometrycollection_begin(&mut self, size: usize, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.geometrycollection_begin(size, idx)
+    }
+
+    fn geometrycollection_end(&mut self, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.geometrycollection_end(idx)
+    }
+}
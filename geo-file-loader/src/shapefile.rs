@@ -1,5 +1,10 @@
-use std::io;
+use std::{collections, io, io::Read, str};
 
+/// Where the language driver ID is in the header of a DBF file
+const DBF_LANGUAGE_DRIVER_OFFSET: usize = 29;
+
+/// A Shapefile, either as a lone `.shp` file or as a zip archive containing the `.shp` and its
+/// companion files. Zip archives of several Shapefiles are loaded as one layer per Shapefile.
 pub struct ShapefileSource {
     pub bytes: bytes::Bytes,
 }
@@ -9,12 +14,284 @@ impl crate::FileLoader for ShapefileSource {
         ShapefileSource { bytes }
     }
 
-    fn load(self) -> Result<geo_features::FeatureCollection, crate::Error> {
-        let mut bytes_cursor = io::Cursor::new(&self.bytes);
-        let shapefile_reader = geozero_shp::Reader::new(&mut bytes_cursor)?;
-        let mut geo_writer = geozero::geo_types::GeoWriter::new();
-        for _ in shapefile_reader.iter_geometries(&mut geo_writer) {}
-        let geometry = geo_writer.take_geometry().ok_or(crate::Error::NoGeometry)?;
-        Ok(geo_features::FeatureCollection::from_geometry(geometry))
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        if !crate::is_zip(&self.bytes) {
+            return ShapefileParts {
+                shp: Some(self.bytes),
+                ..Default::default()
+            }
+            .load();
+        }
+        let mut shapefiles = ShapefileParts::from_zip(&self.bytes)?;
+        if shapefiles.len() <= 1 {
+            return shapefiles
+                .pop()
+                .map(|(_, parts)| parts)
+                .unwrap_or_default()
+                .load();
+        }
+        let mut layers = vec![];
+        for (name, parts) in shapefiles {
+            for mut layer in parts.load()?.layers {
+                layer.name = Some(name.clone());
+                layers.push(layer);
+            }
+        }
+        Ok(crate::LoadedFile { layers })
+    }
+}
+
+/// The files that make up a Shapefile. Only the `.shp` file is required.
+#[derive(Default)]
+pub struct ShapefileParts {
+    pub shp: Option<bytes::Bytes>,
+    pub shx: Option<bytes::Bytes>,
+    pub dbf: Option<bytes::Bytes>,
+    pub prj: Option<bytes::Bytes>,
+    pub cpg: Option<bytes::Bytes>,
+}
+
+impl ShapefileParts {
+    /// Sort files into their Shapefile component by file extension. Files with an unrelated
+    /// extension are ignored.
+    pub fn from_files(files: impl IntoIterator<Item = crate::InputFile>) -> Self {
+        let mut parts = ShapefileParts::default();
+        for file in files {
+            parts.insert(&file.name, file.bytes);
+        }
+        parts
+    }
+
+    /// The Shapefiles in a zip archive, named after their `.shp` file. Companion files are
+    /// matched to the `.shp` file with the same path apart from the extension.
+    fn from_zip(bytes: &bytes::Bytes) -> Result<Vec<(String, Self)>, crate::Error> {
+        let mut archive = zip::ZipArchive::new(io::Cursor::new(bytes))?;
+        let mut shapefiles = Vec::<(String, ShapefileParts)>::new();
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i)?;
+            if file.is_dir() {
+                continue;
+            }
+            let name = file.name().to_owned();
+            let mut contents = Vec::with_capacity(file.size() as usize);
+            file.read_to_end(&mut contents)?;
+            let stem = name
+                .rsplit_once('.')
+                .map_or(name.as_str(), |(stem, _)| stem);
+            match shapefiles
+                .iter_mut()
+                .find(|(other_stem, _)| other_stem.eq_ignore_ascii_case(stem))
+            {
+                Some((_, parts)) => parts.insert(&name, contents.into()),
+                None => {
+                    let mut parts = ShapefileParts::default();
+                    parts.insert(&name, contents.into());
+                    shapefiles.push((stem.to_owned(), parts));
+                }
+            }
+        }
+        // Files without a `.shp`, e.g. a README, aren't Shapefiles
+        shapefiles.retain(|(_, parts)| parts.shp.is_some());
+        Ok(shapefiles
+            .into_iter()
+            .map(|(stem, parts)| {
+                let name = stem.rsplit('/').next().unwrap_or_default().to_owned();
+                (name, parts)
+            })
+            .collect())
+    }
+
+    fn insert(&mut self, file_name: &str, bytes: bytes::Bytes) {
+        let Some((_, extension)) = file_name.rsplit_once('.') else {
+            return;
+        };
+        let slot = match extension.to_ascii_lowercase().as_str() {
+            "shp" => &mut self.shp,
+            "shx" => &mut self.shx,
+            "dbf" => &mut self.dbf,
+            "prj" => &mut self.prj,
+            "cpg" => &mut self.cpg,
+            _ => return,
+        };
+        *slot = Some(bytes);
+    }
+
+    pub fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let shp = self.shp.ok_or(crate::Error::MissingShp)?;
+        let mut shapefile_reader = geozero_shp::Reader::new(io::Cursor::new(&shp))?;
+        if let Some(ref shx) = self.shx {
+            shapefile_reader.add_index_source(io::Cursor::new(shx))?;
+        }
+
+        let mut processor = crate::processor::FeatureCollectionProcessor::new();
+        let feature_collection = match self.dbf {
+            // geozero-shp always decodes text fields as UTF-8, so the records are decoded by
+            // `dbase` with the DBF's own encoding instead, and matched to the shapes by order
+            Some(ref dbf) => {
+                let records = read_dbf_records(dbf, dbf_encoding(dbf, self.cpg.as_deref()))?;
+                for result in shapefile_reader.iter_geometries(&mut processor) {
+                    result?;
+                    processor.finish_feature()?;
+                }
+                let mut feature_collection = processor.into_feature_collection()?;
+                for (feature, properties) in feature_collection.features.iter_mut().zip(records) {
+                    feature.properties = properties;
+                }
+                feature_collection
+            }
+            None => {
+                for result in shapefile_reader.iter_geometries(&mut processor) {
+                    result?;
+                }
+                // Without a `.dbf` file geozero doesn't emit feature boundaries, so all of the
+                // shapes end up in a single feature.
+                processor.finish_feature()?;
+                processor.into_feature_collection()?
+            }
+        };
+
+        let crs = match self.prj.as_deref().map(str::from_utf8) {
+            Some(Ok(prj)) => Some(crate::DetectedCrs::Wkt(prj.trim().to_owned())),
+            Some(Err(e)) => {
+                bevy_log::warn!("Ignoring the Shapefile's .prj, which isn't UTF-8: {}", e);
+                None
+            }
+            None => None,
+        };
+
+        Ok(crate::LoadedLayer {
+            name: None,
+            feature_collection,
+            crs,
+        }
+        .into())
     }
 }
+
+fn read_dbf_records(
+    dbf: &bytes::Bytes,
+    encoding: &'static encoding_rs::Encoding,
+) -> Result<Vec<geo_features::Properties>, crate::Error> {
+    let mut reader = dbase::Reader::new_with_encoding(
+        io::Cursor::new(dbf),
+        dbase::encoding::EncodingRs::from(encoding),
+    )?;
+    reader
+        .iter_records()
+        .map(|record| {
+            let record: collections::HashMap<String, dbase::FieldValue> = record?.into();
+            Ok(record
+                .into_iter()
+                .map(|(name, value)| (name, field_value_to_value(value)))
+                .collect())
+        })
+        .collect()
+}
+
+fn field_value_to_value(field_value: dbase::FieldValue) -> geo_features::Value {
+    match field_value {
+        dbase::FieldValue::Character(Some(s)) | dbase::FieldValue::Memo(s) => {
+            geo_features::Value::String(s)
+        }
+        dbase::FieldValue::Numeric(Some(n))
+        | dbase::FieldValue::Double(n)
+        | dbase::FieldValue::Currency(n) => geo_features::Value::Number(n),
+        dbase::FieldValue::Float(Some(n)) => geo_features::Value::Number(f64::from(n)),
+        dbase::FieldValue::Integer(n) => geo_features::Value::Number(f64::from(n)),
+        dbase::FieldValue::Logical(Some(b)) => geo_features::Value::Boolean(b),
+        dbase::FieldValue::Date(Some(date)) => geo_features::Value::String(format!(
+            "{:04}-{:02}-{:02}",
+            date.year(),
+            date.month(),
+            date.day()
+        )),
+        dbase::FieldValue::DateTime(date_time) => {
+            let (date, time) = (date_time.date(), date_time.time());
+            geo_features::Value::String(format!(
+                "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+                date.year(),
+                date.month(),
+                date.day(),
+                time.hours(),
+                time.minutes(),
+                time.seconds()
+            ))
+        }
+        dbase::FieldValue::Character(None)
+        | dbase::FieldValue::Numeric(None)
+        | dbase::FieldValue::Float(None)
+        | dbase::FieldValue::Logical(None)
+        | dbase::FieldValue::Date(None) => geo_features::Value::Null,
+    }
+}
+
+/// The text encoding of a DBF file, from its `.cpg` file, or else from the language driver ID in
+/// its header. Windows-1252 is the most common encoding of DBF files that declare neither.
+fn dbf_encoding(dbf: &[u8], cpg: Option<&[u8]>) -> &'static encoding_rs::Encoding {
+    cpg.and_then(encoding_from_cpg)
+        .or_else(|| {
+            dbf.get(DBF_LANGUAGE_DRIVER_OFFSET)
+                .and_then(|id| encoding_from_ldid(*id))
+        })
+        .unwrap_or(encoding_rs::WINDOWS_1252)
+}
+
+/// Map the contents of a `.cpg` file to a text encoding.
+///
+/// `.cpg` files either contain a WHATWG encoding label (e.g. `UTF-8`) or a bare Windows/ANSI code
+/// page number (e.g. `1252` or `ANSI 1251`).
+fn encoding_from_cpg(cpg: &[u8]) -> Option<&'static encoding_rs::Encoding> {
+    let label = str::from_utf8(cpg).ok()?.trim();
+    if let Some(encoding) = encoding_rs::Encoding::for_label(label.as_bytes()) {
+        return Some(encoding);
+    }
+    encoding_from_code_page(
+        label
+            .trim_start_matches(|c: char| !c.is_ascii_digit())
+            .trim(),
+    )
+}
+
+/// Map the language driver ID of a DBF file to a text encoding, by the code page it stands for.
+/// DOS code pages other than 866 aren't supported by `encoding_rs`.
+fn encoding_from_ldid(ldid: u8) -> Option<&'static encoding_rs::Encoding> {
+    let code_page = match ldid {
+        0x03 | 0x57 | 0x58 | 0x59 => "1252",
+        0x13 | 0x7B => "932",
+        0x26 | 0x65 => "866",
+        0x4D | 0x7A => "936",
+        0x4E | 0x79 => "949",
+        0x4F | 0x78 => "950",
+        0x7C => "874",
+        0x7D => "1255",
+        0x7E => "1256",
+        0xC8 => "1250",
+        0xC9 => "1251",
+        0xCA => "1254",
+        0xCB => "1253",
+        0xCC => "1257",
+        _ => return None,
+    };
+    encoding_from_code_page(code_page)
+}
+
+fn encoding_from_code_page(code_page: &str) -> Option<&'static encoding_rs::Encoding> {
+    let label = match code_page {
+        "65001" => "utf-8",
+        "866" => "ibm866",
+        "874" => "windows-874",
+        "932" => "shift_jis",
+        "936" => "gbk",
+        "949" => "euc-kr",
+        "950" => "big5",
+        "1250" | "1251" | "1252" | "1253" | "1254" | "1255" | "1256" | "1257" | "1258" => {
+            return encoding_rs::Encoding::for_label(format!("windows-{code_page}").as_bytes())
+        }
+        _ => {
+            // `88591`, `88592`, … are ISO-8859 parts
+            let part = code_page.strip_prefix("8859")?;
+            return encoding_rs::Encoding::for_label(format!("iso-8859-{part}").as_bytes());
+        }
+    };
+    encoding_rs::Encoding::for_label(label.as_bytes())
+}
@@ -0,0 +1,126 @@
+use crate::{pmtiles::PmTiles, TileId};
+use std::{collections::HashMap, fs, io::Read, path, sync};
+
+/// A local archive of vector tiles, which are read and decoded one at a time as they're needed.
+pub struct Tileset {
+    pub name: String,
+    pub min_zoom: u8,
+    pub max_zoom: u8,
+    archive: Archive,
+}
+
+enum Archive {
+    MbTiles(MbTiles),
+    PmTiles(PmTiles),
+}
+
+impl Tileset {
+    /// Open an `.mbtiles` or `.pmtiles` file, told apart by their contents.
+    pub fn open(path: &path::Path) -> Result<Self, crate::Error> {
+        let mut magic = [0; crate::pmtiles::PMTILES_MAGIC.len()];
+        fs::File::open(path)?.read_exact(&mut magic)?;
+        let file_stem = || {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+
+        if magic == crate::pmtiles::PMTILES_MAGIC {
+            let pm_tiles = PmTiles::open(path)?;
+            Ok(Tileset {
+                name: pm_tiles.name.clone().unwrap_or_else(file_stem),
+                min_zoom: pm_tiles.min_zoom(),
+                max_zoom: pm_tiles.max_zoom(),
+                archive: Archive::PmTiles(pm_tiles),
+            })
+        } else {
+            let mb_tiles = MbTiles::open(path)?;
+            Ok(Tileset {
+                name: mb_tiles.name.clone().unwrap_or_else(file_stem),
+                min_zoom: mb_tiles.min_zoom,
+                max_zoom: mb_tiles.max_zoom,
+                archive: Archive::MbTiles(mb_tiles),
+            })
+        }
+    }
+
+    /// The layers of a tile, in Web Mercator (EPSG:3857). Tiles missing from the archive have no
+    /// layers.
+    pub fn load_tile(&self, tile: TileId) -> Result<Vec<crate::LoadedLayer>, crate::Error> {
+        let bytes = match self.archive {
+            Archive::MbTiles(ref mb_tiles) => mb_tiles.read_tile(tile)?,
+            Archive::PmTiles(ref pm_tiles) => pm_tiles.read_tile(tile)?,
+        };
+        match bytes {
+            Some(bytes) => crate::mvt::decode_tile(&bytes, tile),
+            None => Ok(vec![]),
+        }
+    }
+}
+
+/// An MBTiles SQLite database of vector tiles
+struct MbTiles {
+    connection: sync::Mutex<rusqlite::Connection>,
+    name: Option<String>,
+    min_zoom: u8,
+    max_zoom: u8,
+}
+
+impl MbTiles {
+    fn open(path: &path::Path) -> Result<Self, crate::Error> {
+        let connection = rusqlite::Connection::open_with_flags(
+            path,
+            rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+        )?;
+        let metadata = connection
+            .prepare("SELECT name, value FROM metadata")?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<HashMap<String, String>, _>>()?;
+
+        // Raster tilesets use `png`, `jpg` or `webp`
+        if let Some(format) = metadata.get("format").filter(|format| *format != "pbf") {
+            return Err(crate::Error::UnsupportedTileFormat(format.clone()));
+        }
+
+        // The zoom levels are optional metadata, so fall back to the zoom levels of the tiles
+        let zoom_levels = |metadata_key: &str, aggregate: &str| match metadata
+            .get(metadata_key)
+            .and_then(|zoom| zoom.parse().ok())
+        {
+            Some(zoom) => Ok(zoom),
+            None => connection.query_row(
+                &format!("SELECT {aggregate}(zoom_level) FROM tiles"),
+                [],
+                |row| row.get::<_, Option<u8>>(0).map(Option::unwrap_or_default),
+            ),
+        };
+        let min_zoom = zoom_levels("minzoom", "MIN")?;
+        let max_zoom = zoom_levels("maxzoom", "MAX")?;
+
+        Ok(MbTiles {
+            name: metadata.get("name").cloned(),
+            connection: sync::Mutex::new(connection),
+            min_zoom,
+            max_zoom,
+        })
+    }
+
+    fn read_tile(&self, tile: TileId) -> Result<Option<Vec<u8>>, crate::Error> {
+        use rusqlite::OptionalExtension;
+
+        // MBTiles uses the TMS tiling scheme, where rows are numbered from the bottom
+        let max_row = (1u32 << tile.z.min(31)) - 1;
+        let row = max_row.saturating_sub(tile.y);
+        let connection = self
+            .connection
+            .lock()
+            .unwrap_or_else(sync::PoisonError::into_inner);
+        Ok(connection
+            .query_row(
+                "SELECT tile_data FROM tiles WHERE zoom_level = ?1 AND tile_column = ?2 AND tile_row = ?3",
+                (tile.z, tile.x, row),
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+}
@@ -0,0 +1,274 @@
+use std::collections;
+
+pub struct TopoJsonSource {
+    pub bytes: bytes::Bytes,
+}
+
+impl crate::FileLoader for TopoJsonSource {
+    fn from_bytes(bytes: bytes::Bytes) -> Self {
+        TopoJsonSource { bytes }
+    }
+
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let topology = serde_json::from_slice::<Topology>(&self.bytes)?;
+        if topology.type_ != "Topology" {
+            return Err(crate::Error::InvalidTopoJson("not a Topology"));
+        }
+        // Every arc is decoded once and shared by all the geometries referencing it, so borders
+        // between neighbouring polygons stay identical.
+        let arcs = decode_arcs(topology.arcs, topology.transform.as_ref())?;
+
+        let mut layers = Vec::with_capacity(topology.objects.len());
+        for (name, object) in topology.objects {
+            let objects = match object.kind {
+                GeometryKind::GeometryCollection { geometries } => geometries,
+                _ => vec![object],
+            };
+            let features = objects
+                .into_iter()
+                .map(|object| feature_from_object(object, &arcs, topology.transform.as_ref()))
+                .collect::<Result<Vec<_>, _>>()?;
+            layers.push(crate::LoadedLayer {
+                name: Some(name),
+                feature_collection: geo_features::FeatureCollection::from_features(features),
+                crs: None,
+            });
+        }
+
+        if layers
+            .iter()
+            .flat_map(|layer| &layer.feature_collection.features)
+            .all(|feature| feature.geometry.is_none())
+        {
+            return Err(crate::Error::NoGeometry);
+        }
+        Ok(crate::LoadedFile { layers })
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct Topology {
+    #[serde(rename = "type")]
+    type_: String,
+    transform: Option<Transform>,
+    arcs: Vec<Vec<Vec<f64>>>,
+    objects: collections::BTreeMap<String, Object>,
+}
+
+/// Quantized coordinates are multiplied by `scale` then offset by `translate`
+#[derive(serde::Deserialize)]
+struct Transform {
+    scale: [f64; 2],
+    translate: [f64; 2],
+}
+
+impl Transform {
+    fn apply(&self, x: f64, y: f64) -> geo::Coord {
+        let [scale_x, scale_y] = self.scale;
+        let [translate_x, translate_y] = self.translate;
+        geo::Coord {
+            x: x * scale_x + translate_x,
+            y: y * scale_y + translate_y,
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct Object {
+    #[serde(flatten, deserialize_with = "deserialize_geometry_kind")]
+    kind: GeometryKind,
+    properties: Option<serde_json::Map<String, serde_json::Value>>,
+    id: Option<serde_json::Value>,
+}
+
+/// Lines and polygons reference arcs by index. A negative index `i` refers to arc `!i`, reversed.
+#[derive(serde::Deserialize)]
+#[serde(tag = "type")]
+enum GeometryKind {
+    Point {
+        coordinates: Vec<f64>,
+    },
+    MultiPoint {
+        coordinates: Vec<Vec<f64>>,
+    },
+    LineString {
+        arcs: Vec<i64>,
+    },
+    MultiLineString {
+        arcs: Vec<Vec<i64>>,
+    },
+    Polygon {
+        arcs: Vec<Vec<i64>>,
+    },
+    MultiPolygon {
+        arcs: Vec<Vec<Vec<i64>>>,
+    },
+    GeometryCollection {
+        geometries: Vec<Object>,
+    },
+    /// `"type": null`, an object without a geometry
+    #[serde(skip_deserializing)]
+    Null,
+}
+
+/// Internally tagged enums need a string tag, so objects whose `type` is `null` are picked out
+/// first.
+fn deserialize_geometry_kind<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<GeometryKind, D::Error> {
+    let object = <serde_json::Map<String, serde_json::Value> as serde::Deserialize>::deserialize(
+        deserializer,
+    )?;
+    if object.get("type").is_some_and(serde_json::Value::is_null) {
+        return Ok(GeometryKind::Null);
+    }
+    serde_json::from_value(serde_json::Value::Object(object)).map_err(serde::de::Error::custom)
+}
+
+fn decode_arcs(
+    arcs: Vec<Vec<Vec<f64>>>,
+    transform: Option<&Transform>,
+) -> Result<Vec<Vec<geo::Coord>>, crate::Error> {
+    arcs.into_iter()
+        .map(|arc| {
+            // Positions of quantized arcs are deltas from the previous position
+            let (mut x, mut y) = (0., 0.);
+            arc.iter()
+                .map(|position| {
+                    let (dx, dy) = read_position(position)?;
+                    Ok(match transform {
+                        Some(transform) => {
+                            x += dx;
+                            y += dy;
+                            transform.apply(x, y)
+                        }
+                        None => geo::Coord { x: dx, y: dy },
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn read_position(position: &[f64]) -> Result<(f64, f64), crate::Error> {
+    match position {
+        [x, y, ..] => Ok((*x, *y)),
+        _ => Err(crate::Error::InvalidTopoJson(
+            "position with fewer than two values",
+        )),
+    }
+}
+
+fn decode_point(
+    position: &[f64],
+    transform: Option<&Transform>,
+) -> Result<geo::Point, crate::Error> {
+    let (x, y) = read_position(position)?;
+    Ok(match transform {
+        Some(transform) => transform.apply(x, y).into(),
+        None => geo::Point::new(x, y),
+    })
+}
+
+/// Join the referenced arcs into a single line. Consecutive arcs share their end points, so the
+/// first position of every arc after the first one is dropped.
+fn decode_line(arcs: &[Vec<geo::Coord>], indices: &[i64]) -> Result<geo::LineString, crate::Error> {
+    let mut coords = vec![];
+    for &index in indices {
+        let (arc_index, reversed) = if index < 0 {
+            (!index, true)
+        } else {
+            (index, false)
+        };
+        let arc = usize::try_from(arc_index)
+            .ok()
+            .and_then(|arc_index| arcs.get(arc_index))
+            .ok_or(crate::Error::InvalidTopoJson("arc index out of bounds"))?;
+        let skip = usize::from(!coords.is_empty());
+        if reversed {
+            coords.extend(arc.iter().rev().skip(skip));
+        } else {
+            coords.extend(arc.iter().skip(skip));
+        }
+    }
+    Ok(geo::LineString::new(coords))
+}
+
+fn decode_polygon(
+    arcs: &[Vec<geo::Coord>],
+    rings: &[Vec<i64>],
+) -> Result<geo::Polygon, crate::Error> {
+    let mut rings = rings.iter().map(|ring| decode_line(arcs, ring));
+    let exterior = rings
+        .next()
+        .transpose()?
+        .unwrap_or_else(|| geo::LineString::new(vec![]));
+    let interiors = rings.collect::<Result<Vec<_>, _>>()?;
+    Ok(geo::Polygon::new(exterior, interiors))
+}
+
+/// `None` for objects without a geometry
+fn decode_geometry(
+    kind: GeometryKind,
+    arcs: &[Vec<geo::Coord>],
+    transform: Option<&Transform>,
+) -> Result<Option<geo::Geometry>, crate::Error> {
+    Ok(Some(match kind {
+        GeometryKind::Point { coordinates } => decode_point(&coordinates, transform)?.into(),
+        GeometryKind::MultiPoint { coordinates } => geo::MultiPoint::new(
+            coordinates
+                .iter()
+                .map(|position| decode_point(position, transform))
+                .collect::<Result<_, _>>()?,
+        )
+        .into(),
+        GeometryKind::LineString { arcs: indices } => decode_line(arcs, &indices)?.into(),
+        GeometryKind::MultiLineString { arcs: lines } => geo::MultiLineString::new(
+            lines
+                .iter()
+                .map(|indices| decode_line(arcs, indices))
+                .collect::<Result<_, _>>()?,
+        )
+        .into(),
+        GeometryKind::Polygon { arcs: rings } => decode_polygon(arcs, &rings)?.into(),
+        GeometryKind::MultiPolygon { arcs: polygons } => geo::MultiPolygon::new(
+            polygons
+                .iter()
+                .map(|rings| decode_polygon(arcs, rings))
+                .collect::<Result<_, _>>()?,
+        )
+        .into(),
+        GeometryKind::GeometryCollection { geometries } => geo::GeometryCollection::new_from(
+            geometries
+                .into_iter()
+                .filter_map(|object| decode_geometry(object.kind, arcs, transform).transpose())
+                .collect::<Result<_, _>>()?,
+        )
+        .into(),
+        GeometryKind::Null => return Ok(None),
+    }))
+}
+
+fn feature_from_object(
+    object: Object,
+    arcs: &[Vec<geo::Coord>],
+    transform: Option<&Transform>,
+) -> Result<geo_features::Feature, crate::Error> {
+    let mut properties = object
+        .properties
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(key, value)| (key, crate::geojson::json_value_to_value(value)))
+        .collect::<geo_features::Properties>();
+    // Like GeoJSON, the identifier lives outside of `properties`, and is kept as `@id`
+    if let Some(id) = object.id {
+        properties
+            .entry("@id".into())
+            .or_insert_with(|| crate::geojson::json_value_to_value(id));
+    }
+    let mut builder = geo_features::FeatureBuilder::new().with_properties(properties);
+    if let Some(geometry) = decode_geometry(object.kind, arcs, transform)? {
+        builder = builder.with_geometry(geometry);
+    }
+    Ok(builder.build())
+}
@@ -0,0 +1,90 @@
+use geozero::ToGeo;
+
+/// EWKB geometry type flag marking the presence of an SRID after the type
+const EWKB_SRID_FLAG: u32 = 0x2000_0000;
+
+/// WKB or EWKB, either binary or hex encoded. Hex input may contain several geometries separated by
+/// whitespace, e.g. rows copied from a PostGIS query result.
+pub struct WkbSource {
+    pub bytes: bytes::Bytes,
+}
+
+impl crate::FileLoader for WkbSource {
+    fn from_bytes(bytes: bytes::Bytes) -> Self {
+        WkbSource { bytes }
+    }
+
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let geometries = match hex_geometries(&self.bytes) {
+            Some(hex_geometries) => hex_geometries
+                .map(hex::decode)
+                .collect::<Result<Vec<_>, _>>()?,
+            None => vec![self.bytes.to_vec()],
+        };
+        let crs = geometries
+            .first()
+            .and_then(|wkb| ewkb_srid(wkb))
+            .map(crate::DetectedCrs::EpsgCode);
+
+        let mut features = Vec::with_capacity(geometries.len());
+        for wkb in geometries {
+            // EWKB is a superset of WKB, so this handles both
+            let geometry = geozero::wkb::Ewkb(wkb).to_geo()?;
+            features.push(
+                geo_features::FeatureBuilder::new()
+                    .with_geometry(geometry)
+                    .build(),
+            );
+        }
+        if features.is_empty() {
+            return Err(crate::Error::NoGeometry);
+        }
+
+        Ok(crate::LoadedLayer {
+            name: None,
+            feature_collection: geo_features::FeatureCollection::from_features(features),
+            crs,
+        }
+        .into())
+    }
+}
+
+/// The SRID of binary or hex encoded EWKB, if it has one. For hex input with several geometries,
+/// only the first one is read.
+pub fn wkb_srid(bytes: &[u8]) -> Option<u16> {
+    match hex_geometries(bytes) {
+        Some(mut hex_geometries) => ewkb_srid(&hex::decode(hex_geometries.next()?).ok()?),
+        None => ewkb_srid(bytes),
+    }
+}
+
+/// Split hex input into one hex string per geometry. Returns `None` if the input isn't hex.
+fn hex_geometries(bytes: &[u8]) -> Option<impl Iterator<Item = &[u8]>> {
+    let is_hex = bytes
+        .iter()
+        .all(|b| b.is_ascii_hexdigit() || b.is_ascii_whitespace() || *b == b'\\' || *b == b'x');
+    let mut hex_geometries = bytes
+        .split(u8::is_ascii_whitespace)
+        .filter(|hex_geometry| !hex_geometry.is_empty())
+        // PostgreSQL outputs `bytea` values with a `\x` prefix
+        .map(|hex_geometry| hex_geometry.strip_prefix(b"\\x").unwrap_or(hex_geometry))
+        .peekable();
+    (is_hex && hex_geometries.peek().is_some()).then_some(hex_geometries)
+}
+
+fn ewkb_srid(ewkb: &[u8]) -> Option<u16> {
+    let read_u32 = |offset: usize| {
+        let bytes = <[u8; 4]>::try_from(ewkb.get(offset..offset + 4)?).ok()?;
+        // The first byte is the byte order: 0 for big endian, 1 for little endian
+        match ewkb.first()? {
+            0 => Some(u32::from_be_bytes(bytes)),
+            1 => Some(u32::from_le_bytes(bytes)),
+            _ => None,
+        }
+    };
+    let geometry_type = read_u32(1)?;
+    if geometry_type & EWKB_SRID_FLAG == 0 {
+        return None;
+    }
+    u16::try_from(read_u32(5)?).ok()
+}
@@ -0,0 +1,93 @@
+use std::str;
+
+/// An XML element, with namespace prefixes removed from its name and attribute names.
+#[derive(Default)]
+pub(crate) struct XmlElement {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<XmlElement>,
+    pub text: String,
+}
+
+impl XmlElement {
+    fn new(start: &quick_xml::events::BytesStart) -> Result<Self, crate::Error> {
+        let mut attributes = vec![];
+        for attribute in start.attributes() {
+            let attribute = attribute.map_err(quick_xml::Error::from)?;
+            attributes.push((
+                str::from_utf8(attribute.key.local_name().as_ref())?.to_owned(),
+                attribute.unescape_value()?.into_owned(),
+            ));
+        }
+        Ok(XmlElement {
+            name: str::from_utf8(start.local_name().as_ref())?.to_owned(),
+            attributes,
+            children: vec![],
+            text: String::new(),
+        })
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn find_attribute(&self, name: &str) -> Option<String> {
+        self.attribute(name).map(String::from).or_else(|| {
+            self.children
+                .iter()
+                .find_map(|child| child.find_attribute(name))
+        })
+    }
+
+    pub fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    /// The trimmed text of the first child element named `name`
+    pub fn child_text(&self, name: &str) -> Option<&str> {
+        self.child(name).map(|child| child.text.trim())
+    }
+}
+
+/// Read the whole document into a tree. The returned element is a nameless parent of the
+/// document's root element.
+pub(crate) fn parse_xml(bytes: &[u8]) -> Result<XmlElement, crate::Error> {
+    use quick_xml::events::Event;
+
+    let mut reader = quick_xml::Reader::from_reader(bytes);
+    let mut stack = vec![XmlElement::default()];
+    loop {
+        match reader.read_event()? {
+            Event::Start(start) => stack.push(XmlElement::new(&start)?),
+            Event::Empty(start) => {
+                let element = XmlElement::new(&start)?;
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(element);
+                }
+            }
+            Event::End(_) => {
+                if stack.len() > 1 {
+                    if let (Some(element), Some(parent)) = (stack.pop(), stack.last_mut()) {
+                        parent.children.push(element);
+                    }
+                }
+            }
+            Event::Text(text) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&text.unescape()?);
+                }
+            }
+            Event::CData(cdata) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(str::from_utf8(&cdata)?);
+                }
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+    }
+    Ok(stack.into_iter().next().unwrap_or_default())
+}
@@ -0,0 +1,31 @@
+use geozero::ToWkt;
+
+/// One row per feature, with a column per property and the geometry as WKT in the last column.
+pub(crate) fn write(
+    feature_collection: &geo_features::FeatureCollection,
+) -> Result<Vec<u8>, crate::Error> {
+    let columns = crate::columns(feature_collection);
+    let mut writer = ::csv::Writer::from_writer(vec![]);
+    writer.write_record(columns.iter().map(|(name, _)| name.as_str()).chain(["wkt"]))?;
+    for feature in &feature_collection.features {
+        let wkt = match feature.geometry {
+            Some(ref geometry) => geometry.to_wkt()?,
+            None => String::new(),
+        };
+        writer.write_record(
+            columns
+                .iter()
+                .map(|(name, _)| {
+                    feature
+                        .properties
+                        .get(name)
+                        .map(crate::value_to_string)
+                        .unwrap_or_default()
+                })
+                .chain([wkt]),
+        )?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| crate::Error::Io(e.into_error()))
+}
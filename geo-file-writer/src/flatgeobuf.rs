@@ -0,0 +1,69 @@
+use geozero::{ColumnValue, PropertyProcessor};
+
+/// Features without a geometry are left out. The file gets a spatial index, so it can be loaded
+/// by bounding box.
+pub(crate) fn write(
+    name: &str,
+    feature_collection: &geo_features::FeatureCollection,
+    crs_epsg_code: Option<u16>,
+) -> Result<Vec<u8>, crate::Error> {
+    let columns = crate::columns(feature_collection);
+    let mut writer = ::flatgeobuf::FgbWriter::create_with_options(
+        name,
+        // Each feature keeps its own geometry type, so layers can mix types
+        ::flatgeobuf::GeometryType::Unknown,
+        ::flatgeobuf::FgbWriterOptions {
+            detect_type: false,
+            crs: ::flatgeobuf::FgbCrs {
+                // 0 is an unknown CRS
+                code: crs_epsg_code.map(i32::from).unwrap_or_default(),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )?;
+    for (column_name, column_type) in &columns {
+        let column_type = match column_type {
+            crate::ColumnType::Number => ::flatgeobuf::ColumnType::Double,
+            crate::ColumnType::Boolean => ::flatgeobuf::ColumnType::Bool,
+            crate::ColumnType::String => ::flatgeobuf::ColumnType::String,
+        };
+        writer.add_column(column_name, column_type, |_, column| {
+            column.nullable = true;
+        });
+    }
+
+    for feature in &feature_collection.features {
+        let Some(ref geometry) = feature.geometry else {
+            continue;
+        };
+        let mut result = Ok(false);
+        writer.add_feature_geom(geometry.clone(), |feature_writer| {
+            for (index, (column_name, column_type)) in columns.iter().enumerate() {
+                let value = match feature.properties.get(column_name) {
+                    None | Some(geo_features::Value::Null) => continue,
+                    Some(value) => value,
+                };
+                let string;
+                let column_value = match (column_type, value) {
+                    (crate::ColumnType::Number, geo_features::Value::Number(n)) => {
+                        ColumnValue::Double(*n)
+                    }
+                    (crate::ColumnType::Boolean, geo_features::Value::Boolean(b)) => {
+                        ColumnValue::Bool(*b)
+                    }
+                    _ => {
+                        string = crate::value_to_string(value);
+                        ColumnValue::String(&string)
+                    }
+                };
+                result = result.and(feature_writer.property(index, column_name, &column_value));
+            }
+        })?;
+        result?;
+    }
+
+    let mut bytes = vec![];
+    writer.write(&mut bytes)?;
+    Ok(bytes)
+}
@@ -0,0 +1,44 @@
+/// Coordinates are written as they are. GeoJSON readers assume EPSG:4326, so other CRSs need to
+/// be known by whoever reads the file.
+pub(crate) fn write(
+    feature_collection: &geo_features::FeatureCollection,
+) -> Result<Vec<u8>, crate::Error> {
+    let features = feature_collection
+        .features
+        .iter()
+        .map(|feature| ::geojson::Feature {
+            bbox: None,
+            geometry: feature
+                .geometry
+                .as_ref()
+                .map(|geometry| ::geojson::Geometry::new(::geojson::Value::from(geometry))),
+            id: None,
+            properties: Some(
+                feature
+                    .properties
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value_to_json(value)))
+                    .collect(),
+            ),
+            foreign_members: None,
+        })
+        .collect();
+    let feature_collection = ::geojson::FeatureCollection {
+        bbox: None,
+        features,
+        foreign_members: None,
+    };
+    Ok(feature_collection.to_string().into_bytes())
+}
+
+fn value_to_json(value: &geo_features::Value) -> serde_json::Value {
+    match value {
+        geo_features::Value::String(s) => serde_json::Value::String(s.clone()),
+        // JSON has no NaN or infinity
+        geo_features::Value::Number(n) => serde_json::Number::from_f64(*n)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        geo_features::Value::Boolean(b) => serde_json::Value::Bool(*b),
+        geo_features::Value::Null => serde_json::Value::Null,
+    }
+}
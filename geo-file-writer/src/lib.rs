@@ -0,0 +1,165 @@
+#![warn(
+    clippy::unwrap_used,
+    clippy::cast_lossless,
+    clippy::unimplemented,
+    clippy::indexing_slicing,
+    clippy::expect_used
+)]
+
+//! Serializes feature collections, the counterpart of `geo_file_loader`.
+
+use std::collections::BTreeMap;
+
+mod csv;
+mod flatgeobuf;
+mod geojson;
+mod shapefile;
+mod wkt;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FileFormat {
+    GeoJson,
+    Wkt,
+    FlatGeobuf,
+    Csv,
+    /// A zip archive of the `.shp`, `.shx` and `.dbf` files
+    Shapefile,
+}
+
+impl FileFormat {
+    pub const ALL: [FileFormat; 5] = [
+        FileFormat::GeoJson,
+        FileFormat::Wkt,
+        FileFormat::FlatGeobuf,
+        FileFormat::Csv,
+        FileFormat::Shapefile,
+    ];
+
+    pub const fn display_name(self) -> &'static str {
+        match self {
+            FileFormat::GeoJson => "GeoJSON",
+            FileFormat::Wkt => "WKT",
+            FileFormat::FlatGeobuf => "FlatGeobuf",
+            FileFormat::Csv => "CSV",
+            FileFormat::Shapefile => "Shapefile (zipped)",
+        }
+    }
+
+    pub const fn extension(self) -> &'static str {
+        match self {
+            FileFormat::GeoJson => "geojson",
+            FileFormat::Wkt => "wkt",
+            FileFormat::FlatGeobuf => "fgb",
+            FileFormat::Csv => "csv",
+            FileFormat::Shapefile => "zip",
+        }
+    }
+
+    /// The format of a file with this extension, e.g. `geojson` or `fgb`
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "geojson" | "json" => Some(FileFormat::GeoJson),
+            "wkt" => Some(FileFormat::Wkt),
+            "fgb" => Some(FileFormat::FlatGeobuf),
+            "csv" => Some(FileFormat::Csv),
+            "zip" => Some(FileFormat::Shapefile),
+            _ => None,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("{0}")]
+    Geozero(#[from] geozero::error::GeozeroError),
+    #[error("{0}")]
+    FlatGeobuf(#[from] ::flatgeobuf::Error),
+    #[error("{0}")]
+    Shapefile(#[from] ::shapefile::Error),
+    #[error("{0}")]
+    Csv(#[from] ::csv::Error),
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("{0}")]
+    Transform(#[from] transform::Error),
+    #[error("No geometry to write")]
+    NoGeometry,
+    #[error("Shapefiles can only contain one geometry type, found {0} and {1}")]
+    MixedShapefileGeometries(&'static str, &'static str),
+    #[error("{0} geometries can't be written to Shapefiles")]
+    UnsupportedShapefileGeometry(&'static str),
+    #[error("{0} isn't a valid dBase field name")]
+    InvalidShapefileFieldName(String),
+}
+
+/// Serialize `feature_collection`, whose coordinates are in `crs`. Formats that can describe their
+/// CRS, i.e. FlatGeobuf and Shapefile, record it. The `name` is used for the files within a zipped
+/// Shapefile and for FlatGeobuf's dataset name.
+pub fn write(
+    format: FileFormat,
+    name: &str,
+    feature_collection: &geo_features::FeatureCollection,
+    crs: &transform::Crs,
+) -> Result<Vec<u8>, Error> {
+    match format {
+        FileFormat::GeoJson => crate::geojson::write(feature_collection),
+        FileFormat::Wkt => crate::wkt::write(feature_collection),
+        FileFormat::FlatGeobuf => {
+            crate::flatgeobuf::write(name, feature_collection, crs.epsg_code())
+        }
+        FileFormat::Csv => crate::csv::write(feature_collection),
+        FileFormat::Shapefile => crate::shapefile::write(name, feature_collection, crs),
+    }
+}
+
+/// The type of a property column. Formats with typed columns, e.g. FlatGeobuf, need one type
+/// per property.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum ColumnType {
+    Number,
+    Boolean,
+    /// Also used for properties whose values have different types
+    String,
+}
+
+/// The properties of all of the features, ordered by name. Properties that are always null are
+/// left out.
+fn columns(feature_collection: &geo_features::FeatureCollection) -> Vec<(String, ColumnType)> {
+    let mut columns = BTreeMap::<&str, ColumnType>::new();
+    for (name, value) in feature_collection
+        .features
+        .iter()
+        .flat_map(|feature| &feature.properties)
+    {
+        let column_type = match value {
+            geo_features::Value::Number(_) => ColumnType::Number,
+            geo_features::Value::Boolean(_) => ColumnType::Boolean,
+            geo_features::Value::String(_) => ColumnType::String,
+            geo_features::Value::Null => continue,
+        };
+        columns
+            .entry(name)
+            .and_modify(|existing| {
+                if *existing != column_type {
+                    *existing = ColumnType::String;
+                }
+            })
+            .or_insert(column_type);
+    }
+    columns
+        .into_iter()
+        .map(|(name, column_type)| (name.to_owned(), column_type))
+        .collect()
+}
+
+/// A property value as text, for string columns and text formats. Null is empty.
+fn value_to_string(value: &geo_features::Value) -> String {
+    match value {
+        geo_features::Value::String(s) => s.clone(),
+        geo_features::Value::Number(n) => n.to_string(),
+        geo_features::Value::Boolean(b) => b.to_string(),
+        geo_features::Value::Null => String::new(),
+    }
+}
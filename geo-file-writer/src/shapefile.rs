@@ -0,0 +1,283 @@
+use ::shapefile::dbase;
+use std::io::{self, Write};
+
+/// dBase field names are at most 10 ASCII characters
+const MAX_FIELD_NAME_LEN: usize = 10;
+const MAX_CHARACTER_FIELD_LEN: u8 = 254;
+
+/// A zip archive of the `.shp`, `.shx` and `.dbf` files, a `.cpg` file declaring the `.dbf`'s
+/// UTF-8 text, and a `.prj` file if `crs` has a WKT definition, named after `name`. Features without a geometry are left out, and property names
+/// are shortened to unique dBase field names.
+pub(crate) fn write(
+    name: &str,
+    feature_collection: &geo_features::FeatureCollection,
+    crs: &transform::Crs,
+) -> Result<Vec<u8>, crate::Error> {
+    let features = feature_collection
+        .features
+        .iter()
+        .filter_map(|feature| Some((feature.geometry.as_ref()?, &feature.properties)))
+        .collect::<Vec<_>>();
+    let Some((first_geometry, _)) = features.first() else {
+        return Err(crate::Error::NoGeometry);
+    };
+    let shape_type = ShapeType::of(first_geometry)?;
+    for (geometry, _) in &features {
+        let other_shape_type = ShapeType::of(geometry)?;
+        if other_shape_type != shape_type {
+            return Err(crate::Error::MixedShapefileGeometries(
+                shape_type.name(),
+                other_shape_type.name(),
+            ));
+        }
+    }
+
+    let (table, fields) = table(feature_collection)?;
+    let records = features
+        .iter()
+        .map(|(_, properties)| record(&fields, properties))
+        .collect::<Vec<_>>();
+    let geometries = features.iter().map(|(geometry, _)| *geometry);
+    let (shp, shx, dbf) = match shape_type {
+        ShapeType::Point => write_shapes(geometries.filter_map(to_point), &records, table)?,
+        ShapeType::Multipoint => {
+            write_shapes(geometries.filter_map(to_multipoint), &records, table)?
+        }
+        ShapeType::Polyline => write_shapes(geometries.filter_map(to_polyline), &records, table)?,
+        ShapeType::Polygon => write_shapes(geometries.filter_map(to_polygon), &records, table)?,
+    };
+
+    let file_stem = file_stem(name);
+    let mut zip = zip::ZipWriter::new(io::Cursor::new(vec![]));
+    let mut files = vec![
+        ("shp", shp),
+        ("shx", shx),
+        ("dbf", dbf),
+        // Without it, readers assume the `.dbf` is in their ANSI code page
+        ("cpg", b"UTF-8".to_vec()),
+    ];
+    if let Some(wkt) = crs.wkt() {
+        files.push(("prj", wkt.into_owned().into_bytes()));
+    }
+    for (extension, bytes) in files {
+        zip.start_file(
+            format!("{file_stem}.{extension}"),
+            zip::write::SimpleFileOptions::default(),
+        )?;
+        zip.write_all(&bytes)?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+/// A Shapefile holds a single type of shape
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum ShapeType {
+    Point,
+    Multipoint,
+    Polyline,
+    Polygon,
+}
+
+impl ShapeType {
+    fn of(geometry: &geo::Geometry) -> Result<Self, crate::Error> {
+        match geometry {
+            geo::Geometry::Point(_) => Ok(ShapeType::Point),
+            geo::Geometry::MultiPoint(_) => Ok(ShapeType::Multipoint),
+            geo::Geometry::Line(_)
+            | geo::Geometry::LineString(_)
+            | geo::Geometry::MultiLineString(_) => Ok(ShapeType::Polyline),
+            geo::Geometry::Polygon(_)
+            | geo::Geometry::MultiPolygon(_)
+            | geo::Geometry::Rect(_)
+            | geo::Geometry::Triangle(_) => Ok(ShapeType::Polygon),
+            geo::Geometry::GeometryCollection(_) => Err(
+                crate::Error::UnsupportedShapefileGeometry("GeometryCollection"),
+            ),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ShapeType::Point => "Point",
+            ShapeType::Multipoint => "MultiPoint",
+            ShapeType::Polyline => "LineString",
+            ShapeType::Polygon => "Polygon",
+        }
+    }
+}
+
+fn to_point(geometry: &geo::Geometry) -> Option<::shapefile::Point> {
+    match geometry {
+        geo::Geometry::Point(point) => Some((*point).into()),
+        _ => None,
+    }
+}
+
+fn to_multipoint(geometry: &geo::Geometry) -> Option<::shapefile::Multipoint> {
+    match geometry {
+        geo::Geometry::MultiPoint(multi_point) => Some(multi_point.clone().into()),
+        _ => None,
+    }
+}
+
+fn to_polyline(geometry: &geo::Geometry) -> Option<::shapefile::Polyline> {
+    match geometry {
+        geo::Geometry::Line(line) => Some(geo::LineString::from(*line).into()),
+        geo::Geometry::LineString(line_string) => Some(line_string.clone().into()),
+        geo::Geometry::MultiLineString(multi_line_string) => Some(multi_line_string.clone().into()),
+        _ => None,
+    }
+}
+
+fn to_polygon(geometry: &geo::Geometry) -> Option<::shapefile::Polygon> {
+    match geometry {
+        geo::Geometry::Polygon(polygon) => Some(polygon.clone().into()),
+        geo::Geometry::MultiPolygon(multi_polygon) => Some(multi_polygon.clone().into()),
+        geo::Geometry::Rect(rect) => Some(rect.to_polygon().into()),
+        geo::Geometry::Triangle(triangle) => Some(triangle.to_polygon().into()),
+        _ => None,
+    }
+}
+
+/// Write the `.shp`, `.shx` and `.dbf` files
+fn write_shapes<S: ::shapefile::record::EsriShape>(
+    shapes: impl Iterator<Item = S>,
+    records: &[dbase::Record],
+    table: dbase::TableWriterBuilder,
+) -> Result<(Vec<u8>, Vec<u8>, Vec<u8>), crate::Error> {
+    let mut shp = io::Cursor::new(vec![]);
+    let mut shx = io::Cursor::new(vec![]);
+    let mut dbf = io::Cursor::new(vec![]);
+    {
+        let mut writer = ::shapefile::Writer::new(
+            ::shapefile::ShapeWriter::with_shx(&mut shp, &mut shx),
+            table.build_with_dest(&mut dbf),
+        );
+        for (shape, record) in shapes.zip(records) {
+            writer.write_shape_and_record(&shape, record)?;
+        }
+        // The file headers are written when the writer is dropped
+    }
+    Ok((shp.into_inner(), shx.into_inner(), dbf.into_inner()))
+}
+
+/// A dBase field for a property
+struct Field {
+    property_name: String,
+    field_name: String,
+    column_type: crate::ColumnType,
+}
+
+fn table(
+    feature_collection: &geo_features::FeatureCollection,
+) -> Result<(dbase::TableWriterBuilder, Vec<Field>), crate::Error> {
+    let mut table = dbase::TableWriterBuilder::new();
+    let mut fields = Vec::<Field>::new();
+    for (property_name, column_type) in crate::columns(feature_collection) {
+        let field_name = unique_field_name(&property_name, &fields);
+        let dbase_field_name = dbase::FieldName::try_from(field_name.as_str())
+            .map_err(|_| crate::Error::InvalidShapefileFieldName(field_name.clone()))?;
+        table = match column_type {
+            crate::ColumnType::Number => table.add_numeric_field(dbase_field_name, 20, 8),
+            crate::ColumnType::Boolean => table.add_logical_field(dbase_field_name),
+            crate::ColumnType::String => {
+                table.add_character_field(dbase_field_name, MAX_CHARACTER_FIELD_LEN)
+            }
+        };
+        fields.push(Field {
+            property_name,
+            field_name,
+            column_type,
+        });
+    }
+    Ok((table, fields))
+}
+
+/// The property name shortened to a dBase field name. Names that are empty or already taken by
+/// another field get a numbered suffix, e.g. `population_2010` and `population_2020` become
+/// `population` and `populati_1`.
+fn unique_field_name(property_name: &str, fields: &[Field]) -> String {
+    let name = property_name
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+        .collect::<String>();
+    let name = if name.is_empty() {
+        "field".into()
+    } else {
+        name
+    };
+    // dBase field names are case insensitive
+    let is_taken = |candidate: &str| {
+        fields
+            .iter()
+            .any(|field| field.field_name.eq_ignore_ascii_case(candidate))
+    };
+    let shortened = name.chars().take(MAX_FIELD_NAME_LEN).collect::<String>();
+    if !is_taken(&shortened) {
+        return shortened;
+    }
+    (1..)
+        .map(|n| {
+            let suffix = format!("_{n}");
+            let prefix = name
+                .chars()
+                .take(MAX_FIELD_NAME_LEN.saturating_sub(suffix.len()))
+                .collect::<String>();
+            prefix + &suffix
+        })
+        .find(|candidate| !is_taken(candidate))
+        .unwrap_or(shortened)
+}
+
+fn record(fields: &[Field], properties: &geo_features::Properties) -> dbase::Record {
+    let mut record = dbase::Record::default();
+    for field in fields {
+        let value = properties
+            .get(&field.property_name)
+            .filter(|value| !matches!(value, geo_features::Value::Null));
+        let field_value = match (field.column_type, value) {
+            (crate::ColumnType::Number, Some(geo_features::Value::Number(n))) => {
+                dbase::FieldValue::Numeric(Some(*n))
+            }
+            (crate::ColumnType::Number, _) => dbase::FieldValue::Numeric(None),
+            (crate::ColumnType::Boolean, Some(geo_features::Value::Boolean(b))) => {
+                dbase::FieldValue::Logical(Some(*b))
+            }
+            (crate::ColumnType::Boolean, _) => dbase::FieldValue::Logical(None),
+            (crate::ColumnType::String, value) => dbase::FieldValue::Character(
+                value.map(|value| truncate_to_field_len(&crate::value_to_string(value)).to_owned()),
+            ),
+        };
+        record.insert(field.field_name.clone(), field_value);
+    }
+    record
+}
+
+/// The longest start of `s` that fits in a character field. Field lengths are in bytes, so this
+/// stops at the last character boundary before the limit.
+fn truncate_to_field_len(s: &str) -> &str {
+    let end = (0..=s.len().min(usize::from(MAX_CHARACTER_FIELD_LEN)))
+        .rev()
+        .find(|end| s.is_char_boundary(*end))
+        .unwrap_or_default();
+    s.get(..end).unwrap_or_default()
+}
+
+/// The name of the files within the archive, without characters that file systems may reject
+fn file_stem(name: &str) -> String {
+    let file_stem = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    if file_stem.is_empty() {
+        "layer".into()
+    } else {
+        file_stem
+    }
+}
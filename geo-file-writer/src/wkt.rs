@@ -0,0 +1,18 @@
+use geozero::ToWkt;
+
+/// WKT holds a single geometry, so several features are written as a `GEOMETRYCOLLECTION`.
+/// Properties are dropped.
+pub(crate) fn write(
+    feature_collection: &geo_features::FeatureCollection,
+) -> Result<Vec<u8>, crate::Error> {
+    let mut geometries = feature_collection
+        .geometry_iter()
+        .cloned()
+        .collect::<Vec<_>>();
+    let geometry = match geometries.len() {
+        0 => return Err(crate::Error::NoGeometry),
+        1 => geometries.remove(0),
+        _ => geo::Geometry::GeometryCollection(geo::GeometryCollection(geometries)),
+    };
+    Ok(geometry.to_wkt()?.into_bytes())
+}
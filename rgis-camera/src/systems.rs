@@ -32,6 +32,7 @@ fn handle_change_crs_event(
     >,
     windows: Query<&Window, With<PrimaryWindow>>,
     ui_margins: rgis_ui::UiMargins,
+    transformers: Res<rgis_transform::Transformers>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let Some(event) = change_crs_event_reader.read().last() else {
         return Ok(());
@@ -47,11 +48,18 @@ fn handle_change_crs_event(
     };
     let rect = map_area.projected_geo_rect(&transform, window);
 
-    let transformer =
-        transform::Transformer::setup(event.old_crs_epsg_code, event.new_crs_epsg_code)?;
-    transformer.transform(&mut (rect.0.into()))?;
+    let transformer = transformers.get(&event.old_crs, &event.new_crs)?;
+    let mut geometry = geo::Geometry::from(rect.0);
+    transformer.transform(&mut geometry)?;
+    let Some(rect) = geo::BoundingRect::bounding_rect(&geometry) else {
+        return Ok(());
+    };
 
-    crate::utils::center_camera_on_projected_world_rect(rect, &mut transform, map_area);
+    crate::utils::center_camera_on_projected_world_rect(
+        geo_projected::Projected(rect),
+        &mut transform,
+        map_area,
+    );
 
     Ok(())
 }
@@ -0,0 +1,183 @@
+//! `rgis convert`: loads, reprojects and writes a file the same way the map does, without opening
+//! a window.
+
+use std::{fs, path};
+
+/// The CRS assumed when neither the file nor `--from-crs` declares one, like in the add layer
+/// window
+const DEFAULT_SOURCE_CRS: transform::Crs = transform::Crs::WGS_84;
+
+/// The files that make up a Shapefile, loaded together like when they're all selected in the add
+/// layer window
+const SHAPEFILE_EXTENSIONS: &[&str] = &["shp", "shx", "dbf", "prj", "cpg"];
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Load(#[from] geo_file_loader::Error),
+    #[error("{0}")]
+    Write(#[from] geo_file_writer::Error),
+    #[error("{0}")]
+    Transform(#[from] transform::Error),
+    #[error("Could not determine the format of {0}")]
+    UnknownInputFormat(String),
+    #[error("Could not determine the output format from {0}, use --format to set it")]
+    UnknownOutputFormat(String),
+}
+
+pub struct ConvertArgs {
+    pub input: path::PathBuf,
+    pub output: path::PathBuf,
+    /// Used when the file doesn't declare its CRS
+    pub from_crs: Option<transform::Crs>,
+    /// Defaults to the source CRS, i.e. no reprojection
+    pub to_crs: Option<transform::Crs>,
+    /// Guessed from the output's extension if `None`
+    pub format: Option<geo_file_writer::FileFormat>,
+}
+
+pub fn convert(args: ConvertArgs) -> Result<(), Error> {
+    let input_name = file_name(&args.input);
+    let bytes = bytes::Bytes::from(fs::read(&args.input)?);
+    let file_format = geo_file_loader::detect_format(&input_name, &bytes)
+        .ok_or_else(|| Error::UnknownInputFormat(args.input.display().to_string()))?;
+    let output_format = match args.format {
+        Some(format) => format,
+        None => args
+            .output
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(geo_file_writer::FileFormat::from_extension)
+            .ok_or_else(|| Error::UnknownOutputFormat(args.output.display().to_string()))?,
+    };
+    // GeoJSON is WGS 84, unless `--from-crs` says otherwise, e.g. for files following the
+    // pre-RFC 7946 specification, which allowed any CRS
+    let source_crs = args.from_crs.unwrap_or(DEFAULT_SOURCE_CRS);
+
+    let files = if file_format == geo_file_loader::FileFormat::Shapefile {
+        shapefile_files(&args.input, input_name, bytes)?
+    } else {
+        vec![geo_file_loader::InputFile {
+            name: input_name,
+            bytes,
+        }]
+    };
+    let loaded =
+        geo_file_loader::load_files(file_format, files, &geo_file_loader::LoadOptions::default())?;
+
+    let layer_count = loaded.layers.len();
+    for (index, layer) in loaded.layers.into_iter().enumerate() {
+        // A CRS declared by the file takes precedence, like when loading it onto the map
+        let layer_crs = layer
+            .crs
+            .as_ref()
+            .map(geo_file_loader::DetectedCrs::to_crs)
+            .unwrap_or_else(|| source_crs.clone());
+        let target_crs = args.to_crs.clone().unwrap_or_else(|| layer_crs.clone());
+        let mut feature_collection = layer.feature_collection;
+        if target_crs != layer_crs {
+            reproject(&mut feature_collection, &layer_crs, &target_crs)?;
+        }
+
+        // Files containing several layers are written to one file per layer
+        let output = if layer_count == 1 {
+            args.output.clone()
+        } else {
+            layer_output_path(
+                &args.output,
+                &layer.name.unwrap_or_else(|| (index + 1).to_string()),
+            )
+        };
+        let bytes = geo_file_writer::write(
+            output_format,
+            &file_stem(&output),
+            &feature_collection,
+            &target_crs,
+        )?;
+        fs::write(&output, bytes)?;
+        eprintln!(
+            "Wrote {} features to {}",
+            feature_collection.features.len(),
+            output.display()
+        );
+    }
+    Ok(())
+}
+
+/// Reprojects each geometry the same way layers are reprojected for the map
+fn reproject(
+    feature_collection: &mut geo_features::FeatureCollection,
+    source_crs: &transform::Crs,
+    target_crs: &transform::Crs,
+) -> Result<(), Error> {
+    let transformer = transform::Transformer::setup(source_crs, target_crs)?;
+    transformer
+        .transform_geometries(
+            feature_collection
+                .features
+                .iter_mut()
+                .filter_map(|feature| feature.geometry.as_mut()),
+        )
+        .map_err(transform::Error::from)?;
+    for feature in &mut feature_collection.features {
+        feature.recalculate_bounding_rect();
+    }
+    feature_collection.recalculate_bounding_rect();
+    Ok(())
+}
+
+/// The `.shp` and the files next to it with the same name, or the zip archive by itself
+fn shapefile_files(
+    input: &path::Path,
+    input_name: String,
+    bytes: bytes::Bytes,
+) -> Result<Vec<geo_file_loader::InputFile>, Error> {
+    let mut files = vec![geo_file_loader::InputFile {
+        name: input_name,
+        bytes,
+    }];
+    let is_shp = input
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("shp"));
+    if !is_shp {
+        return Ok(files);
+    }
+    for extension in SHAPEFILE_EXTENSIONS.iter().filter(|e| **e != "shp") {
+        let path = input.with_extension(extension);
+        if path.is_file() {
+            files.push(geo_file_loader::InputFile {
+                name: file_name(&path),
+                bytes: fs::read(&path)?.into(),
+            });
+        }
+    }
+    Ok(files)
+}
+
+/// `out.geojson` becomes `out-roads.geojson` for a layer named `roads`
+fn layer_output_path(output: &path::Path, layer_name: &str) -> path::PathBuf {
+    let layer_name = layer_name
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    let mut file_name = format!("{}-{}", file_stem(output), layer_name);
+    if let Some(extension) = output.extension().and_then(|extension| extension.to_str()) {
+        file_name.push('.');
+        file_name.push_str(extension);
+    }
+    output.with_file_name(file_name)
+}
+
+fn file_name(path: &path::Path) -> String {
+    path.file_name()
+        .map(|file_name| file_name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn file_stem(path: &path::Path) -> String {
+    path.file_stem()
+        .map(|file_stem| file_stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
@@ -7,18 +7,32 @@
 )]
 
 use bevy::prelude::*;
-use clap::{Arg, ArgAction, Command};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use std::path;
+
+mod convert;
 
 static DEFAULT_MSAA: &str = "4";
 
 type MsaaSampleCount = u32;
 
+fn parse_crs(crs: &str) -> Result<transform::Crs, String> {
+    crs.parse().map_err(|e: transform::Error| e.to_string())
+}
+
 #[derive(Copy, Clone, Resource)]
 pub struct Values {
     pub msaa_sample_count: MsaaSampleCount,
 }
 
-pub fn run() -> Result<Values, String> {
+pub enum Action {
+    /// Open the map window
+    View(Values),
+    /// A subcommand ran to completion without opening a window
+    Exit,
+}
+
+pub fn run() -> Result<Action, String> {
     let matches = Command::new("rgis")
         .author("Corey Farwell <coreyf@rwell.org>")
         .about("Geospatial data viewer written in Rust")
@@ -30,12 +44,75 @@ pub fn run() -> Result<Values, String> {
                 .help("Multi-Sample Anti-Aliasing sample count. Setting the sample count higher will result in smoother edges, but it will also increase the cost to render those edges. The range should generally be somewhere between 1 (no multi sampling, but cheap) to 8 (crisp but expensive).")
                 .value_parser(clap::value_parser!(u32))
         )
+        .subcommand(
+            Command::new("convert")
+                .about("Convert a file to another format and CRS without opening a window, e.g. `rgis convert in.shp out.geojson --from-crs 27700 --to-crs 4326`")
+                .arg(
+                    Arg::new("INPUT")
+                        .required(true)
+                        .help("File to convert, in any format rgis can load. The files next to a Shapefile's .shp are read too.")
+                        .value_parser(clap::value_parser!(path::PathBuf))
+                )
+                .arg(
+                    Arg::new("OUTPUT")
+                        .required(true)
+                        .help("File to write. Files containing several layers are written to one file per layer, named after the layer.")
+                        .value_parser(clap::value_parser!(path::PathBuf))
+                )
+                .arg(
+                    Arg::new("FROM CRS")
+                        .long("from-crs")
+                        .action(ArgAction::Set)
+                        .help("The input's CRS, e.g. 27700, EPSG:27700, ESRI:102100, WKT, PROJJSON or a PROJ string. A CRS declared by the file takes precedence, like when it's added to the map. Defaults to EPSG:4326.")
+                        .value_parser(parse_crs)
+                )
+                .arg(
+                    Arg::new("TO CRS")
+                        .long("to-crs")
+                        .action(ArgAction::Set)
+                        .help("The output's CRS, in any of the forms --from-crs accepts. Defaults to the input's CRS.")
+                        .value_parser(parse_crs)
+                )
+                .arg(
+                    Arg::new("FORMAT")
+                        .long("format")
+                        .action(ArgAction::Set)
+                        .help("Output format, as a file extension: geojson, wkt, fgb, csv, or zip for a zipped Shapefile. Defaults to the output's extension.")
+                        .value_parser(|format: &str| {
+                            geo_file_writer::FileFormat::from_extension(format)
+                                .ok_or_else(|| format!("Unknown output format: {format}"))
+                        })
+                )
+        )
         .get_matches();
 
-    Ok(Values {
+    if let Some(convert_matches) = matches.subcommand_matches("convert") {
+        convert::convert(convert_args(convert_matches)?).map_err(|e| e.to_string())?;
+        return Ok(Action::Exit);
+    }
+
+    Ok(Action::View(Values {
         msaa_sample_count: *matches
             .get_one::<MsaaSampleCount>("MSAA SAMPLE COUNT")
             .ok_or("Could not fetch MSAA sample count from clap")?,
+    }))
+}
+
+fn convert_args(matches: &ArgMatches) -> Result<convert::ConvertArgs, String> {
+    Ok(convert::ConvertArgs {
+        input: matches
+            .get_one::<path::PathBuf>("INPUT")
+            .cloned()
+            .ok_or("Could not fetch input path from clap")?,
+        output: matches
+            .get_one::<path::PathBuf>("OUTPUT")
+            .cloned()
+            .ok_or("Could not fetch output path from clap")?,
+        from_crs: matches.get_one::<transform::Crs>("FROM CRS").cloned(),
+        to_crs: matches.get_one::<transform::Crs>("TO CRS").cloned(),
+        format: matches
+            .get_one::<geo_file_writer::FileFormat>("FORMAT")
+            .copied(),
     })
 }
 
@@ -93,13 +93,23 @@ pub enum LoadFileEvent {
     FromNetwork {
         name: String,
         url: String,
-        crs_epsg_code: u16,
+        crs: transform::Crs,
     },
     FromBytes {
         file_name: String,
         file_format: geo_file_loader::FileFormat,
         bytes: bytes::Bytes,
-        crs_epsg_code: u16,
+        crs: transform::Crs,
+        options: geo_file_loader::LoadOptions,
+    },
+    /// A file that was opened together with its companion files, e.g. a Shapefile's `.shp`,
+    /// `.dbf` and `.prj`
+    FromFiles {
+        name: String,
+        file_format: geo_file_loader::FileFormat,
+        files: Vec<geo_file_loader::InputFile>,
+        crs: transform::Crs,
+        options: geo_file_loader::LoadOptions,
     },
 }
 
@@ -157,14 +167,14 @@ impl ZoomCameraEvent {
 
 #[derive(Event)]
 pub struct ChangeCrsEvent {
-    pub old_crs_epsg_code: u16,
-    pub new_crs_epsg_code: u16,
+    pub old_crs: transform::Crs,
+    pub new_crs: transform::Crs,
 }
 
 #[derive(Event)]
 pub struct CrsChangedEvent {
-    pub old_crs_epsg_code: u16,
-    pub new_crs_epsg_code: u16,
+    pub old_crs: transform::Crs,
+    pub new_crs: transform::Crs,
 }
 
 #[derive(Event)]
@@ -177,7 +187,7 @@ pub struct RenderFeaturePropertiesEvent(pub geo_features::Properties);
 pub struct CreateLayerEvent {
     pub feature_collection: geo_projected::Unprojected<geo_features::FeatureCollection>,
     pub name: String,
-    pub source_crs_epsg_code: u16,
+    pub source_crs: transform::Crs,
 }
 
 #[derive(Event)]
@@ -189,6 +199,66 @@ pub struct ShowAddLayerWindow;
 #[derive(Default, Event)]
 pub struct HideAddLayerWindow;
 
+/// Open a local `.mbtiles` or `.pmtiles` tileset
+#[derive(Event, Debug)]
+pub struct OpenTilesetEvent(pub std::path::PathBuf);
+
+#[derive(Event, Debug)]
+pub struct DeleteTilesetEvent(pub rgis_layer_id::LayerId);
+
+/// A raster image loaded from a file, e.g. a GeoTIFF, to be drawn under the layers
+#[derive(Event)]
+pub struct CreateRasterEvent {
+    pub name: String,
+    pub raster: geo_file_loader::Raster,
+    pub source_crs: transform::Crs,
+}
+
+#[derive(Event, Debug)]
+pub struct DeleteRasterEvent(pub rgis_layer_id::LayerId);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MapImageFormat {
+    /// Rendered pixels, as they're drawn on the map
+    Png,
+    /// Vector shapes built from the layers' geometries
+    Svg,
+}
+
+impl MapImageFormat {
+    pub const ALL: [MapImageFormat; 2] = [MapImageFormat::Png, MapImageFormat::Svg];
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            MapImageFormat::Png => "PNG",
+            MapImageFormat::Svg => "SVG",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            MapImageFormat::Png => "png",
+            MapImageFormat::Svg => "svg",
+        }
+    }
+}
+
+/// Render the part of the map within `rect` to an image of `width_px` by `height_px` pixels
+#[derive(Event, Debug)]
+pub struct ExportMapImageEvent {
+    pub format: MapImageFormat,
+    pub rect: geo_projected::Projected<geo::Rect>,
+    pub width_px: u32,
+    pub height_px: u32,
+    pub dpi: f32,
+}
+
+#[derive(Event, Debug)]
+pub struct MapImageExportedEvent {
+    pub format: MapImageFormat,
+    pub bytes: Vec<u8>,
+}
+
 impl bevy::app::Plugin for Plugin {
     fn build(&self, app: &mut App) {
         app.add_event::<LoadFileEvent>()
@@ -218,6 +288,12 @@ impl bevy::app::Plugin for Plugin {
             .add_event::<DespawnMeshesEvent>()
             .add_event::<FeatureSelectedEvent>()
             .add_event::<FeaturesDeselectedEvent>()
-            .add_event::<ShowManageLayerWindowEvent>();
+            .add_event::<ShowManageLayerWindowEvent>()
+            .add_event::<OpenTilesetEvent>()
+            .add_event::<DeleteTilesetEvent>()
+            .add_event::<CreateRasterEvent>()
+            .add_event::<DeleteRasterEvent>()
+            .add_event::<ExportMapImageEvent>()
+            .add_event::<MapImageExportedEvent>();
     }
 }
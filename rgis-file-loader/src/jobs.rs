@@ -1,14 +1,19 @@
 pub struct LoadFileJob {
     pub file_format: geo_file_loader::FileFormat,
-    pub bytes: bytes::Bytes,
+    pub files: Vec<geo_file_loader::InputFile>,
     pub name: String,
-    pub source_crs_epsg_code: u16,
+    pub source_crs: transform::Crs,
+    pub options: geo_file_loader::LoadOptions,
 }
 
 pub struct LoadFileJobOutcome {
+    pub layers: Vec<LoadedLayer>,
+}
+
+pub struct LoadedLayer {
     pub feature_collection: geo_projected::Unprojected<geo_features::FeatureCollection>,
     pub name: String,
-    pub source_crs_epsg_code: u16,
+    pub source_crs: transform::Crs,
 }
 
 impl bevy_jobs::Job for LoadFileJob {
@@ -19,16 +24,101 @@ impl bevy_jobs::Job for LoadFileJob {
         format!("Loading {} file", self.file_format.display_name())
     }
 
+    fn perform(self, ctx: bevy_jobs::Context) -> bevy_jobs::AsyncReturn<Self::Outcome> {
+        Box::pin(async move {
+            let loaded = if self.file_format == geo_file_loader::FileFormat::GeoJsonSeq {
+                load_geojson_seq(self.files, ctx).await?
+            } else {
+                geo_file_loader::load_files(self.file_format, self.files, &self.options)?
+            };
+            let layers = loaded
+                .layers
+                .into_iter()
+                .map(|layer| LoadedLayer {
+                    feature_collection: geo_projected::Unprojected::new(layer.feature_collection),
+                    source_crs: layer
+                        .crs
+                        .as_ref()
+                        .map(geo_file_loader::DetectedCrs::to_crs)
+                        .unwrap_or_else(|| self.source_crs.clone()),
+                    // Files containing several layers get one map layer per contained layer
+                    name: match layer.name {
+                        Some(layer_name) => format!("{}: {}", self.name, layer_name),
+                        None => self.name.clone(),
+                    },
+                })
+                .collect();
+            Ok(LoadFileJobOutcome { layers })
+        })
+    }
+}
+
+/// Rasters are drawn as a single GPU texture, whose size is limited, notably by WebGL2
+#[cfg(target_arch = "wasm32")]
+const MAX_RASTER_SIZE: u32 = 2048;
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_RASTER_SIZE: u32 = 8192;
+
+pub struct LoadRasterJob {
+    pub bytes: bytes::Bytes,
+    pub name: String,
+    pub source_crs: transform::Crs,
+}
+
+pub struct LoadRasterJobOutcome {
+    pub raster: geo_file_loader::Raster,
+    pub name: String,
+    pub source_crs: transform::Crs,
+}
+
+impl bevy_jobs::Job for LoadRasterJob {
+    type Outcome = Result<LoadRasterJobOutcome, geo_file_loader::Error>;
+    const JOB_TYPE: bevy_jobs::JobType = bevy_jobs::JobType::Io;
+
+    fn name(&self) -> String {
+        format!("Loading raster '{}'", self.name)
+    }
+
     fn perform(self, _: bevy_jobs::Context) -> bevy_jobs::AsyncReturn<Self::Outcome> {
         Box::pin(async move {
-            Ok(LoadFileJobOutcome {
-                feature_collection: geo_projected::Unprojected::new(geo_file_loader::load_file(
-                    self.file_format,
-                    self.bytes,
-                )?),
+            let raster = geo_file_loader::GeoTiffSource {
+                bytes: self.bytes,
+                max_size: MAX_RASTER_SIZE,
+            }
+            .load()?;
+            Ok(LoadRasterJobOutcome {
+                source_crs: raster
+                    .crs
+                    .as_ref()
+                    .map(geo_file_loader::DetectedCrs::to_crs)
+                    .unwrap_or(self.source_crs),
+                raster,
                 name: self.name,
-                source_crs_epsg_code: self.source_crs_epsg_code,
             })
         })
     }
 }
+
+/// GeoJSON sequences can have millions of features, so report progress while reading them.
+async fn load_geojson_seq(
+    files: Vec<geo_file_loader::InputFile>,
+    ctx: bevy_jobs::Context,
+) -> Result<geo_file_loader::LoadedFile, geo_file_loader::Error> {
+    let [file] = <[geo_file_loader::InputFile; 1]>::try_from(files).map_err(|_| {
+        geo_file_loader::Error::MultipleFilesUnsupported(
+            geo_file_loader::FileFormat::GeoJsonSeq.display_name(),
+        )
+    })?;
+    let mut reader = geo_file_loader::GeoJsonSeqReader::new(file.bytes);
+    let mut features = vec![];
+    let mut last_percent: u8 = 0;
+    while let Some(feature) = reader.read_feature() {
+        features.push(feature?);
+        let new_percent = reader.progress_percent();
+        if new_percent != last_percent {
+            let _ = ctx.send_progress(new_percent).await;
+            last_percent = new_percent;
+        }
+    }
+    reader.finish(features)
+}
@@ -7,11 +7,18 @@ fn handle_network_fetch_finished_jobs(
     while let Some(outcome) = finished_jobs.take_next::<rgis_network::NetworkFetchJob>() {
         match outcome {
             Ok(fetched) => {
+                let Some(file_format) =
+                    geo_file_loader::detect_format(&fetched.name, &fetched.bytes)
+                else {
+                    bevy::log::error!("Could not detect the file format of '{}'", fetched.name);
+                    continue;
+                };
                 load_event_reader.send(rgis_events::LoadFileEvent::FromBytes {
-                    file_format: geo_file_loader::FileFormat::GeoJson,
+                    file_format,
                     bytes: fetched.bytes,
                     file_name: fetched.name,
-                    crs_epsg_code: fetched.crs_epsg_code,
+                    crs: fetched.crs,
+                    options: geo_file_loader::LoadOptions::default(),
                 });
             }
             Err(e) => {
@@ -21,30 +28,139 @@ fn handle_network_fetch_finished_jobs(
     }
 }
 
+/// Files dropped onto the window are loaded with their detected format. A Shapefile's sibling
+/// files, e.g. its `.dbf` and `.prj`, are grouped by their stem and loaded together. Files that
+/// don't declare their CRS are assumed to be in WGS 84, like in the add layer window.
+#[cfg(not(target_arch = "wasm32"))]
+fn handle_dropped_files(
+    mut file_drag_and_drop_event_reader: EventReader<bevy::window::FileDragAndDrop>,
+    mut load_file_event_writer: EventWriter<rgis_events::LoadFileEvent>,
+) {
+    // The dropped files by their path without extension, in the order they were dropped
+    let mut groups = Vec::<(std::path::PathBuf, Vec<geo_file_loader::InputFile>)>::new();
+    for event in file_drag_and_drop_event_reader.read() {
+        let bevy::window::FileDragAndDrop::DroppedFile { path_buf, .. } = event else {
+            continue;
+        };
+        let file_name = path_buf
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let bytes = match std::fs::read(path_buf) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                bevy::log::error!("Could not read dropped file '{}': {:?}", file_name, e);
+                continue;
+            }
+        };
+        let file = geo_file_loader::InputFile {
+            name: file_name,
+            bytes: bytes.into(),
+        };
+        let stem_path = path_buf.with_extension("");
+        match groups.iter_mut().find(|(path, _)| *path == stem_path) {
+            Some((_, files)) => files.push(file),
+            None => groups.push((stem_path, vec![file])),
+        }
+    }
+
+    for (stem_path, files) in groups {
+        let is_shapefile = files.iter().any(|file| {
+            file.name
+                .rsplit_once('.')
+                .is_some_and(|(_, extension)| extension.eq_ignore_ascii_case("shp"))
+        });
+        if is_shapefile && files.len() > 1 {
+            load_file_event_writer.send(rgis_events::LoadFileEvent::FromFiles {
+                name: stem_path
+                    .file_name()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                file_format: geo_file_loader::FileFormat::Shapefile,
+                files,
+                crs: transform::Crs::WGS_84,
+                options: geo_file_loader::LoadOptions::default(),
+            });
+            continue;
+        }
+        for file in files {
+            let Some(file_format) = geo_file_loader::detect_format(&file.name, &file.bytes) else {
+                bevy::log::error!("Could not detect the file format of '{}'", file.name);
+                continue;
+            };
+            load_file_event_writer.send(rgis_events::LoadFileEvent::FromBytes {
+                crs: transform::Crs::WGS_84,
+                file_name: file.name,
+                file_format,
+                bytes: file.bytes,
+                options: geo_file_loader::LoadOptions::default(),
+            });
+        }
+    }
+}
+
 fn handle_load_file_events(
     mut load_event_reader: ResMut<Events<rgis_events::LoadFileEvent>>,
     mut job_spawner: bevy_jobs::JobSpawner,
 ) {
     for event in load_event_reader.drain() {
         match event {
-            rgis_events::LoadFileEvent::FromNetwork {
-                url,
-                crs_epsg_code,
-                name,
-            } => job_spawner.spawn(rgis_network::NetworkFetchJob {
-                url,
-                crs_epsg_code,
-                name,
+            rgis_events::LoadFileEvent::FromNetwork { url, crs, name } => {
+                job_spawner.spawn(rgis_network::NetworkFetchJob { url, crs, name })
+            }
+            rgis_events::LoadFileEvent::FromBytes {
+                file_name,
+                bytes,
+                file_format: geo_file_loader::FileFormat::GeoTiff,
+                crs,
+                ..
+            } => job_spawner.spawn(crate::jobs::LoadRasterJob {
+                bytes,
+                name: file_name,
+                source_crs: crs,
             }),
+            // Each GeoTIFF is its own raster
+            rgis_events::LoadFileEvent::FromFiles {
+                files,
+                file_format: geo_file_loader::FileFormat::GeoTiff,
+                crs,
+                ..
+            } => {
+                for file in files {
+                    job_spawner.spawn(crate::jobs::LoadRasterJob {
+                        bytes: file.bytes,
+                        name: file.name,
+                        source_crs: crs.clone(),
+                    });
+                }
+            }
             rgis_events::LoadFileEvent::FromBytes {
                 file_name,
                 bytes,
                 file_format,
-                crs_epsg_code,
+                crs,
+                options,
             } => job_spawner.spawn(crate::jobs::LoadFileJob {
-                source_crs_epsg_code: crs_epsg_code,
+                source_crs: crs,
+                options,
+                files: vec![geo_file_loader::InputFile {
+                    name: file_name.clone(),
+                    bytes,
+                }],
                 name: file_name,
-                bytes,
+                file_format,
+            }),
+            rgis_events::LoadFileEvent::FromFiles {
+                name,
+                files,
+                file_format,
+                crs,
+                options,
+            } => job_spawner.spawn(crate::jobs::LoadFileJob {
+                source_crs: crs,
+                options,
+                files,
+                name,
                 file_format,
             }),
         };
@@ -58,14 +174,36 @@ fn handle_load_file_job_finished_events(
     while let Some(outcome) = finished_jobs.take_next::<crate::jobs::LoadFileJob>() {
         match outcome {
             Ok(outcome) => {
-                create_layer_event_writer.send(rgis_events::CreateLayerEvent {
+                for layer in outcome.layers {
+                    create_layer_event_writer.send(rgis_events::CreateLayerEvent {
+                        name: layer.name,
+                        feature_collection: layer.feature_collection,
+                        source_crs: layer.source_crs,
+                    });
+                }
+            }
+            Err(e) => {
+                bevy::log::error!("Encountered error when loading file: {:?}", e);
+            }
+        }
+    }
+}
+
+fn handle_load_raster_job_finished_events(
+    mut finished_jobs: bevy_jobs::FinishedJobs,
+    mut create_raster_event_writer: EventWriter<rgis_events::CreateRasterEvent>,
+) {
+    while let Some(outcome) = finished_jobs.take_next::<crate::jobs::LoadRasterJob>() {
+        match outcome {
+            Ok(outcome) => {
+                create_raster_event_writer.send(rgis_events::CreateRasterEvent {
                     name: outcome.name,
-                    feature_collection: outcome.feature_collection,
-                    source_crs_epsg_code: outcome.source_crs_epsg_code,
+                    raster: outcome.raster,
+                    source_crs: outcome.source_crs,
                 });
             }
             Err(e) => {
-                bevy::log::error!("Encountered error when loading file: {:?}", e);
+                bevy::log::error!("Encountered error when loading raster: {:?}", e);
             }
         }
     }
@@ -78,6 +216,10 @@ pub fn configure(app: &mut App) {
             handle_network_fetch_finished_jobs,
             handle_load_file_events,
             handle_load_file_job_finished_events,
+            handle_load_raster_job_finished_events,
         ),
     );
+
+    #[cfg(not(target_arch = "wasm32"))]
+    app.add_systems(Update, handle_dropped_files);
 }
@@ -150,7 +150,7 @@ impl Layers {
         &mut self,
         unprojected: geo_projected::Unprojected<geo_features::FeatureCollection>,
         name: String,
-        source_crs_epsg_code: u16,
+        source_crs: transform::Crs,
     ) -> rgis_layer_id::LayerId {
         let layer_id = self.next_layer_id();
         let geom_type = geo_geom_type::determine(unprojected.as_raw().geometry_iter());
@@ -171,7 +171,7 @@ impl Layers {
             name,
             visible: true,
             id: layer_id,
-            crs_epsg_code: source_crs_epsg_code,
+            crs: source_crs,
             geom_type,
         };
         self.data.push(layer);
@@ -204,7 +204,7 @@ pub struct Layer {
     pub id: rgis_layer_id::LayerId,
     pub name: String,
     pub visible: bool,
-    pub crs_epsg_code: u16,
+    pub crs: transform::Crs,
     pub geom_type: geo_geom_type::GeomType,
 }
 
@@ -12,12 +12,12 @@ use std::{io, sync};
 pub struct FetchedFile {
     pub name: String,
     pub bytes: bytes::Bytes,
-    pub crs_epsg_code: u16,
+    pub crs: transform::Crs,
 }
 
 pub struct NetworkFetchJob {
     pub url: String,
-    pub crs_epsg_code: u16,
+    pub crs: transform::Crs,
     pub name: String,
 }
 
@@ -41,9 +41,9 @@ impl bevy_jobs::Job for NetworkFetchJob {
 
     fn perform(self, ctx: bevy_jobs::Context) -> bevy_jobs::AsyncReturn<Self::Outcome> {
         Box::pin(async move {
-            await_future(async move {
-                build_request_future(self.url, self.crs_epsg_code, self.name, ctx).await
-            })
+            await_future(
+                async move { build_request_future(self.url, self.crs, self.name, ctx).await },
+            )
             .await?
         })
     }
@@ -57,7 +57,7 @@ impl bevy::app::Plugin for Plugin {
 
 async fn build_request_future(
     url: String,
-    crs_epsg_code: u16,
+    crs: transform::Crs,
     name: String,
     ctx: bevy_jobs::Context,
 ) -> Result<FetchedFile, Error> {
@@ -81,7 +81,7 @@ async fn build_request_future(
 
     Ok(FetchedFile {
         bytes: bytes::Bytes::from(bytes),
-        crs_epsg_code,
+        crs,
         name,
     })
 }
@@ -0,0 +1,86 @@
+use bevy::render::{mesh, render_asset::RenderAssetUsages};
+
+/// Reprojection bends the raster's edges, so it's drawn as a grid of this many cells per side
+/// rather than as a single quad.
+const GRID_CELLS: u32 = 32;
+
+pub struct RasterMeshJob {
+    pub raster_id: rgis_layer_id::LayerId,
+    pub width: u32,
+    pub height: u32,
+    pub geo_transform: geo_file_loader::GeoTransform,
+    pub source_crs: transform::Crs,
+    pub target_crs: transform::Crs,
+}
+
+pub struct RasterMeshJobOutcome {
+    pub raster_id: rgis_layer_id::LayerId,
+    pub target_crs: transform::Crs,
+    pub mesh: bevy::render::mesh::Mesh,
+}
+
+impl bevy_jobs::Job for RasterMeshJob {
+    type Outcome = Result<RasterMeshJobOutcome, transform::Error>;
+
+    fn name(&self) -> String {
+        "Building raster mesh".to_string()
+    }
+
+    fn perform(self, _: bevy_jobs::Context) -> bevy_jobs::AsyncReturn<Self::Outcome> {
+        Box::pin(async move {
+            let cells = f64::from(GRID_CELLS);
+            let mut points = vec![];
+            let mut uvs = vec![];
+            for row in 0..=GRID_CELLS {
+                for column in 0..=GRID_CELLS {
+                    let (u, v) = (f64::from(column) / cells, f64::from(row) / cells);
+                    points.push(geo::Point(
+                        self.geo_transform
+                            .apply(u * f64::from(self.width), v * f64::from(self.height)),
+                    ));
+                    uvs.push([u as f32, v as f32]);
+                }
+            }
+
+            let mut geometry = geo::Geometry::MultiPoint(geo::MultiPoint(points));
+            if self.source_crs != self.target_crs {
+                transform::Transformer::setup(&self.source_crs, &self.target_crs)?
+                    .transform(&mut geometry)
+                    .map_err(transform::Error::from)?;
+            }
+            let positions = geo::CoordsIter::coords_iter(&geometry)
+                .map(|coord| [coord.x as f32, coord.y as f32, 0.])
+                .collect::<Vec<_>>();
+
+            let mut indices = vec![];
+            for row in 0..GRID_CELLS {
+                for column in 0..GRID_CELLS {
+                    let top_left = row * (GRID_CELLS + 1) + column;
+                    let bottom_left = top_left + GRID_CELLS + 1;
+                    indices.extend([
+                        top_left,
+                        bottom_left,
+                        top_left + 1,
+                        top_left + 1,
+                        bottom_left,
+                        bottom_left + 1,
+                    ]);
+                }
+            }
+
+            let mesh = mesh::Mesh::new(
+                mesh::PrimitiveTopology::TriangleList,
+                RenderAssetUsages::RENDER_WORLD,
+            )
+            .with_inserted_attribute(mesh::Mesh::ATTRIBUTE_POSITION, positions)
+            .with_inserted_attribute(mesh::Mesh::ATTRIBUTE_UV_0, uvs)
+            .with_inserted_indices(mesh::Indices::U32(indices));
+
+            Ok(RasterMeshJobOutcome {
+                raster_id: self.raster_id,
+                target_crs: self.target_crs,
+                mesh,
+            })
+        })
+    }
+}
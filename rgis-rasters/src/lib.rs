@@ -0,0 +1,66 @@
+#![warn(
+    clippy::unwrap_used,
+    clippy::cast_lossless,
+    clippy::unimplemented,
+    clippy::indexing_slicing,
+    clippy::expect_used
+)]
+
+//! Rasters are images, e.g. GeoTIFF orthophotos or elevation models, drawn under the layers. Each
+//! raster is a texture stretched over a grid mesh, so reprojecting it only moves the grid's
+//! vertices.
+
+use bevy::prelude::*;
+
+mod jobs;
+mod systems;
+
+#[derive(Default, Resource)]
+pub struct Rasters {
+    // Ordered by when they were loaded
+    data: Vec<Raster>,
+}
+
+impl Rasters {
+    pub fn iter(&self) -> impl Iterator<Item = &Raster> {
+        self.data.iter()
+    }
+
+    fn get_mut(&mut self, raster_id: rgis_layer_id::LayerId) -> Option<&mut Raster> {
+        self.data.iter_mut().find(|raster| raster.id == raster_id)
+    }
+
+    fn remove(&mut self, raster_id: rgis_layer_id::LayerId) -> Option<Raster> {
+        let index = self.data.iter().position(|raster| raster.id == raster_id)?;
+        Some(self.data.remove(index))
+    }
+}
+
+pub struct Raster {
+    pub id: rgis_layer_id::LayerId,
+    pub name: String,
+    pub crs: transform::Crs,
+    pub width: u32,
+    pub height: u32,
+    geo_transform: geo_file_loader::GeoTransform,
+    image: Handle<Image>,
+    /// The raster's mesh, once it's built for the current CRS
+    entity: Option<Entity>,
+}
+
+impl Raster {
+    fn despawn(&mut self, commands: &mut Commands) {
+        if let Some(entity) = self.entity.take() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rasters>();
+        systems::configure(app);
+    }
+}
@@ -0,0 +1,144 @@
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+
+use crate::jobs::RasterMeshJob;
+
+// The 2D camera is at z=999.9 and sees 1000 units away, so rasters are drawn between z=-0.1 and
+// the bottom layer at z=0, above tilesets.
+const RASTER_Z: f32 = -0.02;
+
+pub fn configure(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            handle_create_raster_events,
+            handle_delete_raster_events,
+            handle_crs_changed_events,
+            handle_raster_mesh_job,
+        )
+            .chain(),
+    );
+}
+
+fn handle_create_raster_events(
+    mut create_raster_events: ResMut<Events<rgis_events::CreateRasterEvent>>,
+    mut rasters: ResMut<crate::Rasters>,
+    mut images: ResMut<Assets<Image>>,
+    rgis_settings: Res<rgis_settings::RgisSettings>,
+    mut job_spawner: bevy_jobs::JobSpawner,
+) {
+    for event in create_raster_events.drain() {
+        let raster = event.raster;
+        let image = Image::new(
+            Extent3d {
+                width: raster.width,
+                height: raster.height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            raster.rgba,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::RENDER_WORLD,
+        );
+        let id = rgis_layer_id::LayerId::new();
+        job_spawner.spawn(RasterMeshJob {
+            raster_id: id,
+            width: raster.width,
+            height: raster.height,
+            geo_transform: raster.geo_transform,
+            source_crs: event.source_crs.clone(),
+            target_crs: rgis_settings.target_crs.clone(),
+        });
+        rasters.data.push(crate::Raster {
+            id,
+            name: event.name,
+            crs: event.source_crs,
+            width: raster.width,
+            height: raster.height,
+            geo_transform: raster.geo_transform,
+            image: images.add(image),
+            entity: None,
+        });
+    }
+}
+
+fn handle_delete_raster_events(
+    mut delete_raster_event_reader: EventReader<rgis_events::DeleteRasterEvent>,
+    mut rasters: ResMut<crate::Rasters>,
+    mut images: ResMut<Assets<Image>>,
+    mut commands: Commands,
+) {
+    for event in delete_raster_event_reader.read() {
+        if let Some(mut raster) = rasters.remove(event.0) {
+            raster.despawn(&mut commands);
+            images.remove(&raster.image);
+        }
+    }
+}
+
+/// The raster is hidden until its mesh is rebuilt in the new CRS
+fn handle_crs_changed_events(
+    mut crs_changed_event_reader: EventReader<rgis_events::CrsChangedEvent>,
+    mut rasters: ResMut<crate::Rasters>,
+    mut job_spawner: bevy_jobs::JobSpawner,
+    mut commands: Commands,
+) {
+    let Some(event) = crs_changed_event_reader.read().last() else {
+        return;
+    };
+    for raster in &mut rasters.data {
+        raster.despawn(&mut commands);
+        job_spawner.spawn(RasterMeshJob {
+            raster_id: raster.id,
+            width: raster.width,
+            height: raster.height,
+            geo_transform: raster.geo_transform,
+            source_crs: raster.crs.clone(),
+            target_crs: event.new_crs.clone(),
+        });
+    }
+}
+
+fn handle_raster_mesh_job(
+    mut finished_jobs: bevy_jobs::FinishedJobs,
+    mut rasters: ResMut<crate::Rasters>,
+    rgis_settings: Res<rgis_settings::RgisSettings>,
+    mut commands: Commands,
+    mut assets_meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    while let Some(outcome) = finished_jobs.take_next::<RasterMeshJob>() {
+        let outcome = match outcome {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                bevy::log::error!("Could not reproject raster: {}", e);
+                continue;
+            }
+        };
+        // The mesh was built for a previous CRS
+        if outcome.target_crs != rgis_settings.target_crs {
+            continue;
+        }
+        let Some(raster) = rasters.get_mut(outcome.raster_id) else {
+            continue;
+        };
+        raster.despawn(&mut commands);
+        let entity = commands
+            .spawn(bevy::sprite::MaterialMesh2dBundle {
+                material: materials.add(ColorMaterial {
+                    color: Color::WHITE,
+                    texture: Some(raster.image.clone()),
+                }),
+                mesh: bevy::sprite::Mesh2dHandle(assets_meshes.add(outcome.mesh)),
+                transform: Transform::from_xyz(0., 0., RASTER_Z),
+                ..Default::default()
+            })
+            .id();
+        raster.entity = Some(entity);
+    }
+}
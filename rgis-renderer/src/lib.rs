@@ -9,10 +9,14 @@
 use bevy::prelude::*;
 
 mod jobs;
+mod map_image;
+#[cfg(not(target_arch = "wasm32"))]
+mod readback;
+mod svg;
 mod systems;
 mod z_index;
 
-use z_index::ZIndex;
+pub use z_index::ZIndex;
 
 #[derive(Clone, Copy, Component, PartialEq, Eq)]
 pub enum RenderEntityType {
@@ -30,6 +34,7 @@ pub struct Plugin;
 impl bevy::app::Plugin for Plugin {
     fn build(&self, app: &mut App) {
         systems::configure(app);
+        map_image::configure(app);
     }
 }
 
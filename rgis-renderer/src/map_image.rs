@@ -0,0 +1,42 @@
+use bevy::prelude::*;
+
+fn handle_export_map_image_events(
+    mut export_map_image_event_reader: EventReader<rgis_events::ExportMapImageEvent>,
+    mut map_image_exported_event_writer: EventWriter<rgis_events::MapImageExportedEvent>,
+    layers: Res<rgis_layers::Layers>,
+    clear_color: Res<ClearColor>,
+) {
+    for event in export_map_image_event_reader.read() {
+        match event.format {
+            rgis_events::MapImageFormat::Svg => {
+                let svg = crate::svg::map_svg(
+                    &layers,
+                    event.rect,
+                    event.width_px,
+                    event.height_px,
+                    event.dpi,
+                    clear_color.0,
+                );
+                map_image_exported_event_writer.send(rgis_events::MapImageExportedEvent {
+                    format: rgis_events::MapImageFormat::Svg,
+                    bytes: svg.into_bytes(),
+                });
+            }
+            // Rendered by `crate::readback`
+            #[cfg(not(target_arch = "wasm32"))]
+            rgis_events::MapImageFormat::Png => (),
+            // Waiting on the GPU to copy the image out blocks, which WebGL doesn't allow
+            #[cfg(target_arch = "wasm32")]
+            rgis_events::MapImageFormat::Png => {
+                bevy::log::error!("Exporting the map as a PNG is not supported on the web");
+            }
+        }
+    }
+}
+
+pub fn configure(app: &mut App) {
+    app.add_systems(Update, handle_export_map_image_events);
+
+    #[cfg(not(target_arch = "wasm32"))]
+    crate::readback::configure(app);
+}
@@ -0,0 +1,402 @@
+//! Renders the map to a PNG. A second camera renders into an offscreen image for a single frame,
+//! then the image is copied out of the GPU and encoded.
+//!
+//! The camera only exists from `PostUpdate` until the next frame's `First`, so systems in
+//! `Update` that expect a single camera never see it.
+
+use bevy::{
+    prelude::*,
+    render::{
+        camera::{CameraUpdateSystem, RenderTarget},
+        render_asset::{RenderAssetUsages, RenderAssets},
+        render_graph::{self, RenderGraph, RenderLabel},
+        render_resource::{
+            Buffer, BufferDescriptor, BufferUsages, Extent3d, ImageCopyBuffer, ImageDataLayout,
+            Maintain, MapMode, TextureDimension, TextureFormat, TextureUsages,
+        },
+        renderer::{RenderContext, RenderDevice},
+        texture::GpuImage,
+        Extract, ExtractSchedule, Render, RenderApp, RenderSet,
+    },
+};
+use std::sync;
+
+/// The largest texture size all GPUs support
+const MAX_SIZE_PX: u32 = 8192;
+
+const BYTES_PER_PIXEL: u32 = 4;
+
+/// Where the render world puts the image's pixels once they've been copied out of the GPU
+type Output = sync::Arc<sync::Mutex<Option<Result<Vec<u8>, String>>>>;
+
+#[derive(Resource)]
+struct MapImageCapture {
+    image: Handle<Image>,
+    rect: geo::Rect,
+    width_px: u32,
+    height_px: u32,
+    dpi: f32,
+    state: CaptureState,
+    output: Output,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CaptureState {
+    /// The camera will be spawned at the end of this frame
+    Requested,
+    /// The camera renders into the image this frame
+    Rendering(Entity),
+    /// The camera is gone, waiting on the image to be copied out of the GPU
+    ReadingBack,
+}
+
+#[derive(Component)]
+struct MapImageCamera;
+
+fn handle_export_map_image_events(
+    mut commands: Commands,
+    mut export_map_image_event_reader: EventReader<rgis_events::ExportMapImageEvent>,
+    mut images: ResMut<Assets<Image>>,
+    capture: Option<Res<MapImageCapture>>,
+) {
+    let mut is_capturing = capture.is_some();
+    for event in export_map_image_event_reader.read() {
+        if event.format != rgis_events::MapImageFormat::Png {
+            continue;
+        }
+        if is_capturing {
+            bevy::log::warn!("A map image is already being exported");
+            continue;
+        }
+        let (width_px, height_px) = clamp_size(event.width_px, event.height_px);
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: width_px,
+                height: height_px,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 0],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+        image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING
+            | TextureUsages::COPY_SRC
+            | TextureUsages::RENDER_ATTACHMENT;
+        commands.insert_resource(MapImageCapture {
+            image: images.add(image),
+            rect: *event.rect.as_raw(),
+            width_px,
+            height_px,
+            dpi: event.dpi,
+            state: CaptureState::Requested,
+            output: Output::default(),
+        });
+        is_capturing = true;
+    }
+}
+
+/// Scales the size down, keeping its aspect ratio, if either side is larger than the GPU allows
+fn clamp_size(width_px: u32, height_px: u32) -> (u32, u32) {
+    let largest_px = width_px.max(height_px);
+    if largest_px <= MAX_SIZE_PX {
+        return (width_px.max(1), height_px.max(1));
+    }
+    bevy::log::warn!(
+        "The map image is larger than {MAX_SIZE_PX} pixels on a side and will be scaled down"
+    );
+    let scale = f64::from(MAX_SIZE_PX) / f64::from(largest_px);
+    (
+        ((f64::from(width_px) * scale) as u32).max(1),
+        ((f64::from(height_px) * scale) as u32).max(1),
+    )
+}
+
+fn spawn_map_image_camera(mut commands: Commands, capture: Option<ResMut<MapImageCapture>>) {
+    let Some(mut capture) = capture else {
+        return;
+    };
+    if capture.state != CaptureState::Requested {
+        return;
+    }
+    let center = capture.rect.center();
+    // Projected units per pixel
+    let scale = (capture.rect.width() / f64::from(capture.width_px)) as f32;
+
+    let mut camera_bundle = Camera2dBundle::default();
+    camera_bundle.camera.target = RenderTarget::Image(capture.image.clone());
+    // Render before the window's camera
+    camera_bundle.camera.order = -1;
+    camera_bundle.transform.translation.x = center.x as f32;
+    camera_bundle.transform.translation.y = center.y as f32;
+    camera_bundle.transform.scale = Vec3::new(scale, scale, 1.);
+
+    let camera = commands.spawn((camera_bundle, MapImageCamera)).id();
+    capture.state = CaptureState::Rendering(camera);
+}
+
+fn despawn_map_image_camera(mut commands: Commands, capture: Option<ResMut<MapImageCapture>>) {
+    let Some(mut capture) = capture else {
+        return;
+    };
+    if let CaptureState::Rendering(camera) = capture.state {
+        commands.entity(camera).despawn();
+        capture.state = CaptureState::ReadingBack;
+    }
+}
+
+fn handle_map_image_read_back(
+    mut commands: Commands,
+    capture: Option<Res<MapImageCapture>>,
+    mut job_spawner: bevy_jobs::JobSpawner,
+) {
+    let Some(capture) = capture else {
+        return;
+    };
+    if capture.state != CaptureState::ReadingBack {
+        return;
+    }
+    let Some(result) = capture
+        .output
+        .lock()
+        .ok()
+        .and_then(|mut output| output.take())
+    else {
+        return;
+    };
+    commands.remove_resource::<MapImageCapture>();
+
+    match result {
+        Ok(rgba) => {
+            job_spawner.spawn(EncodePngJob {
+                rgba,
+                width_px: capture.width_px,
+                height_px: capture.height_px,
+                dpi: capture.dpi,
+            });
+        }
+        Err(e) => bevy::log::error!("Could not copy the map image from the GPU: {}", e),
+    }
+}
+
+fn handle_encode_png_job(
+    mut finished_jobs: bevy_jobs::FinishedJobs,
+    mut map_image_exported_event_writer: EventWriter<rgis_events::MapImageExportedEvent>,
+) {
+    while let Some(outcome) = finished_jobs.take_next::<EncodePngJob>() {
+        match outcome {
+            Ok(bytes) => {
+                map_image_exported_event_writer.send(rgis_events::MapImageExportedEvent {
+                    format: rgis_events::MapImageFormat::Png,
+                    bytes,
+                });
+            }
+            Err(e) => bevy::log::error!("Could not encode the map image: {}", e),
+        }
+    }
+}
+
+struct EncodePngJob {
+    rgba: Vec<u8>,
+    width_px: u32,
+    height_px: u32,
+    dpi: f32,
+}
+
+impl bevy_jobs::Job for EncodePngJob {
+    type Outcome = Result<Vec<u8>, png::EncodingError>;
+
+    fn name(&self) -> String {
+        "Encoding map image".to_string()
+    }
+
+    fn perform(self, _: bevy_jobs::Context) -> bevy_jobs::AsyncReturn<Self::Outcome> {
+        Box::pin(async move {
+            let mut bytes = vec![];
+            let mut encoder = png::Encoder::new(&mut bytes, self.width_px, self.height_px);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+            // PNG stores the resolution in pixels per meter
+            let pixels_per_meter = (f64::from(self.dpi) / 0.0254).round() as u32;
+            encoder.set_pixel_dims(Some(png::PixelDimensions {
+                xppu: pixels_per_meter,
+                yppu: pixels_per_meter,
+                unit: png::Unit::Meter,
+            }));
+            let mut writer = encoder.write_header()?;
+            writer.write_image_data(&self.rgba)?;
+            writer.finish()?;
+            Ok(bytes)
+        })
+    }
+}
+
+/// Render world copy of a [`MapImageCapture`] that's being rendered
+#[derive(Resource)]
+struct MapImageReadback {
+    image: AssetId<Image>,
+    width_px: u32,
+    height_px: u32,
+    /// Rows copied out of a texture must be aligned, so they may be padded
+    padded_bytes_per_row: u32,
+    buffer: Option<Buffer>,
+    output: Output,
+}
+
+fn extract_map_image_capture(
+    mut commands: Commands,
+    capture: Extract<Option<Res<MapImageCapture>>>,
+) {
+    let Some(capture) = capture.as_ref() else {
+        return;
+    };
+    if let CaptureState::Rendering(_) = capture.state {
+        commands.insert_resource(MapImageReadback {
+            image: capture.image.id(),
+            width_px: capture.width_px,
+            height_px: capture.height_px,
+            padded_bytes_per_row: RenderDevice::align_copy_bytes_per_row(
+                (capture.width_px * BYTES_PER_PIXEL) as usize,
+            ) as u32,
+            buffer: None,
+            output: capture.output.clone(),
+        });
+    }
+}
+
+fn prepare_map_image_readback_buffer(
+    readback: Option<ResMut<MapImageReadback>>,
+    render_device: Res<RenderDevice>,
+) {
+    let Some(mut readback) = readback else {
+        return;
+    };
+    if readback.buffer.is_none() {
+        readback.buffer = Some(render_device.create_buffer(&BufferDescriptor {
+            label: Some("map_image_readback_buffer"),
+            size: u64::from(readback.padded_bytes_per_row) * u64::from(readback.height_px),
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        }));
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct MapImageReadbackLabel;
+
+/// Copies the rendered image into the readback buffer, after every camera has rendered
+#[derive(Default)]
+struct MapImageReadbackNode;
+
+impl render_graph::Node for MapImageReadbackNode {
+    fn run(
+        &self,
+        _graph: &mut render_graph::RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), render_graph::NodeRunError> {
+        let (Some(readback), Some(gpu_images)) = (
+            world.get_resource::<MapImageReadback>(),
+            world.get_resource::<RenderAssets<GpuImage>>(),
+        ) else {
+            return Ok(());
+        };
+        let (Some(buffer), Some(gpu_image)) =
+            (readback.buffer.as_ref(), gpu_images.get(readback.image))
+        else {
+            return Ok(());
+        };
+        render_context.command_encoder().copy_texture_to_buffer(
+            gpu_image.texture.as_image_copy(),
+            ImageCopyBuffer {
+                buffer,
+                layout: ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(readback.padded_bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            Extent3d {
+                width: readback.width_px,
+                height: readback.height_px,
+                depth_or_array_layers: 1,
+            },
+        );
+        Ok(())
+    }
+}
+
+/// Waits on the GPU to finish the frame, then hands the pixels to the main world
+fn read_back_map_image(
+    mut commands: Commands,
+    readback: Option<Res<MapImageReadback>>,
+    render_device: Res<RenderDevice>,
+) {
+    let Some(readback) = readback else {
+        return;
+    };
+    let Some(buffer) = readback.buffer.as_ref() else {
+        return;
+    };
+    commands.remove_resource::<MapImageReadback>();
+
+    let buffer_slice = buffer.slice(..);
+    let (sender, receiver) = sync::mpsc::channel();
+    render_device.map_buffer(&buffer_slice, MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    render_device.poll(Maintain::wait());
+    let result = match receiver.recv() {
+        Ok(Ok(())) => {
+            let row_len = (readback.width_px * BYTES_PER_PIXEL) as usize;
+            let rgba = buffer_slice
+                .get_mapped_range()
+                .chunks(readback.padded_bytes_per_row as usize)
+                .flat_map(|row| row.iter().take(row_len))
+                .copied()
+                .collect::<Vec<_>>();
+            buffer.unmap();
+            Ok(rgba)
+        }
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    if let Ok(mut output) = readback.output.lock() {
+        *output = Some(result);
+    }
+}
+
+pub fn configure(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            handle_export_map_image_events,
+            handle_map_image_read_back,
+            handle_encode_png_job,
+        ),
+    )
+    .add_systems(
+        PostUpdate,
+        spawn_map_image_camera.before(CameraUpdateSystem),
+    )
+    .add_systems(First, despawn_map_image_camera);
+
+    let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+        return;
+    };
+    render_app
+        .add_systems(ExtractSchedule, extract_map_image_capture)
+        .add_systems(
+            Render,
+            (
+                prepare_map_image_readback_buffer.in_set(RenderSet::Prepare),
+                read_back_map_image.in_set(RenderSet::Cleanup),
+            ),
+        );
+    let mut render_graph = render_app.world_mut().resource_mut::<RenderGraph>();
+    render_graph.add_node(MapImageReadbackLabel, MapImageReadbackNode);
+    render_graph.add_node_edge(
+        bevy::render::graph::CameraDriverLabel,
+        MapImageReadbackLabel,
+    );
+}
@@ -0,0 +1,248 @@
+use crate::{RenderEntityType, ZIndex};
+use bevy::prelude::*;
+use geo::Intersects;
+use std::fmt::Write;
+
+/// Sizes below are in logical pixels, which are this many to the inch. They're scaled to the DPI
+/// of the image, like the sprites of the PNG export are.
+const LOGICAL_DPI: f64 = 96.;
+/// Points are drawn as a stroke-colored circle with a smaller fill-colored circle on top, like on
+/// the map
+const POINT_STROKE_RADIUS_PX: f64 = 4.;
+const POINT_FILL_RADIUS_PX: f64 = POINT_STROKE_RADIUS_PX * 0.7;
+const STROKE_WIDTH_PX: f64 = 1.;
+
+/// An SVG document of the visible layers within `rect`. Elements are ordered the same way the
+/// map orders its meshes, see [`ZIndex`].
+pub(crate) fn map_svg(
+    layers: &rgis_layers::Layers,
+    rect: geo_projected::Projected<geo::Rect>,
+    width_px: u32,
+    height_px: u32,
+    dpi: f32,
+    background_color: Color,
+) -> String {
+    let rect = *rect.as_raw();
+    let view = View {
+        rect,
+        x_scale: f64::from(width_px) / rect.width(),
+        y_scale: f64::from(height_px) / rect.height(),
+        size_scale: f64::from(dpi) / LOGICAL_DPI,
+    };
+
+    let mut elements = vec![];
+    for (index, layer) in layers.iter_bottom_to_top().enumerate() {
+        if !layer.visible {
+            continue;
+        }
+        let Some(feature_collection) = layer.projected_feature_collection.as_ref() else {
+            continue;
+        };
+        let layer_index = rgis_layers::LayerIndex(index);
+        for feature in &feature_collection.as_raw().features {
+            let (Some(geometry), Some(bounding_rect)) =
+                (feature.geometry.as_ref(), feature.bounding_rect)
+            else {
+                continue;
+            };
+            if !bounding_rect.intersects(&rect) {
+                continue;
+            }
+            push_geometry_elements(&mut elements, geometry, layer, layer_index, &view);
+        }
+    }
+    // Stable, so elements with the same z-index keep their feature order
+    elements.sort_by_key(|element| element.z_index.0);
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width_px}" height="{height_px}" viewBox="0 0 {width_px} {height_px}">"#
+    );
+    svg.push('\n');
+    let _ = writeln!(
+        svg,
+        r#"<rect width="100%" height="100%" {}/>"#,
+        paint("fill", background_color)
+    );
+    for element in elements {
+        svg.push_str(&element.svg);
+        svg.push('\n');
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+struct Element {
+    z_index: ZIndex,
+    svg: String,
+}
+
+/// Converts projected coordinates to SVG pixels, which grow rightward and downward from the
+/// top-left corner
+struct View {
+    rect: geo::Rect,
+    x_scale: f64,
+    y_scale: f64,
+    /// From logical pixels to image pixels, for point radii and stroke widths
+    size_scale: f64,
+}
+
+impl View {
+    fn px(&self, coord: geo::Coord) -> (f64, f64) {
+        (
+            (coord.x - self.rect.min().x) * self.x_scale,
+            (self.rect.max().y - coord.y) * self.y_scale,
+        )
+    }
+
+    fn path_data<'a>(&self, line_strings: impl Iterator<Item = &'a geo::LineString>) -> String {
+        let mut data = String::new();
+        for line_string in line_strings {
+            for (i, coord) in line_string.coords().enumerate() {
+                let (x, y) = self.px(*coord);
+                let command = if i == 0 { 'M' } else { 'L' };
+                let _ = write!(data, "{command}{x:.2},{y:.2} ");
+            }
+            if line_string.is_closed() {
+                data.push('Z');
+            }
+        }
+        data
+    }
+}
+
+fn push_geometry_elements(
+    elements: &mut Vec<Element>,
+    geometry: &geo::Geometry,
+    layer: &rgis_layers::Layer,
+    layer_index: rgis_layers::LayerIndex,
+    view: &View,
+) {
+    match geometry {
+        geo::Geometry::Point(point) => {
+            push_point_elements(elements, point.0, layer, layer_index, view);
+        }
+        geo::Geometry::MultiPoint(multi_point) => {
+            for point in multi_point {
+                push_point_elements(elements, point.0, layer, layer_index, view);
+            }
+        }
+        geo::Geometry::Line(line) => {
+            push_line_string_element(elements, &(*line).into(), layer, layer_index, view);
+        }
+        geo::Geometry::LineString(line_string) => {
+            push_line_string_element(elements, line_string, layer, layer_index, view);
+        }
+        geo::Geometry::MultiLineString(multi_line_string) => {
+            for line_string in multi_line_string {
+                push_line_string_element(elements, line_string, layer, layer_index, view);
+            }
+        }
+        geo::Geometry::Polygon(polygon) => {
+            push_polygon_elements(elements, polygon, layer, layer_index, view);
+        }
+        geo::Geometry::MultiPolygon(multi_polygon) => {
+            for polygon in multi_polygon {
+                push_polygon_elements(elements, polygon, layer, layer_index, view);
+            }
+        }
+        geo::Geometry::Rect(rect) => {
+            push_polygon_elements(elements, &rect.to_polygon(), layer, layer_index, view);
+        }
+        geo::Geometry::Triangle(triangle) => {
+            push_polygon_elements(elements, &triangle.to_polygon(), layer, layer_index, view);
+        }
+        geo::Geometry::GeometryCollection(geometry_collection) => {
+            for geometry in geometry_collection {
+                push_geometry_elements(elements, geometry, layer, layer_index, view);
+            }
+        }
+    }
+}
+
+fn push_point_elements(
+    elements: &mut Vec<Element>,
+    coord: geo::Coord,
+    layer: &rgis_layers::Layer,
+    layer_index: rgis_layers::LayerIndex,
+    view: &View,
+) {
+    let (x, y) = view.px(coord);
+    let stroke_radius = POINT_STROKE_RADIUS_PX * view.size_scale;
+    let fill_radius = POINT_FILL_RADIUS_PX * view.size_scale;
+    elements.push(Element {
+        z_index: ZIndex::calculate(layer_index, RenderEntityType::PointStroke),
+        svg: format!(
+            r#"<circle cx="{x:.2}" cy="{y:.2}" r="{stroke_radius:.2}" {}/>"#,
+            paint("fill", layer.color.stroke)
+        ),
+    });
+    if let Some(fill) = layer.color.fill {
+        elements.push(Element {
+            z_index: ZIndex::calculate(layer_index, RenderEntityType::PointFill),
+            svg: format!(
+                r#"<circle cx="{x:.2}" cy="{y:.2}" r="{fill_radius:.2}" {}/>"#,
+                paint("fill", fill)
+            ),
+        });
+    }
+}
+
+fn push_line_string_element(
+    elements: &mut Vec<Element>,
+    line_string: &geo::LineString,
+    layer: &rgis_layers::Layer,
+    layer_index: rgis_layers::LayerIndex,
+    view: &View,
+) {
+    elements.push(Element {
+        z_index: ZIndex::calculate(layer_index, RenderEntityType::LineString),
+        svg: stroke_path(
+            &view.path_data(std::iter::once(line_string)),
+            layer.color.stroke,
+            view,
+        ),
+    });
+}
+
+fn push_polygon_elements(
+    elements: &mut Vec<Element>,
+    polygon: &geo::Polygon,
+    layer: &rgis_layers::Layer,
+    layer_index: rgis_layers::LayerIndex,
+    view: &View,
+) {
+    let path_data = view.path_data(std::iter::once(polygon.exterior()).chain(polygon.interiors()));
+    if let Some(fill) = layer.color.fill {
+        elements.push(Element {
+            z_index: ZIndex::calculate(layer_index, RenderEntityType::Polygon),
+            svg: format!(
+                r#"<path d="{path_data}" fill-rule="evenodd" {}/>"#,
+                paint("fill", fill)
+            ),
+        });
+    }
+    // Borders are separate elements, so they're drawn above every fill of the layer
+    elements.push(Element {
+        z_index: ZIndex::calculate(layer_index, RenderEntityType::LineString),
+        svg: stroke_path(&path_data, layer.color.stroke, view),
+    });
+}
+
+fn stroke_path(path_data: &str, color: Color, view: &View) -> String {
+    let stroke_width = STROKE_WIDTH_PX * view.size_scale;
+    format!(
+        r#"<path d="{path_data}" fill="none" stroke-width="{stroke_width:.2}" {}/>"#,
+        paint("stroke", color)
+    )
+}
+
+/// SVG 1.1 colors can't hold an alpha channel, so it's set as a separate opacity attribute
+fn paint(attribute: &str, color: Color) -> String {
+    let srgba = color.to_srgba();
+    let [red, green, blue] =
+        [srgba.red, srgba.green, srgba.blue].map(|c| (c.clamp(0., 1.) * 255.).round() as u8);
+    format!(
+        r##"{attribute}="#{red:02x}{green:02x}{blue:02x}" {attribute}-opacity="{}""##,
+        srgba.alpha.clamp(0., 1.)
+    )
+}
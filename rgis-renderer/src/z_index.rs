@@ -1,5 +1,6 @@
 use crate::RenderEntityType;
 
+/// The z coordinate of a rendered entity. Entities with a higher z-index are drawn on top.
 pub struct ZIndex(pub usize);
 
 impl ZIndex {
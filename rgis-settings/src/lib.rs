@@ -10,7 +10,7 @@ use bevy::prelude::*;
 
 mod systems;
 
-static DEFAULT_TARGET_CRS: u16 = 3857;
+const DEFAULT_TARGET_CRS: transform::Crs = transform::Crs::Epsg(3857);
 
 #[derive(PartialEq, Eq, Copy, Clone)]
 pub enum Tool {
@@ -20,7 +20,7 @@ pub enum Tool {
 
 #[derive(Resource)]
 pub struct RgisSettings {
-    pub target_crs_epsg_code: u16,
+    pub target_crs: transform::Crs,
     pub current_tool: Tool,
 }
 
@@ -29,7 +29,7 @@ pub struct Plugin;
 impl bevy::prelude::Plugin for Plugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.insert_resource(RgisSettings {
-            target_crs_epsg_code: DEFAULT_TARGET_CRS,
+            target_crs: DEFAULT_TARGET_CRS,
             current_tool: Tool::Pan,
         })
         .add_systems(Update, systems::handle_crs_changed_events);
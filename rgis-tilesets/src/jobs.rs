@@ -0,0 +1,112 @@
+use std::{path, sync};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("{0}")]
+    FileLoader(#[from] geo_file_loader::Error),
+    #[error("{0}")]
+    Transform(#[from] transform::Error),
+}
+
+pub struct OpenTilesetJob {
+    pub path: path::PathBuf,
+}
+
+impl bevy_jobs::Job for OpenTilesetJob {
+    type Outcome = Result<geo_file_loader::Tileset, geo_file_loader::Error>;
+    const JOB_TYPE: bevy_jobs::JobType = bevy_jobs::JobType::Io;
+
+    fn name(&self) -> String {
+        format!("Opening tileset '{}'", self.path.display())
+    }
+
+    fn perform(self, _: bevy_jobs::Context) -> bevy_jobs::AsyncReturn<Self::Outcome> {
+        Box::pin(async move { geo_file_loader::Tileset::open(&self.path) })
+    }
+}
+
+pub struct TileLoadingJob {
+    pub tileset_id: rgis_layer_id::LayerId,
+    pub archive: sync::Arc<geo_file_loader::Tileset>,
+    pub tile: geo_file_loader::TileId,
+    pub target_crs: transform::Crs,
+}
+
+pub struct TileLoadingJobOutcome {
+    pub tileset_id: rgis_layer_id::LayerId,
+    pub tile: geo_file_loader::TileId,
+    pub target_crs: transform::Crs,
+    /// The meshes of each of the tile's layers, bottom to top
+    pub meshes: Result<Vec<(String, geo_bevy::GeometryMesh)>, Error>,
+}
+
+impl bevy_jobs::Job for TileLoadingJob {
+    type Outcome = TileLoadingJobOutcome;
+    const JOB_TYPE: bevy_jobs::JobType = bevy_jobs::JobType::Io;
+
+    fn name(&self) -> String {
+        format!("Loading tile {}", self.tile)
+    }
+
+    fn perform(self, _: bevy_jobs::Context) -> bevy_jobs::AsyncReturn<Self::Outcome> {
+        Box::pin(async move {
+            TileLoadingJobOutcome {
+                meshes: load_meshes(&self.archive, self.tile, &self.target_crs),
+                tileset_id: self.tileset_id,
+                tile: self.tile,
+                target_crs: self.target_crs,
+            }
+        })
+    }
+}
+
+fn load_meshes(
+    archive: &geo_file_loader::Tileset,
+    tile: geo_file_loader::TileId,
+    target_crs: &transform::Crs,
+) -> Result<Vec<(String, geo_bevy::GeometryMesh)>, Error> {
+    let transformer = if *target_crs == crate::WEB_MERCATOR {
+        None
+    } else {
+        Some(transform::Transformer::setup(
+            &crate::WEB_MERCATOR,
+            target_crs,
+        )?)
+    };
+
+    let mut meshes = vec![];
+    for layer in archive.load_tile(tile)? {
+        let name = layer.name.unwrap_or_default();
+        // Tile layers can mix geometry types, e.g. a `water` layer with lakes and rivers, and a
+        // mesh is built for each type
+        let (mut polygons, mut line_strings) = (vec![], vec![]);
+        for geometry in layer.feature_collection.to_geometry_collection() {
+            match geometry {
+                geo::Geometry::Polygon(_) | geo::Geometry::MultiPolygon(_) => {
+                    polygons.push(geometry)
+                }
+                geo::Geometry::LineString(_) | geo::Geometry::MultiLineString(_) => {
+                    line_strings.push(geometry)
+                }
+                // Points of basemaps are mostly label positions, which aren't rendered
+                _ => (),
+            }
+        }
+        for geometries in [polygons, line_strings] {
+            if geometries.is_empty() {
+                continue;
+            }
+            let mut geometry =
+                geo::Geometry::GeometryCollection(geo::GeometryCollection(geometries));
+            if let Some(ref transformer) = transformer {
+                transformer
+                    .transform(&mut geometry)
+                    .map_err(transform::Error::from)?;
+            }
+            if let Some(mesh) = geo_bevy::geometry_to_mesh(&geometry) {
+                meshes.push((name.clone(), mesh));
+            }
+        }
+    }
+    Ok(meshes)
+}
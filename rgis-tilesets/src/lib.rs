@@ -0,0 +1,118 @@
+#![warn(
+    clippy::unwrap_used,
+    clippy::cast_lossless,
+    clippy::unimplemented,
+    clippy::indexing_slicing,
+    clippy::expect_used
+)]
+
+//! Tilesets are local `.mbtiles` and `.pmtiles` archives of vector tiles. Unlike layers, their
+//! features are never all loaded: only the tiles covering the visible part of the map are
+//! decoded and rendered, and they're despawned once they're out of view.
+
+use bevy::prelude::*;
+use std::{collections::HashMap, sync};
+
+mod jobs;
+mod systems;
+
+const WEB_MERCATOR: transform::Crs = transform::Crs::Epsg(3857);
+
+/// Zooming out past a tileset's minimum zoom level would need too many tiles, so nothing is
+/// loaded instead.
+const MAX_VISIBLE_TILES: usize = 64;
+
+#[derive(Default, Resource)]
+pub struct Tilesets {
+    // Ordered by when they were opened
+    data: Vec<Tileset>,
+}
+
+impl Tilesets {
+    pub fn iter(&self) -> impl Iterator<Item = &Tileset> {
+        self.data.iter()
+    }
+
+    fn get_mut(&mut self, tileset_id: rgis_layer_id::LayerId) -> Option<&mut Tileset> {
+        self.data
+            .iter_mut()
+            .find(|tileset| tileset.id == tileset_id)
+    }
+
+    fn remove(&mut self, tileset_id: rgis_layer_id::LayerId) -> Option<Tileset> {
+        let index = self
+            .data
+            .iter()
+            .position(|tileset| tileset.id == tileset_id)?;
+        Some(self.data.remove(index))
+    }
+}
+
+pub struct Tileset {
+    pub id: rgis_layer_id::LayerId,
+    pub name: String,
+    archive: sync::Arc<geo_file_loader::Tileset>,
+    /// Tiles that are loading, failed to load or are on the map
+    tiles: HashMap<geo_file_loader::TileId, TileState>,
+}
+
+impl Tileset {
+    pub fn loaded_tile_count(&self) -> usize {
+        self.tiles
+            .values()
+            .filter(|state| matches!(state, TileState::Loaded(_)))
+            .count()
+    }
+
+    /// The tiles covering `rect`, which is in Web Mercator coordinates and shown `width_px` pixels
+    /// wide. Tiles of the maximum zoom level are used when zoomed in further.
+    fn visible_tiles(&self, rect: geo::Rect, width_px: f32) -> Vec<geo_file_loader::TileId> {
+        let meters_per_pixel = rect.width() / f64::from(width_px);
+        let zoom = geo_file_loader::TileId::zoom_for_resolution(meters_per_pixel).round();
+        // Saturating float to int casts clamp negative and NaN zoom levels to 0
+        let z = (zoom as u8)
+            .min(self.archive.max_zoom)
+            .max(self.archive.min_zoom);
+        let tiles = geo_file_loader::TileId::covering(rect, z)
+            .take(MAX_VISIBLE_TILES + 1)
+            .collect::<Vec<_>>();
+        if tiles.len() > MAX_VISIBLE_TILES {
+            vec![]
+        } else {
+            tiles
+        }
+    }
+
+    fn despawn_tiles(&mut self, commands: &mut Commands) {
+        for (_, state) in self.tiles.drain() {
+            state.despawn(commands);
+        }
+    }
+}
+
+enum TileState {
+    Loading,
+    /// Loading the tile failed. It's loaded again the next time the view changes.
+    Failed,
+    /// The entities of the tile's meshes
+    Loaded(Vec<Entity>),
+}
+
+impl TileState {
+    fn despawn(self, commands: &mut Commands) {
+        if let TileState::Loaded(entities) = self {
+            for entity in entities {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Tilesets>();
+        systems::configure(app);
+    }
+}
@@ -0,0 +1,246 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use std::{
+    collections::hash_map,
+    hash::{Hash, Hasher},
+    sync,
+};
+
+use crate::jobs::{OpenTilesetJob, TileLoadingJob};
+
+// The 2D camera is at z=999.9 and sees 1000 units away, so tiles are drawn between z=-0.1 and the
+// bottom layer at z=0.
+const POLYGON_Z: f32 = -0.09;
+const LINE_STRING_Z: f32 = -0.05;
+
+const COLORS: [colorous::Color; 9] = colorous::PASTEL1;
+
+pub fn configure(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            handle_open_tileset_events,
+            handle_open_tileset_job,
+            handle_delete_tileset_events,
+            handle_crs_changed_events,
+            update_visible_tiles.pipe(log_error),
+            handle_tile_loading_job,
+        )
+            .chain(),
+    );
+}
+
+fn log_error(result: In<Result<(), Box<dyn std::error::Error + Send + Sync>>>) {
+    if let Err(e) = result.0 {
+        bevy::log::error!("{}", e);
+    }
+}
+
+fn handle_open_tileset_events(
+    mut open_tileset_event_reader: EventReader<rgis_events::OpenTilesetEvent>,
+    mut job_spawner: bevy_jobs::JobSpawner,
+) {
+    for event in open_tileset_event_reader.read() {
+        job_spawner.spawn(OpenTilesetJob {
+            path: event.0.clone(),
+        });
+    }
+}
+
+fn handle_open_tileset_job(
+    mut finished_jobs: bevy_jobs::FinishedJobs,
+    mut tilesets: ResMut<crate::Tilesets>,
+) {
+    while let Some(outcome) = finished_jobs.take_next::<OpenTilesetJob>() {
+        match outcome {
+            Ok(archive) => tilesets.data.push(crate::Tileset {
+                id: rgis_layer_id::LayerId::new(),
+                name: archive.name.clone(),
+                archive: sync::Arc::new(archive),
+                tiles: Default::default(),
+            }),
+            Err(e) => bevy::log::error!("Could not open tileset: {}", e),
+        }
+    }
+}
+
+fn handle_delete_tileset_events(
+    mut delete_tileset_event_reader: EventReader<rgis_events::DeleteTilesetEvent>,
+    mut tilesets: ResMut<crate::Tilesets>,
+    mut commands: Commands,
+) {
+    for event in delete_tileset_event_reader.read() {
+        if let Some(mut tileset) = tilesets.remove(event.0) {
+            tileset.despawn_tiles(&mut commands);
+        }
+    }
+}
+
+/// Tiles are reloaded in the new CRS by `update_visible_tiles`
+fn handle_crs_changed_events(
+    mut crs_changed_event_reader: EventReader<rgis_events::CrsChangedEvent>,
+    mut tilesets: ResMut<crate::Tilesets>,
+    mut commands: Commands,
+) {
+    if crs_changed_event_reader.read().last().is_none() {
+        return;
+    }
+    for tileset in &mut tilesets.data {
+        tileset.despawn_tiles(&mut commands);
+    }
+}
+
+fn update_visible_tiles(
+    mut tilesets: ResMut<crate::Tilesets>,
+    camera_query: Query<Ref<Transform>, With<Camera>>,
+    windows: Query<Ref<Window>, With<PrimaryWindow>>,
+    rgis_settings: Res<rgis_settings::RgisSettings>,
+    mut job_spawner: bevy_jobs::JobSpawner,
+    mut commands: Commands,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let (Ok(transform), Ok(window)) = (camera_query.get_single(), windows.get_single()) else {
+        return Ok(());
+    };
+    let has_view_changed = transform.is_changed()
+        || window.is_changed()
+        || rgis_settings.is_changed()
+        || tilesets.is_changed();
+    if tilesets.data.is_empty() || !has_view_changed {
+        return Ok(());
+    }
+
+    // Tiles behind the UI panels are loaded too, so the whole window is used
+    let map_area = rgis_units::MapArea {
+        window: &window,
+        left_offset_px: 0.,
+        right_offset_px: 0.,
+        top_offset_px: 0.,
+        bottom_offset_px: 0.,
+    };
+    let Some(rect) = web_mercator_rect(
+        map_area.projected_geo_rect(&transform, &window),
+        &rgis_settings.target_crs,
+    )?
+    else {
+        return Ok(());
+    };
+    let width_px = map_area.size().width;
+
+    for tileset in &mut tilesets.data {
+        let visible_tiles = tileset.visible_tiles(rect, width_px);
+        tileset.tiles.retain(|tile, state| {
+            let is_visible = visible_tiles.contains(tile);
+            if !is_visible {
+                std::mem::replace(state, crate::TileState::Loading).despawn(&mut commands);
+            }
+            is_visible
+        });
+        for tile in visible_tiles {
+            // Tiles that failed to load are retried whenever the view changes
+            match tileset.tiles.entry(tile) {
+                hash_map::Entry::Vacant(entry) => {
+                    entry.insert(crate::TileState::Loading);
+                }
+                hash_map::Entry::Occupied(mut entry)
+                    if matches!(entry.get(), crate::TileState::Failed) =>
+                {
+                    entry.insert(crate::TileState::Loading);
+                }
+                hash_map::Entry::Occupied(_) => continue,
+            }
+            job_spawner.spawn(TileLoadingJob {
+                tileset_id: tileset.id,
+                archive: tileset.archive.clone(),
+                tile,
+                target_crs: rgis_settings.target_crs.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Tiles are in Web Mercator, so the visible area is projected to find the tiles covering it. Web
+/// Mercator doesn't reach the poles, so the area is clamped to the latitudes it covers on the way.
+fn web_mercator_rect(
+    rect: geo_projected::Projected<geo::Rect>,
+    target_crs: &transform::Crs,
+) -> Result<Option<geo::Rect>, Box<dyn std::error::Error + Send + Sync>> {
+    if *target_crs == crate::WEB_MERCATOR {
+        return Ok(Some(rect.0));
+    }
+    let mut geometry = geo::Geometry::from(rect.0);
+    transform::Transformer::setup(target_crs, &transform::Crs::WGS_84)?.transform(&mut geometry)?;
+    let max_latitude = crate::WEB_MERCATOR.max_latitude().unwrap_or(90.);
+    geo::MapCoordsInPlace::map_coords_in_place(&mut geometry, |coord| geo::Coord {
+        x: coord.x,
+        y: coord.y.clamp(-max_latitude, max_latitude),
+    });
+    transform::Transformer::setup(&transform::Crs::WGS_84, &crate::WEB_MERCATOR)?
+        .transform(&mut geometry)?;
+    Ok(geo::BoundingRect::bounding_rect(&geometry))
+}
+
+fn handle_tile_loading_job(
+    mut finished_jobs: bevy_jobs::FinishedJobs,
+    mut tilesets: ResMut<crate::Tilesets>,
+    rgis_settings: Res<rgis_settings::RgisSettings>,
+    mut commands: Commands,
+    mut assets_meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    while let Some(outcome) = finished_jobs.take_next::<TileLoadingJob>() {
+        // The tile was loaded for a previous CRS
+        if outcome.target_crs != rgis_settings.target_crs {
+            continue;
+        }
+        let Some(tileset) = tilesets.get_mut(outcome.tileset_id) else {
+            continue;
+        };
+        // The tile went out of view while it was loading, or an earlier job for the same tile
+        // already loaded it
+        let Some(state) = tileset
+            .tiles
+            .get_mut(&outcome.tile)
+            .filter(|state| matches!(state, crate::TileState::Loading))
+        else {
+            continue;
+        };
+        let meshes = match outcome.meshes {
+            Ok(meshes) => meshes,
+            Err(e) => {
+                bevy::log::error!("Could not load tile {}: {}", outcome.tile, e);
+                *state = crate::TileState::Failed;
+                continue;
+            }
+        };
+
+        let mut entities = vec![];
+        for (layer_name, geometry_mesh) in meshes {
+            let (mesh, z) = match geometry_mesh {
+                // Polygons are clipped at the tile's edges, so their borders aren't drawn
+                geo_bevy::GeometryMesh::Polygon(polygon_mesh) => (polygon_mesh.mesh, POLYGON_Z),
+                geo_bevy::GeometryMesh::LineString(mesh) => (mesh, LINE_STRING_Z),
+                geo_bevy::GeometryMesh::Point(_) => continue,
+            };
+            let entity = commands
+                .spawn(bevy::sprite::MaterialMesh2dBundle {
+                    material: materials.add(layer_color(&layer_name)),
+                    mesh: bevy::sprite::Mesh2dHandle(assets_meshes.add(mesh)),
+                    transform: Transform::from_xyz(0., 0., z),
+                    ..Default::default()
+                })
+                .id();
+            entities.push(entity);
+        }
+        *state = crate::TileState::Loaded(entities);
+    }
+}
+
+/// Each tile layer, e.g. `water` or `roads`, has the same color in every tile
+fn layer_color(layer_name: &str) -> Color {
+    let mut hasher = hash_map::DefaultHasher::new();
+    layer_name.hash(&mut hasher);
+    #[allow(clippy::indexing_slicing)]
+    let color = COLORS[hasher.finish() as usize % COLORS.len()];
+    Color::srgb_u8(color.r, color.g, color.b)
+}
@@ -0,0 +1,375 @@
+//! Longitudes wrap around at the antimeridian, projected coordinates don't: a line from 179° to
+//! -179° is 2° long on the globe, but crosses the whole map once projected. Before reprojecting,
+//! geometries are cut where they cross the meridian opposite the target CRS's central meridian,
+//! for projections whose map is split there, and latitudes are clamped for projections undefined
+//! at the poles. Both happen in geographic coordinates.
+
+use geo::{BooleanOps, BoundingRect, CoordsIter, MapCoordsInPlace, Translate};
+
+pub(crate) struct Cutter {
+    /// The longitude in the middle of the map, `None` if the map isn't split opposite it
+    central_meridian: Option<f64>,
+    max_latitude: Option<f64>,
+}
+
+impl Cutter {
+    /// `None` if the target CRS's map has no seam and is defined at the poles, e.g. a polar
+    /// azimuthal projection
+    pub(crate) fn new(target_crs: &transform::Crs) -> Option<Self> {
+        let central_meridian = target_crs
+            .is_split_at_antimeridian()
+            .then(|| target_crs.central_meridian());
+        let max_latitude = target_crs.max_latitude();
+        if central_meridian.is_none() && max_latitude.is_none() {
+            return None;
+        }
+        Some(Cutter {
+            central_meridian,
+            max_latitude,
+        })
+    }
+
+    /// Returns whether `geometry` changed
+    pub(crate) fn cut(&self, geometry: &mut geo::Geometry) -> bool {
+        let cut = self.cut_at_antimeridian(geometry);
+        let clamped = self.clamp_latitudes(geometry);
+        cut || clamped
+    }
+
+    fn cut_at_antimeridian(&self, geometry: &mut geo::Geometry) -> bool {
+        match self.central_meridian {
+            Some(central_meridian) => Seam { central_meridian }.cut(geometry),
+            None => false,
+        }
+    }
+
+    fn clamp_latitudes(&self, geometry: &mut geo::Geometry) -> bool {
+        let Some(max_latitude) = self.max_latitude else {
+            return false;
+        };
+        if !geometry
+            .coords_iter()
+            .any(|coord| coord.y.abs() > max_latitude)
+        {
+            return false;
+        }
+        geometry.map_coords_in_place(|coord| geo::Coord {
+            x: coord.x,
+            y: coord.y.clamp(-max_latitude, max_latitude),
+        });
+        true
+    }
+}
+
+/// The meridian opposite `central_meridian`, where the map is split
+struct Seam {
+    central_meridian: f64,
+}
+
+impl Seam {
+    /// Returns whether `geometry` changed
+    fn cut(&self, geometry: &mut geo::Geometry) -> bool {
+        match geometry {
+            geo::Geometry::Point(point) => self.wrap_point(point),
+            geo::Geometry::MultiPoint(multi_point) => multi_point
+                .0
+                .iter_mut()
+                .fold(false, |changed, point| self.wrap_point(point) || changed),
+            geo::Geometry::Line(line) if self.needs_cutting(&[line.start, line.end]) => {
+                *geometry = self.cut_line_strings([(*line).into()]).into();
+                true
+            }
+            geo::Geometry::LineString(line_string) if self.needs_cutting(&line_string.0) => {
+                *geometry = self.cut_line_strings([line_string.clone()]).into();
+                true
+            }
+            geo::Geometry::MultiLineString(multi_line_string)
+                if multi_line_string
+                    .0
+                    .iter()
+                    .any(|line_string| self.needs_cutting(&line_string.0)) =>
+            {
+                *geometry = self.cut_line_strings(multi_line_string.0.clone()).into();
+                true
+            }
+            geo::Geometry::Polygon(polygon) if self.polygon_needs_cutting(polygon) => {
+                *geometry = self.cut_polygons([polygon.clone()]).into();
+                true
+            }
+            geo::Geometry::MultiPolygon(multi_polygon)
+                if multi_polygon
+                    .0
+                    .iter()
+                    .any(|polygon| self.polygon_needs_cutting(polygon)) =>
+            {
+                *geometry = self.cut_polygons(multi_polygon.0.clone()).into();
+                true
+            }
+            geo::Geometry::Triangle(triangle) if self.needs_cutting(&triangle.to_array()) => {
+                *geometry = self.cut_polygons([triangle.to_polygon()]).into();
+                true
+            }
+            geo::Geometry::GeometryCollection(geometry_collection) => geometry_collection
+                .0
+                .iter_mut()
+                .fold(false, |changed, geometry| self.cut(geometry) || changed),
+            _ => false,
+        }
+    }
+
+    /// Whether `coords` jump more than half way around the globe, or lie outside the map
+    fn needs_cutting(&self, coords: &[geo::Coord]) -> bool {
+        coords
+            .windows(2)
+            .any(|pair| matches!(pair, [a, b] if (b.x - a.x).abs() > 180.))
+            || coords
+                .iter()
+                .any(|coord| (coord.x - self.central_meridian).abs() > 180.)
+    }
+
+    fn polygon_needs_cutting(&self, polygon: &geo::Polygon) -> bool {
+        std::iter::once(polygon.exterior())
+            .chain(polygon.interiors())
+            .any(|ring| self.needs_cutting(&ring.0))
+    }
+
+    /// Points outside the map are moved a whole turn onto it
+    fn wrap_point(&self, point: &mut geo::Point) -> bool {
+        if (point.x() - self.central_meridian).abs() <= 180. {
+            return false;
+        }
+        point.set_x(point.x() - 360. * self.turns(point.x()));
+        true
+    }
+
+    /// How many whole turns `longitude` is east of the map
+    fn turns(&self, longitude: f64) -> f64 {
+        ((longitude - self.central_meridian) / 360.).round()
+    }
+
+    fn cut_line_strings(
+        &self,
+        line_strings: impl IntoIterator<Item = geo::LineString>,
+    ) -> geo::MultiLineString {
+        line_strings
+            .into_iter()
+            .flat_map(|line_string| {
+                let line_string =
+                    geo::MultiLineString::new(vec![self.unwrap_longitudes(line_string, None)]);
+                self.copies_of_map(line_string.bounding_rect())
+                    .into_iter()
+                    .flat_map(move |(turns, map)| {
+                        map.clip(&line_string, false).translate(-360. * turns, 0.)
+                    })
+            })
+            .collect()
+    }
+
+    fn cut_polygons(&self, polygons: impl IntoIterator<Item = geo::Polygon>) -> geo::MultiPolygon {
+        polygons
+            .into_iter()
+            .flat_map(|polygon| {
+                let (exterior, interiors) = polygon.into_inner();
+                let exterior = close_around_pole(self.unwrap_longitudes(exterior, None));
+                let start = exterior.0.first().map(|coord| coord.x);
+                let interiors = interiors
+                    .into_iter()
+                    .map(|interior| self.unwrap_longitudes(interior, start))
+                    .collect();
+                let polygon = geo::Polygon::new(exterior, interiors);
+                self.copies_of_map(polygon.bounding_rect())
+                    .into_iter()
+                    .flat_map(move |(turns, map)| {
+                        map.intersection(&polygon).translate(-360. * turns, 0.)
+                    })
+            })
+            .collect()
+    }
+
+    /// Removes the jumps at the antimeridian, so longitudes continue past ±180°. The first
+    /// longitude is moved onto the map, or next to `near` if given, e.g. a hole next to its
+    /// polygon's exterior.
+    fn unwrap_longitudes(
+        &self,
+        line_string: geo::LineString,
+        near: Option<f64>,
+    ) -> geo::LineString {
+        let mut previous = None::<(f64, f64)>;
+        line_string
+            .into_iter()
+            .map(|coord| {
+                let x = match previous {
+                    Some((previous_x, unwrapped_x)) => {
+                        let delta = coord.x - previous_x;
+                        unwrapped_x + delta - 360. * (delta / 360.).round()
+                    }
+                    None => match near {
+                        Some(near) => coord.x - 360. * ((coord.x - near) / 360.).round(),
+                        None => coord.x - 360. * self.turns(coord.x),
+                    },
+                };
+                previous = Some((coord.x, x));
+                geo::Coord { x, y: coord.y }
+            })
+            .collect()
+    }
+
+    /// The copies of the map, a whole number of turns apart, that `bounding_rect` overlaps
+    fn copies_of_map(&self, bounding_rect: Option<geo::Rect>) -> Vec<(f64, geo::Polygon)> {
+        let Some(bounding_rect) = bounding_rect else {
+            return vec![];
+        };
+        let first = self.turns(bounding_rect.min().x) as i32;
+        let last = self.turns(bounding_rect.max().x) as i32;
+        (first..=last)
+            .map(|turns| {
+                let turns = f64::from(turns);
+                let west = self.central_meridian - 180. + 360. * turns;
+                // Taller than the globe, so rings closed at the poles aren't cut there
+                let map = geo::Rect::new(
+                    geo::coord! { x: west, y: -180. },
+                    geo::coord! { x: west + 360., y: 180. },
+                );
+                (turns, map.to_polygon())
+            })
+            .collect()
+    }
+}
+
+/// A ring around a pole, e.g. Antarctica's, doesn't end where it starts once unwrapped. It's
+/// closed along the pole instead, so it can be cut like any other ring.
+fn close_around_pole(mut ring: geo::LineString) -> geo::LineString {
+    let (Some(&first), Some(&last)) = (ring.0.first(), ring.0.last()) else {
+        return ring;
+    };
+    if (last.x - first.x).abs() < 180. {
+        return ring;
+    }
+    let pole = if ring.0.iter().map(|coord| coord.y).sum::<f64>() > 0. {
+        90.
+    } else {
+        -90.
+    };
+    ring.0.extend([
+        geo::coord! { x: last.x, y: pole },
+        geo::coord! { x: first.x, y: pole },
+        first,
+    ]);
+    ring
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::Area;
+
+    const GREENWICH: Seam = Seam {
+        central_meridian: 0.,
+    };
+
+    /// Clipping computes the points on the seam, so they may be off by a rounding error
+    fn round(value: f64) -> f64 {
+        (value * 1e9).round() / 1e9
+    }
+
+    /// The westernmost and easternmost longitude of each line string, from west to east
+    fn longitude_ranges(multi_line_string: &geo::MultiLineString) -> Vec<(f64, f64)> {
+        let mut ranges = multi_line_string
+            .iter()
+            .filter_map(|line_string| line_string.bounding_rect())
+            .map(|rect| (round(rect.min().x), round(rect.max().x)))
+            .collect::<Vec<_>>();
+        ranges.sort_by(|a, b| a.0.total_cmp(&b.0));
+        ranges
+    }
+
+    #[test]
+    fn line_across_antimeridian_is_cut_in_two() {
+        let mut geometry = geo::Geometry::from(geo::line_string![
+            (x: 179., y: 0.),
+            (x: -179., y: 0.)
+        ]);
+        assert!(GREENWICH.cut(&mut geometry));
+        let geo::Geometry::MultiLineString(cut) = geometry else {
+            panic!("expected the line to be cut into a MultiLineString");
+        };
+        assert_eq!(longitude_ranges(&cut), vec![(-180., -179.), (179., 180.)]);
+    }
+
+    #[test]
+    fn line_within_map_is_left_alone() {
+        let line_string = geo::line_string![(x: 10., y: 0.), (x: 20., y: 5.)];
+        let mut geometry = geo::Geometry::from(line_string.clone());
+        assert!(!GREENWICH.cut(&mut geometry));
+        assert_eq!(geometry, geo::Geometry::from(line_string));
+    }
+
+    #[test]
+    fn point_outside_map_is_wrapped() {
+        let mut geometry = geo::Geometry::from(geo::point!(x: 190., y: 10.));
+        assert!(GREENWICH.cut(&mut geometry));
+        assert_eq!(geometry, geo::Geometry::from(geo::point!(x: -170., y: 10.)));
+    }
+
+    #[test]
+    fn longitudes_continue_past_antimeridian() {
+        let unwrapped = GREENWICH.unwrap_longitudes(
+            geo::line_string![(x: 179., y: 0.), (x: -179., y: 0.), (x: -178., y: 1.)],
+            None,
+        );
+        assert_eq!(
+            unwrapped,
+            geo::line_string![(x: 179., y: 0.), (x: 181., y: 0.), (x: 182., y: 1.)]
+        );
+    }
+
+    /// A ring around the south pole, like Antarctica's
+    fn south_polar_ring() -> geo::LineString {
+        geo::line_string![
+            (x: 0., y: -70.),
+            (x: 90., y: -70.),
+            (x: 180., y: -70.),
+            (x: -90., y: -70.),
+            (x: 0., y: -70.)
+        ]
+    }
+
+    #[test]
+    fn ring_around_pole_is_closed_along_pole() {
+        let ring = close_around_pole(GREENWICH.unwrap_longitudes(south_polar_ring(), None));
+        assert_eq!(
+            ring,
+            geo::line_string![
+                (x: 0., y: -70.),
+                (x: 90., y: -70.),
+                (x: 180., y: -70.),
+                (x: 270., y: -70.),
+                (x: 360., y: -70.),
+                (x: 360., y: -90.),
+                (x: 0., y: -90.),
+                (x: 0., y: -70.)
+            ]
+        );
+    }
+
+    #[test]
+    fn polygon_around_pole_covers_the_map_south_of_it() {
+        let mut geometry = geo::Geometry::from(geo::Polygon::new(south_polar_ring(), vec![]));
+        assert!(GREENWICH.cut(&mut geometry));
+        let geo::Geometry::MultiPolygon(cut) = geometry else {
+            panic!("expected the polygon to be cut into a MultiPolygon");
+        };
+        let Some(bounding_rect) = cut.bounding_rect() else {
+            panic!("expected polygons");
+        };
+        let bounds = [
+            bounding_rect.min().x,
+            bounding_rect.min().y,
+            bounding_rect.max().x,
+            bounding_rect.max().y,
+        ];
+        assert_eq!(bounds.map(round), [-180., -90., 180., -70.]);
+        // The band from 70°S to the pole, 360° wide and 20° tall
+        assert!((cut.unsigned_area() - 7200.).abs() < 1e-6);
+    }
+}
@@ -1,14 +1,20 @@
+/// How many times progress is reported while reprojecting a large layer
+const PROGRESS_STEPS: usize = 20;
+
 pub struct ReprojectGeometryJob {
     pub feature_collection: geo_projected::Unprojected<geo_features::FeatureCollection>,
     pub layer_id: rgis_layer_id::LayerId,
-    pub source_epsg_code: u16,
-    pub target_epsg_code: u16,
+    pub source_crs: transform::Crs,
+    pub target_crs: transform::Crs,
+    pub transformers: crate::Transformers,
 }
 
 pub struct ReprojectGeometryJobOutcome {
     pub feature_collection: geo_projected::Projected<geo_features::FeatureCollection>,
     pub layer_id: rgis_layer_id::LayerId,
-    pub target_crs_epsg_code: u16,
+    pub target_crs: transform::Crs,
+    /// Features cut at the antimeridian or clamped near the poles
+    pub cut_feature_count: usize,
 }
 
 impl bevy_jobs::Job for ReprojectGeometryJob {
@@ -23,19 +29,87 @@ impl bevy_jobs::Job for ReprojectGeometryJob {
         progress_sender: bevy_jobs::Context,
     ) -> bevy_jobs::AsyncReturn<Self::Outcome> {
         Box::pin(async move {
-            let total = self.feature_collection.features_iter_mut().count();
+            let direct = self.transformers.get(&self.source_crs, &self.target_crs)?;
+            // Layers already in the map's CRS need no cutting, and geometries are cut in
+            // geographic coordinates, so projected ones take a detour
+            let cutter = if self.source_crs == self.target_crs || direct.is_identity() {
+                None
+            } else {
+                crate::antimeridian::Cutter::new(&self.target_crs)
+            };
+            let to_geographic = match cutter {
+                Some(_) if !self.source_crs.is_geographic() => Some(
+                    self.transformers
+                        .get(&self.source_crs, &transform::Crs::WGS_84)?,
+                ),
+                _ => None,
+            };
+            let transformer = match to_geographic {
+                Some(_) => self
+                    .transformers
+                    .get(&transform::Crs::WGS_84, &self.target_crs)?,
+                None => direct,
+            };
+            let mut cut_feature_count = 0;
+
+            // Features are transformed a batch at a time, so progress can be reported between
+            // batches. Batches have enough coordinates to keep every thread busy.
+            let mut features = self
+                .feature_collection
+                .features_iter_mut()
+                .collect::<Vec<_>>();
+            let total = features.iter().map(coords_count).sum::<usize>().max(1);
+            let batch_size = (total / PROGRESS_STEPS).max(transform::parallel_batch_size());
+            let mut done = 0;
+            let mut rest = features.as_mut_slice();
 
-            let transformer =
-                transform::Transformer::setup(self.source_epsg_code, self.target_epsg_code)?;
+            while !rest.is_empty() {
+                // In u64, as 100 times the coordinates of a large layer overflows a 32 bit usize
+                let percent = done as u64 * 100 / total as u64;
+                let _ = progress_sender
+                    .send_progress(u8::try_from(percent).unwrap_or(100))
+                    .await;
 
-            for (i, feature) in self.feature_collection.features_iter_mut().enumerate() {
-                let _ = progress_sender.send_progress((100 * i / total) as u8).await;
+                // At least one feature, then as many as fit in the batch
+                let mut batch_len = 0;
+                let mut batch_coords = 0;
+                while let Some(feature) = rest.get(batch_len) {
+                    if batch_len > 0 && batch_coords + coords_count(feature) > batch_size {
+                        break;
+                    }
+                    batch_coords += coords_count(feature);
+                    batch_len += 1;
+                }
+                let (batch, remaining) = std::mem::take(&mut rest).split_at_mut(batch_len);
+                rest = remaining;
+                done += batch_coords;
+
+                if let Some(to_geographic) = &to_geographic {
+                    to_geographic.transform_geometries(
+                        batch
+                            .iter_mut()
+                            .filter_map(|feature| feature.0.geometry.as_mut()),
+                    )?;
+                }
 
-                if let Some(ref mut geometry) = &mut feature.0.geometry {
-                    transformer.transform(geometry)?;
+                if let Some(cutter) = &cutter {
+                    cut_feature_count += batch
+                        .iter_mut()
+                        .filter_map(|feature| feature.0.geometry.as_mut())
+                        .map(|geometry| cutter.cut(geometry))
+                        .filter(|cut| *cut)
+                        .count();
                 }
 
-                feature.0.recalculate_bounding_rect();
+                transformer.transform_geometries(
+                    batch
+                        .iter_mut()
+                        .filter_map(|feature| feature.0.geometry.as_mut()),
+                )?;
+
+                for feature in batch {
+                    feature.0.recalculate_bounding_rect();
+                }
             }
 
             self.feature_collection.0.recalculate_bounding_rect();
@@ -43,8 +117,17 @@ impl bevy_jobs::Job for ReprojectGeometryJob {
             Ok(ReprojectGeometryJobOutcome {
                 feature_collection: self.feature_collection.into_projected(),
                 layer_id: self.layer_id,
-                target_crs_epsg_code: self.target_epsg_code,
+                target_crs: self.target_crs,
+                cut_feature_count,
             })
         })
     }
 }
+
+fn coords_count(feature: &geo_projected::Unprojected<&mut geo_features::Feature>) -> usize {
+    feature
+        .0
+        .geometry
+        .as_ref()
+        .map_or(0, geo::CoordsIter::coords_count)
+}
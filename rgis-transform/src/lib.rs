@@ -6,13 +6,50 @@
     clippy::expect_used
 )]
 
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+mod antimeridian;
 mod jobs;
 mod systems;
 
+type TransformerKey = (transform::Crs, transform::Crs);
+
+/// Transformers by source and target CRS, so e.g. toggling the map CRS back and forth doesn't set
+/// up the same pipelines again. Cloning shares the cache, so jobs can use it off the main thread.
+#[derive(Clone, Default, bevy::prelude::Resource)]
+pub struct Transformers(Arc<Mutex<HashMap<TransformerKey, Arc<transform::Transformer>>>>);
+
+impl Transformers {
+    pub fn get(
+        &self,
+        source_crs: &transform::Crs,
+        target_crs: &transform::Crs,
+    ) -> Result<Arc<transform::Transformer>, transform::Error> {
+        let key = (source_crs.clone(), target_crs.clone());
+        if let Some(transformer) = self.lock().get(&key) {
+            return Ok(transformer.clone());
+        }
+        // Set up without holding the lock, other jobs may need other transformers meanwhile
+        let transformer = Arc::new(transform::Transformer::setup(source_crs, target_crs)?);
+        Ok(self.lock().entry(key).or_insert(transformer).clone())
+    }
+
+    fn lock(
+        &self,
+    ) -> std::sync::MutexGuard<'_, HashMap<TransformerKey, Arc<transform::Transformer>>> {
+        // The map is never left half updated, so a panic elsewhere doesn't spoil it
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
 pub struct Plugin;
 
 impl bevy::app::Plugin for Plugin {
     fn build(&self, app: &mut bevy::app::App) {
+        app.init_resource::<Transformers>();
         systems::configure(app);
     }
 }
@@ -4,6 +4,7 @@ fn handle_layer_created_events(
     mut layer_created_event_reader: bevy::ecs::event::EventReader<rgis_events::LayerCreatedEvent>,
     layers: bevy::ecs::system::Res<rgis_layers::Layers>,
     rgis_settings: bevy::ecs::system::Res<rgis_settings::RgisSettings>,
+    transformers: bevy::ecs::system::Res<crate::Transformers>,
     mut job_spawner: bevy_jobs::JobSpawner,
 ) {
     for event in layer_created_event_reader.read() {
@@ -14,8 +15,9 @@ fn handle_layer_created_events(
         job_spawner.spawn(crate::jobs::ReprojectGeometryJob {
             feature_collection: layer.unprojected_feature_collection.clone(),
             layer_id: event.0,
-            source_epsg_code: layer.crs_epsg_code,
-            target_epsg_code: rgis_settings.target_crs_epsg_code,
+            source_crs: layer.crs.clone(),
+            target_crs: rgis_settings.target_crs.clone(),
+            transformers: crate::Transformers::clone(&transformers),
         });
     }
 }
@@ -37,7 +39,7 @@ fn handle_reproject_geometry_job_completion_events(
             }
         };
 
-        if outcome.target_crs_epsg_code != rgis_settings.target_crs_epsg_code {
+        if outcome.target_crs != rgis_settings.target_crs {
             bevy::log::error!("Encountered a reprojected geometry with a different CRS than the current target CRS");
             continue;
         }
@@ -46,6 +48,14 @@ fn handle_reproject_geometry_job_completion_events(
             continue;
         };
 
+        if outcome.cut_feature_count > 0 {
+            bevy::log::info!(
+                "Cut {} features of {} at the antimeridian or near the poles",
+                outcome.cut_feature_count,
+                layer.name
+            );
+        }
+
         layer.projected_feature_collection = Some(outcome.feature_collection);
 
         layer_reprojected_event_writer.send(rgis_events::LayerReprojectedEvent(outcome.layer_id));
@@ -56,6 +66,7 @@ fn handle_crs_changed_events(
     mut crs_changed_event_reader: bevy::ecs::event::EventReader<rgis_events::CrsChangedEvent>,
     mut layers: bevy::ecs::system::ResMut<rgis_layers::Layers>,
     rgis_settings: bevy::ecs::system::Res<rgis_settings::RgisSettings>,
+    transformers: bevy::ecs::system::Res<crate::Transformers>,
     mut job_spawner: bevy_jobs::JobSpawner,
 ) {
     if crs_changed_event_reader.read().next().is_some() {
@@ -65,8 +76,9 @@ fn handle_crs_changed_events(
             job_spawner.spawn(crate::jobs::ReprojectGeometryJob {
                 feature_collection: layer.unprojected_feature_collection.clone(),
                 layer_id: layer.id,
-                source_epsg_code: layer.crs_epsg_code,
-                target_epsg_code: rgis_settings.target_crs_epsg_code,
+                source_crs: layer.crs.clone(),
+                target_crs: rgis_settings.target_crs.clone(),
+                transformers: crate::Transformers::clone(&transformers),
             });
         }
     }
@@ -13,10 +13,11 @@ pub struct Events<'w, 's> {
         bevy::ecs::system::ResMut<'w, bevy::ecs::event::Events<rgis_events::HideAddLayerWindow>>,
 }
 
+/// Several files can be picked at once, e.g. a Shapefile's `.shp`, `.dbf` and `.prj`
 pub struct OpenFileJob;
 
 impl bevy_jobs::Job for OpenFileJob {
-    type Outcome = Option<OpenedFile>;
+    type Outcome = Option<Vec<OpenedFile>>;
     const JOB_TYPE: bevy_jobs::JobType = bevy_jobs::JobType::Io;
 
     fn name(&self) -> String {
@@ -25,15 +26,135 @@ impl bevy_jobs::Job for OpenFileJob {
 
     fn perform(self, _: bevy_jobs::Context) -> bevy_jobs::AsyncReturn<Self::Outcome> {
         Box::pin(async move {
-            let task = rfd::AsyncFileDialog::new().pick_file();
-            let file_handle = task.await?;
-            let file_name = file_handle.file_name();
-            let bytes = file_handle.read().await;
-            Some(OpenedFile { file_name, bytes })
+            let file_handles = rfd::AsyncFileDialog::new().pick_files().await?;
+            let mut opened_files = Vec::with_capacity(file_handles.len());
+            for file_handle in file_handles {
+                opened_files.push(OpenedFile {
+                    file_name: file_handle.file_name(),
+                    bytes: file_handle.read().await,
+                });
+            }
+            Some(opened_files)
         })
     }
 }
 
+/// Lists the feature tables of the selected GeoPackage, which means opening its SQLite database
+pub struct GeoPackageTablesJob {
+    bytes: Vec<u8>,
+    /// Tells the outcome apart from that of a job for a previously selected file
+    id: usize,
+}
+
+pub struct GeoPackageTablesJobOutcome {
+    id: usize,
+    tables: Result<Vec<String>, geo_file_loader::Error>,
+}
+
+impl bevy_jobs::Job for GeoPackageTablesJob {
+    type Outcome = GeoPackageTablesJobOutcome;
+
+    fn name(&self) -> String {
+        "Reading GeoPackage tables".into()
+    }
+
+    fn perform(self, _: bevy_jobs::Context) -> bevy_jobs::AsyncReturn<Self::Outcome> {
+        Box::pin(async move {
+            GeoPackageTablesJobOutcome {
+                id: self.id,
+                tables: geo_file_loader::geopackage_feature_tables(&self.bytes),
+            }
+        })
+    }
+}
+
+/// Picks a directory and reads all of the vector tiles within it, e.g. `14/8192/5461.pbf`. Each
+/// file is named after its path relative to the directory.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct OpenTileDirectoryJob;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl bevy_jobs::Job for OpenTileDirectoryJob {
+    type Outcome = Option<Vec<OpenedFile>>;
+    const JOB_TYPE: bevy_jobs::JobType = bevy_jobs::JobType::Io;
+
+    fn name(&self) -> String {
+        "Opening tile directory".into()
+    }
+
+    fn perform(self, _: bevy_jobs::Context) -> bevy_jobs::AsyncReturn<Self::Outcome> {
+        Box::pin(async move {
+            let directory = rfd::AsyncFileDialog::new().pick_folder().await?;
+            let mut opened_files = vec![];
+            read_tiles(directory.path(), directory.path(), &mut opened_files);
+            Some(opened_files)
+        })
+    }
+}
+
+/// Picks an `.mbtiles` or `.pmtiles` file. Tilesets are read from disk as tiles come into view, so
+/// only the path is needed.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct PickTilesetJob;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl bevy_jobs::Job for PickTilesetJob {
+    type Outcome = Option<std::path::PathBuf>;
+    const JOB_TYPE: bevy_jobs::JobType = bevy_jobs::JobType::Io;
+
+    fn name(&self) -> String {
+        "Opening tileset".into()
+    }
+
+    fn perform(self, _: bevy_jobs::Context) -> bevy_jobs::AsyncReturn<Self::Outcome> {
+        Box::pin(async move {
+            let file_handle = rfd::AsyncFileDialog::new()
+                .add_filter("Tileset", &["mbtiles", "pmtiles"])
+                .pick_file()
+                .await?;
+            Some(file_handle.path().to_owned())
+        })
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_tiles(
+    directory: &std::path::Path,
+    root: &std::path::Path,
+    opened_files: &mut Vec<OpenedFile>,
+) {
+    let entries = match std::fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(e) => {
+            bevy::log::error!("Could not read directory {}: {:?}", directory.display(), e);
+            return;
+        }
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            read_tiles(&path, root, opened_files);
+            continue;
+        }
+        let is_tile = path
+            .extension()
+            .is_some_and(|extension| extension == "pbf" || extension == "mvt");
+        if !is_tile {
+            continue;
+        }
+        match std::fs::read(&path) {
+            Ok(bytes) => opened_files.push(OpenedFile {
+                file_name: path
+                    .strip_prefix(root)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .into_owned(),
+                bytes,
+            }),
+            Err(e) => bevy::log::error!("Could not read tile {}: {:?}", path.display(), e),
+        }
+    }
+}
+
 pub(crate) struct AddLayerWindow<'a, 'w1, 's1, 'w2, 's2> {
     pub state: &'a mut State,
     pub is_visible: &'a mut bool,
@@ -41,6 +162,9 @@ pub(crate) struct AddLayerWindow<'a, 'w1, 's1, 'w2, 's2> {
     pub bevy_egui_ctx: &'a mut bevy_egui::EguiContext,
     pub job_spawner: &'a mut bevy_jobs::JobSpawner<'w1, 's1>,
     pub events: &'a mut Events<'w2, 's2>,
+    /// The visible area of the map, in the target CRS
+    pub map_extent: Option<geo_projected::Projected<geo::Rect>>,
+    pub target_crs: &'a transform::Crs,
 }
 
 #[derive(PartialEq, Eq)]
@@ -57,9 +181,97 @@ pub struct State {
     selected_source: Source,
     selected_format: Option<FileFormat>,
     crs_input_outcome: Option<crate::widgets::crs_input::Outcome>,
+    only_load_map_extent: bool,
+    split_kml_folders: bool,
+    /// OSM tag key to split the features into layers by, e.g. `highway`. Empty for a single layer.
+    osm_split_key: String,
+    /// Position of a single vector tile, e.g. `14/8192/5461`
+    mvt_tile: String,
+    /// Feature tables of the selected GeoPackage, and whether each one should be loaded
+    geopackage_tables: Option<Vec<(String, bool)>>,
+    /// The ID of the `GeoPackageTablesJob` listing the selected GeoPackage's tables
+    reading_geopackage_tables: Option<usize>,
+    /// How many `GeoPackageTablesJob`s were spawned, so each gets a new ID. Never reset, as jobs
+    /// for earlier files may still be running.
+    geopackage_tables_jobs_spawned: usize,
+    csv_column_mapping: Option<CsvColumnMapping>,
+    /// Whether the format of the selected file was detected
+    inspected_selected_file: bool,
+}
+
+/// Which columns of the selected CSV file the user chose to build geometries from
+struct CsvColumnMapping {
+    headers: Vec<String>,
+    uses_geometry_column: bool,
+    x: String,
+    y: String,
+    geometry: String,
+}
+
+impl CsvColumnMapping {
+    fn new(headers: Vec<String>) -> Self {
+        let first_header = headers.first().cloned().unwrap_or_default();
+        let mut mapping = CsvColumnMapping {
+            uses_geometry_column: false,
+            x: first_header.clone(),
+            y: first_header.clone(),
+            geometry: first_header,
+            headers,
+        };
+        match geo_file_loader::CsvGeometryColumns::guess(&mapping.headers) {
+            Some(geo_file_loader::CsvGeometryColumns::Coordinates { x, y }) => {
+                mapping.x = x;
+                mapping.y = y;
+            }
+            Some(geo_file_loader::CsvGeometryColumns::Geometry(geometry)) => {
+                mapping.uses_geometry_column = true;
+                mapping.geometry = geometry;
+            }
+            None => (),
+        }
+        mapping
+    }
+
+    fn geometry_columns(&self) -> geo_file_loader::CsvGeometryColumns {
+        if self.uses_geometry_column {
+            geo_file_loader::CsvGeometryColumns::Geometry(self.geometry.clone())
+        } else {
+            geo_file_loader::CsvGeometryColumns::Coordinates {
+                x: self.x.clone(),
+                y: self.y.clone(),
+            }
+        }
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.label("Geometry columns:");
+        ui.radio_value(&mut self.uses_geometry_column, false, "X/Y coordinates");
+        ui.radio_value(&mut self.uses_geometry_column, true, "WKT or WKB geometry");
+        if self.uses_geometry_column {
+            column_combo_box(ui, "Geometry", &self.headers, &mut self.geometry);
+        } else {
+            column_combo_box(ui, "X (longitude)", &self.headers, &mut self.x);
+            column_combo_box(ui, "Y (latitude)", &self.headers, &mut self.y);
+        }
+    }
+}
+
+fn column_combo_box(ui: &mut egui::Ui, label: &str, headers: &[String], selected: &mut String) {
+    egui::ComboBox::from_label(label)
+        .selected_text(selected.as_str())
+        .show_ui(ui, |ui| {
+            for header in headers {
+                ui.selectable_value(selected, header.clone(), header.as_str());
+            }
+        });
 }
 
 const DEFAULT_CRS_INPUT: &str = "4326";
+/// Above this, only the number of selected files is shown, e.g. for a directory of tiles
+const MAX_LISTED_FILES: usize = 5;
+/// Files whose format is used over that of the files selected along with them, e.g. a `.shp` over
+/// its `.dbf` and `.prj`
+const MAIN_FILE_EXTENSIONS: &[&str] = &["shp", "zip", "kmz"];
 
 impl Default for State {
     fn default() -> Self {
@@ -69,12 +281,21 @@ impl Default for State {
             crs_input_outcome: None,
             selected_format: None,
             selected_source: Source::Unselected,
+            only_load_map_extent: false,
+            split_kml_folders: false,
+            osm_split_key: String::new(),
+            mvt_tile: String::new(),
+            geopackage_tables: None,
+            reading_geopackage_tables: None,
+            geopackage_tables_jobs_spawned: 0,
+            csv_column_mapping: None,
+            inspected_selected_file: false,
         }
     }
 }
 
 #[derive(Default, Resource)]
-pub struct SelectedFile(pub Option<OpenedFile>);
+pub struct SelectedFile(pub Option<Vec<OpenedFile>>);
 
 impl State {
     pub fn reset(&mut self) {
@@ -82,6 +303,68 @@ impl State {
         self.crs_input = DEFAULT_CRS_INPUT.into();
         self.selected_source = Source::Unselected;
         self.selected_format = None;
+        self.only_load_map_extent = false;
+        self.split_kml_folders = false;
+        self.osm_split_key = String::new();
+        self.mvt_tile = String::new();
+        self.geopackage_tables = None;
+        self.reading_geopackage_tables = None;
+        self.csv_column_mapping = None;
+        self.inspected_selected_file = false;
+    }
+
+    /// Show the tables listed by a `GeoPackageTablesJob`, all of them selected
+    pub fn set_geopackage_tables(&mut self, outcome: GeoPackageTablesJobOutcome) {
+        // Another file was selected in the meantime
+        if self.reading_geopackage_tables != Some(outcome.id) {
+            return;
+        }
+        self.reading_geopackage_tables = None;
+        let tables = match outcome.tables {
+            Ok(tables) => tables,
+            Err(e) => {
+                bevy::log::error!("Could not read the GeoPackage's tables: {}", e);
+                vec![]
+            }
+        };
+        self.geopackage_tables = Some(tables.into_iter().map(|table| (table, true)).collect());
+    }
+
+    /// Pre-select the format of newly selected files, and pre-fill the source CRS of EWKB
+    fn inspect_selected_file(&mut self, files: &[OpenedFile]) {
+        // A Shapefile or archive decides over the other files selected along with it
+        let is_main_file = |file: &&OpenedFile| {
+            file.file_name
+                .rsplit_once('.')
+                .is_some_and(|(_, extension)| {
+                    MAIN_FILE_EXTENSIONS
+                        .iter()
+                        .any(|main| extension.eq_ignore_ascii_case(main))
+                })
+        };
+        if let Some(file_format) = files
+            .iter()
+            .filter(is_main_file)
+            .chain(files.iter().filter(|file| !is_main_file(file)))
+            .find_map(|file| geo_file_loader::detect_format(&file.file_name, &file.bytes))
+        {
+            self.selected_format = Some(file_format);
+        }
+        if self.selected_format == Some(FileFormat::Wkb) {
+            if let Some(srid) = files
+                .first()
+                .and_then(|file| geo_file_loader::wkb_srid(&file.bytes))
+            {
+                self.prefill_crs(srid);
+            }
+        }
+    }
+
+    /// Pre-fill the source CRS, e.g. with the SRID of pasted EWKB
+    fn prefill_crs(&mut self, epsg_code: u16) {
+        self.crs_input = epsg_code.to_string();
+        // Makes the CRS input widget parse the new value
+        self.crs_input_outcome = None;
     }
 }
 
@@ -136,9 +419,54 @@ impl<'a, 'w1, 's1, 'w2, 's2> AddLayerWindow<'a, 'w1, 's1, 'w2, 's2> {
                     &mut self.state.crs_input_outcome,
                 );
                 ui.add(crs_input_widget);
+                // Layers can't be added while the CRS doesn't parse, the input shows why
+                let source_crs = transform::Crs::from_str(&self.state.crs_input).ok();
 
                 ui.separator();
 
+                if self.state.selected_source == Source::File {
+                    ui.label("Select file:");
+                    ui.label("For Shapefiles, select the .shp file along with its .dbf, .prj and .cpg files, or a .zip containing them.");
+
+                    if ui.button("📄 Select file").clicked() {
+                        self.state.geopackage_tables = None;
+                        self.state.reading_geopackage_tables = None;
+                        self.state.csv_column_mapping = None;
+                        self.state.inspected_selected_file = false;
+                        self.job_spawner.spawn(OpenFileJob);
+                    }
+
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if ui.button("📁 Select vector tile directory").clicked() {
+                        self.state.inspected_selected_file = false;
+                        self.job_spawner.spawn(OpenTileDirectoryJob);
+                    }
+
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if ui.button("🗺 Open tileset (.mbtiles, .pmtiles)").clicked() {
+                        self.job_spawner.spawn(PickTilesetJob);
+                    }
+
+                    if let Some(loaded_files) = &self.selected_file.0 {
+                        if loaded_files.len() > MAX_LISTED_FILES {
+                            ui.label(format!("Selected {} files", loaded_files.len()));
+                        } else {
+                            let file_names = loaded_files
+                                .iter()
+                                .map(|loaded_file| loaded_file.file_name.as_str())
+                                .collect::<Vec<_>>();
+                            ui.label(format!("Selected file: {}", file_names.join(", ")));
+                        }
+
+                        if !self.state.inspected_selected_file {
+                            self.state.inspected_selected_file = true;
+                            self.state.inspect_selected_file(loaded_files);
+                        }
+                    }
+
+                    ui.separator();
+                }
+
                 if self.state.selected_source == Source::File
                     || self.state.selected_source == Source::Text
                 {
@@ -154,11 +482,41 @@ impl<'a, 'w1, 's1, 'w2, 's2> AddLayerWindow<'a, 'w1, 's1, 'w2, 's2> {
                         "GeoJSON",
                     );
 
+                    ui.radio_value(
+                        &mut self.state.selected_format,
+                        Some(FileFormat::GeoJsonSeq),
+                        "GeoJSONSeq (newline-delimited)",
+                    );
+
                     ui.radio_value(
                         &mut self.state.selected_format,
                         Some(FileFormat::Gpx),
                         "GPX",
                     );
+
+                    ui.radio_value(
+                        &mut self.state.selected_format,
+                        Some(FileFormat::Csv),
+                        "CSV",
+                    );
+
+                    ui.radio_value(
+                        &mut self.state.selected_format,
+                        Some(FileFormat::Kml),
+                        "KML/KMZ",
+                    );
+
+                    ui.radio_value(
+                        &mut self.state.selected_format,
+                        Some(FileFormat::TopoJson),
+                        "TopoJSON",
+                    );
+
+                    ui.radio_value(
+                        &mut self.state.selected_format,
+                        Some(FileFormat::Gml),
+                        "GML",
+                    );
                 }
 
                 if self.state.selected_source == Source::File {
@@ -167,6 +525,44 @@ impl<'a, 'w1, 's1, 'w2, 's2> AddLayerWindow<'a, 'w1, 's1, 'w2, 's2> {
                         Some(FileFormat::Shapefile),
                         "Shapefile",
                     );
+
+                    ui.radio_value(
+                        &mut self.state.selected_format,
+                        Some(FileFormat::FlatGeobuf),
+                        "FlatGeobuf",
+                    );
+
+                    ui.radio_value(
+                        &mut self.state.selected_format,
+                        Some(FileFormat::GeoParquet),
+                        "GeoParquet",
+                    );
+
+                    ui.radio_value(
+                        &mut self.state.selected_format,
+                        Some(FileFormat::Osm),
+                        "OpenStreetMap (.osm, .osm.pbf)",
+                    );
+
+                    ui.radio_value(
+                        &mut self.state.selected_format,
+                        Some(FileFormat::Mvt),
+                        "Vector tiles (.mvt, .pbf)",
+                    );
+
+                    ui.radio_value(
+                        &mut self.state.selected_format,
+                        Some(FileFormat::GeoTiff),
+                        "GeoTIFF raster (.tif)",
+                    );
+
+                    ui.add_enabled_ui(cfg!(not(target_arch = "wasm32")), |ui| {
+                        ui.radio_value(
+                            &mut self.state.selected_format,
+                            Some(FileFormat::GeoPackage),
+                            "GeoPackage",
+                        );
+                    });
                 }
 
                 if self.state.selected_source == Source::File
@@ -177,6 +573,12 @@ impl<'a, 'w1, 's1, 'w2, 's2> AddLayerWindow<'a, 'w1, 's1, 'w2, 's2> {
                         Some(FileFormat::Wkt),
                         "WKT",
                     );
+
+                    ui.radio_value(
+                        &mut self.state.selected_format,
+                        Some(FileFormat::Wkb),
+                        "WKB / EWKB (binary or hex)",
+                    );
                 }
 
                 let Some(selected_format) = self.state.selected_format else {
@@ -186,37 +588,165 @@ impl<'a, 'w1, 's1, 'w2, 's2> AddLayerWindow<'a, 'w1, 's1, 'w2, 's2> {
                 ui.separator();
 
                 if self.state.selected_source == Source::File {
-                    ui.label("Select file:");
+                    if selected_format == FileFormat::FlatGeobuf {
+                        ui.checkbox(
+                            &mut self.state.only_load_map_extent,
+                            "Only load features within the current map extent",
+                        );
+                    }
 
-                    if ui.button("📄 Select file").clicked() {
-                        self.job_spawner.spawn(OpenFileJob);
+                    if selected_format == FileFormat::Kml {
+                        ui.checkbox(
+                            &mut self.state.split_kml_folders,
+                            "Load each folder as a separate layer",
+                        );
+                    }
+
+                    // A single tile's position is read from its path, or asked for if the path
+                    // doesn't include it
+                    let needs_mvt_tile = selected_format == FileFormat::Mvt
+                        && match self.selected_file.0.as_deref() {
+                            Some([file]) => {
+                                geo_file_loader::TileId::from_path(&file.file_name).is_none()
+                            }
+                            _ => false,
+                        };
+                    if needs_mvt_tile {
+                        ui.horizontal(|ui| {
+                            ui.label("Tile (z/x/y):");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.state.mvt_tile)
+                                    .hint_text("e.g. 14/8192/5461"),
+                            );
+                        });
+                    }
+
+                    if selected_format == FileFormat::Osm {
+                        ui.horizontal(|ui| {
+                            ui.label("Split into layers by tag key:");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.state.osm_split_key)
+                                    .hint_text("e.g. highway"),
+                            );
+                        });
                     }
 
-                    let submittable = self.selected_file.0.is_some();
+                    let crs = match selected_format {
+                        FileFormat::GeoJson | FileFormat::GeoJsonSeq => {
+                            Some(transform::Crs::WGS_84)
+                        }
+                        _ => source_crs,
+                    };
+                    let mut submittable = crs.is_some()
+                        && self.selected_file.0.is_some()
+                        && (!needs_mvt_tile
+                            || geo_file_loader::TileId::from_path(self.state.mvt_tile.trim())
+                                .is_some());
+
+                    if let Some(loaded_files) = &self.selected_file.0 {
+                        if selected_format == FileFormat::GeoPackage {
+                            match &mut self.state.geopackage_tables {
+                                Some(tables) => {
+                                    ui.label("Tables:");
+                                    for (table, selected) in tables.iter_mut() {
+                                        ui.checkbox(selected, table.as_str());
+                                    }
+                                    submittable &= tables.iter().any(|(_, selected)| *selected);
+                                }
+                                None => {
+                                    if self.state.reading_geopackage_tables.is_none() {
+                                        if let Some(file) = loaded_files.first() {
+                                            let id = self.state.geopackage_tables_jobs_spawned;
+                                            self.state.geopackage_tables_jobs_spawned += 1;
+                                            self.state.reading_geopackage_tables = Some(id);
+                                            self.job_spawner.spawn(GeoPackageTablesJob {
+                                                bytes: file.bytes.clone(),
+                                                id,
+                                            });
+                                        }
+                                    }
+                                    ui.label("Reading tables…");
+                                    submittable = false;
+                                }
+                            }
+                        }
 
-                    if let Some(loaded_file) = &self.selected_file.0 {
-                        ui.label(format!("Selected file: {}", loaded_file.file_name));
+                        if selected_format == FileFormat::Csv {
+                            self.state
+                                .csv_column_mapping
+                                .get_or_insert_with(|| {
+                                    CsvColumnMapping::new(csv_headers(loaded_files))
+                                })
+                                .ui(ui);
+                        }
                     }
 
                     ui.separator();
 
-                    if ui
+                    let add_clicked = ui
                         .add_enabled(submittable, egui::Button::new("Add layer"))
-                        .clicked()
-                    {
-                        let crs_epsg_code = match selected_format {
-                            FileFormat::GeoJson => 4326,
-                            // TODO: don't allow the user to add a layer if the CRS isn't valid
-                            _ => u16::from_str(&self.state.crs_input).unwrap(),
+                        .clicked();
+                    if let Some(crs) = crs.filter(|_| add_clicked) {
+                        let options = geo_file_loader::LoadOptions {
+                            bbox: match self.map_extent {
+                                Some(map_extent) if self.state.only_load_map_extent => {
+                                    Some(geo_file_loader::Bbox {
+                                        rect: map_extent.0,
+                                        crs: self.target_crs.clone(),
+                                    })
+                                }
+                                _ => None,
+                            },
+                            default_crs: Some(crs.clone()),
+                            geopackage_tables: self.state.geopackage_tables.as_ref().map(
+                                |tables| {
+                                    tables
+                                        .iter()
+                                        .filter(|(_, selected)| *selected)
+                                        .map(|(table, _)| table.clone())
+                                        .collect()
+                                },
+                            ),
+                            csv_geometry_columns: self
+                                .state
+                                .csv_column_mapping
+                                .as_ref()
+                                .map(CsvColumnMapping::geometry_columns),
+                            split_kml_folders: self.state.split_kml_folders,
+                            osm_split_key: Some(self.state.osm_split_key.trim())
+                                .filter(|key| !key.is_empty())
+                                .map(String::from),
+                            mvt_tile: geo_file_loader::TileId::from_path(
+                                self.state.mvt_tile.trim(),
+                            ),
                         };
                         match self.selected_file.0.take() {
-                            Some(loaded_file) => {
+                            Some(mut loaded_files) if loaded_files.len() == 1 => {
+                                let loaded_file = loaded_files.remove(0);
                                 self.events.load_file_event_writer.send(
                                     rgis_events::LoadFileEvent::FromBytes {
                                         file_name: loaded_file.file_name,
                                         file_format: selected_format,
                                         bytes: loaded_file.bytes.into(),
-                                        crs_epsg_code,
+                                        crs,
+                                        options,
+                                    },
+                                );
+                            }
+                            Some(loaded_files) => {
+                                self.events.load_file_event_writer.send(
+                                    rgis_events::LoadFileEvent::FromFiles {
+                                        name: layer_name_for_files(&loaded_files),
+                                        file_format: selected_format,
+                                        files: loaded_files
+                                            .into_iter()
+                                            .map(|loaded_file| geo_file_loader::InputFile {
+                                                name: loaded_file.file_name,
+                                                bytes: loaded_file.bytes.into(),
+                                            })
+                                            .collect(),
+                                        crs,
+                                        options,
                                     },
                                 );
                             }
@@ -232,39 +762,61 @@ impl<'a, 'w1, 's1, 'w2, 's2> AddLayerWindow<'a, 'w1, 's1, 'w2, 's2> {
                 } else if self.state.selected_source == Source::Text {
                     ui.label("Input text:");
 
-                    egui::ScrollArea::vertical()
+                    let text_edit_output = egui::ScrollArea::vertical()
                         .max_height(300.)
                         .show(ui, |ui| {
                             egui::widgets::TextEdit::multiline(&mut self.state.text_edit_contents)
                                 .code_editor()
                                 .hint_text(hint_text(selected_format))
-                                .show(ui);
-                        });
+                                .show(ui)
+                        })
+                        .inner;
+
+                    if text_edit_output.response.changed() && selected_format == FileFormat::Wkb {
+                        if let Some(srid) =
+                            geo_file_loader::wkb_srid(self.state.text_edit_contents.as_bytes())
+                        {
+                            self.state.prefill_crs(srid);
+                        }
+                    }
 
-                    let submittable = !self.state.text_edit_contents.is_empty();
+                    let submittable =
+                        source_crs.is_some() && !self.state.text_edit_contents.is_empty();
 
                     ui.separator();
 
-                    if ui
+                    let add_clicked = ui
                         .add_enabled(submittable, egui::Button::new("Add layer"))
-                        .clicked()
-                    {
+                        .clicked();
+                    if let Some(crs) = source_crs.filter(|_| add_clicked) {
                         let new = mem::take(&mut self.state.text_edit_contents);
                         match selected_format {
-                            FileFormat::Shapefile => {
+                            FileFormat::Shapefile
+                            | FileFormat::FlatGeobuf
+                            | FileFormat::GeoPackage
+                            | FileFormat::GeoParquet
+                            | FileFormat::Mvt
+                            | FileFormat::Osm
+                            | FileFormat::GeoTiff => {
                                 unreachable!()
                             }
                             file_format @ (FileFormat::Wkt
+                            | FileFormat::Wkb
                             | FileFormat::GeoJson
-                            | FileFormat::Gpx) => {
+                            | FileFormat::GeoJsonSeq
+                            | FileFormat::Gpx
+                            | FileFormat::Csv
+                            | FileFormat::Kml
+                            | FileFormat::TopoJson
+                            | FileFormat::Gml) => {
                                 self.events.load_file_event_writer.send(
                                     rgis_events::LoadFileEvent::FromBytes {
                                         file_name: "Inputted file".into(),
                                         file_format,
                                         bytes: new.into(),
-                                        // TODO: don't allow the user to add a layer if the CRS isn't valid
-                                        crs_epsg_code: u16::from_str(&self.state.crs_input)
-                                            .unwrap(),
+                                        crs,
+                                        // CSV geometry columns are guessed from the column names
+                                        options: geo_file_loader::LoadOptions::default(),
                                     },
                                 );
                             }
@@ -282,12 +834,57 @@ impl<'a, 'w1, 's1, 'w2, 's2> AddLayerWindow<'a, 'w1, 's1, 'w2, 's2> {
     }
 }
 
+fn csv_headers(files: &[OpenedFile]) -> Vec<String> {
+    let Some(file) = files.first() else {
+        return vec![];
+    };
+    match geo_file_loader::csv_headers(&file.bytes) {
+        Ok(headers) => headers,
+        Err(e) => {
+            bevy::log::error!("Could not read the CSV file's columns: {}", e);
+            vec![]
+        }
+    }
+}
+
+/// Name a layer built from several files after the `.shp` file (without its extension), falling
+/// back to the first file's name.
+fn layer_name_for_files(files: &[OpenedFile]) -> String {
+    files
+        .iter()
+        .find_map(|file| {
+            let (stem, extension) = file.file_name.rsplit_once('.')?;
+            extension.eq_ignore_ascii_case("shp").then_some(stem)
+        })
+        .or_else(|| files.first().map(|file| file.file_name.as_str()))
+        .unwrap_or_default()
+        .to_owned()
+}
+
 const fn hint_text(format: FileFormat) -> &'static str {
     match format {
         FileFormat::GeoJson => "{\n  \"type\": \"FeatureCollection\",\n  \"features\": []\n}",
+        FileFormat::GeoJsonSeq => {
+            "{\"type\": \"Feature\", \"geometry\": {\"type\": \"Point\", \"coordinates\": [-74.006, 40.7128]}, \"properties\": {}}"
+        }
         FileFormat::Shapefile => panic!("Shapefiles are not textual"),
+        FileFormat::FlatGeobuf => panic!("FlatGeobuf files are not textual"),
+        FileFormat::GeoPackage => panic!("GeoPackages are not textual"),
+        FileFormat::GeoParquet => panic!("GeoParquet files are not textual"),
+        FileFormat::Mvt => panic!("Vector tiles are not textual"),
+        FileFormat::Osm => panic!("OpenStreetMap files are not textual"),
+        FileFormat::GeoTiff => panic!("GeoTIFF files are not textual"),
         FileFormat::Wkt => "LINESTRING (30 10, 10 30, 40 40)",
+        FileFormat::Wkb => "0101000020E6100000000000000000F03F0000000000000040",
         FileFormat::Gpx => "", // TODO: add example GPX
+        FileFormat::Gml => {
+            "<gml:Point xmlns:gml=\"http://www.opengis.net/gml/3.2\" srsName=\"EPSG:4326\">\n  <gml:pos>-74.006 40.7128</gml:pos>\n</gml:Point>"
+        }
+        FileFormat::Csv => "name,longitude,latitude\nNew York,-74.006,40.7128",
+        FileFormat::Kml => "", // TODO: add example KML
+        FileFormat::TopoJson => {
+            "{\n  \"type\": \"Topology\",\n  \"arcs\": [],\n  \"objects\": {}\n}"
+        }
     }
 }
 
@@ -330,7 +927,7 @@ impl<'a, 'w, 's> egui::Widget for LibraryEntryWidget<'a, 'w, 's> {
                     .send(rgis_events::LoadFileEvent::FromNetwork {
                         name: format!("{}: {}", self.folder.name, self.entry.name),
                         url: self.entry.url.into(),
-                        crs_epsg_code: self.entry.crs,
+                        crs: self.entry.crs.into(),
                     });
                 self.events.hide_add_layer_window_events.send_default();
             }
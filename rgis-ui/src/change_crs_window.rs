@@ -9,6 +9,8 @@ pub(crate) struct ChangeCrsWindow<'a, 'w> {
         &'a mut bevy::ecs::event::EventWriter<'w, rgis_events::ChangeCrsEvent>,
     pub rgis_settings: &'a rgis_settings::RgisSettings,
     pub crs_input_outcome: &'a mut Option<crate::widgets::crs_input::Outcome>,
+    /// The extent of all layers, in WGS 84 longitude and latitude
+    pub layers_extent: Option<geo::Rect>,
 }
 
 impl<'a, 'w> ChangeCrsWindow<'a, 'w> {
@@ -17,24 +19,27 @@ impl<'a, 'w> ChangeCrsWindow<'a, 'w> {
             .open(self.is_visible)
             .anchor(egui::Align2::LEFT_TOP, [5., 5.])
             .show(self.bevy_egui_ctx.get_mut(), |ui| {
-                ui.add(crate::widgets::CrsInput::new(
-                    self.text_field_value,
-                    self.crs_input_outcome,
-                ));
+                ui.add(
+                    crate::widgets::CrsInput::new(self.text_field_value, self.crs_input_outcome)
+                        .with_extent(self.layers_extent),
+                );
                 let is_ok = self
                     .crs_input_outcome
                     .as_ref()
                     .map(|n| n.is_ok())
                     .unwrap_or(false);
                 if ui.add_enabled(is_ok, egui::Button::new("Set")).clicked() {
-                    let Ok(value) = u16::from_str(self.text_field_value) else {
-                        bevy::log::error!("Could not parse u16 value");
-                        return;
+                    let value = match transform::Crs::from_str(self.text_field_value) {
+                        Ok(value) => value,
+                        Err(e) => {
+                            bevy::log::error!("Could not parse CRS: {}", e);
+                            return;
+                        }
                     };
                     self.change_crs_event_writer
                         .send(rgis_events::ChangeCrsEvent {
-                            old_crs_epsg_code: self.rgis_settings.target_crs_epsg_code,
-                            new_crs_epsg_code: value,
+                            old_crs: self.rgis_settings.target_crs.clone(),
+                            new_crs: value,
                         });
                 }
             });
@@ -5,3 +5,6 @@ pub struct OpenOperationWindowEvent {
     pub operation: Box<dyn Send + Sync + rgis_geo_ops::Operation>,
     pub feature_collection: geo_projected::Unprojected<geo_features::FeatureCollection>,
 }
+
+#[derive(Event)]
+pub struct OpenExportLayerWindowEvent(pub rgis_layer_id::LayerId);
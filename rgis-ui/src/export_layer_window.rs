@@ -0,0 +1,116 @@
+use bevy_egui::egui;
+use geo_file_writer::FileFormat;
+
+pub(crate) struct ExportLayerWindow<'a, 'w, 's> {
+    pub state: &'a mut crate::ExportLayerWindowState,
+    pub layers: &'a rgis_layers::Layers,
+    pub target_crs: &'a transform::Crs,
+    pub bevy_egui_ctx: &'a mut bevy_egui::EguiContext,
+    pub job_spawner: &'a mut bevy_jobs::JobSpawner<'w, 's>,
+}
+
+impl<'a, 'w, 's> ExportLayerWindow<'a, 'w, 's> {
+    pub(crate) fn render(&mut self) {
+        let (true, Some(layer_id)) = (self.state.is_visible, self.state.layer_id) else {
+            return;
+        };
+        let Some(layer) = self.layers.get(layer_id) else {
+            bevy::log::warn!(
+                "Could not find layer with ID {:?}, closing export layer window",
+                layer_id
+            );
+            self.state.is_visible = false;
+            return;
+        };
+        let mut is_visible = self.state.is_visible;
+        egui::Window::new("Export Layer")
+            .open(&mut is_visible)
+            .show(self.bevy_egui_ctx.get_mut(), |ui| {
+                ui.label(format!("Layer: {}", layer.name));
+                ui.separator();
+
+                ui.label("Format:");
+                for format in FileFormat::ALL {
+                    ui.radio_value(&mut self.state.format, format, format.display_name());
+                }
+                ui.separator();
+
+                // GeoJSON is always WGS 84, which is also how rgis reads it back
+                let is_geojson = self.state.format == FileFormat::GeoJson;
+                if is_geojson {
+                    ui.label("GeoJSON coordinates are written in WGS 84 (EPSG:4326)");
+                } else {
+                    ui.checkbox(
+                        &mut self.state.use_map_crs,
+                        format!(
+                            "Use the map's CRS ({}) instead of the layer's ({})",
+                            self.target_crs, layer.crs
+                        ),
+                    );
+                }
+
+                if ui.button("💾 Export").clicked() {
+                    let (feature_collection, crs) = if self.state.use_map_crs && !is_geojson {
+                        let Some(projected) = layer.get_projected_feature_collection_or_log()
+                        else {
+                            return;
+                        };
+                        (projected.as_raw().clone(), self.target_crs.clone())
+                    } else {
+                        (
+                            layer.unprojected_feature_collection.as_raw().clone(),
+                            layer.crs.clone(),
+                        )
+                    };
+                    self.job_spawner.spawn(ExportLayerJob {
+                        feature_collection,
+                        format: self.state.format,
+                        name: layer.name.clone(),
+                        crs,
+                    });
+                    self.state.is_visible = false;
+                }
+            });
+        self.state.is_visible &= is_visible;
+    }
+}
+
+pub(crate) struct ExportLayerJob {
+    pub feature_collection: geo_features::FeatureCollection,
+    pub format: FileFormat,
+    pub name: String,
+    pub crs: transform::Crs,
+}
+
+impl bevy_jobs::Job for ExportLayerJob {
+    type Outcome = Result<crate::save_file::ExportedFile, geo_file_writer::Error>;
+
+    fn name(&self) -> String {
+        format!("Exporting {} file", self.format.display_name())
+    }
+
+    fn perform(self, _: bevy_jobs::Context) -> bevy_jobs::AsyncReturn<Self::Outcome> {
+        Box::pin(async move {
+            let mut feature_collection = self.feature_collection;
+            let mut crs = self.crs;
+            if self.format == FileFormat::GeoJson && crs != transform::Crs::WGS_84 {
+                transform::Transformer::setup(&crs, &transform::Crs::WGS_84)?
+                    .transform_geometries(
+                        feature_collection
+                            .features
+                            .iter_mut()
+                            .filter_map(|feature| feature.geometry.as_mut()),
+                    )
+                    .map_err(transform::Error::from)?;
+                crs = transform::Crs::WGS_84;
+            }
+            let bytes = geo_file_writer::write(self.format, &self.name, &feature_collection, &crs)?;
+            Ok(crate::save_file::ExportedFile {
+                file_name: format!("{}.{}", self.name, self.format.extension()),
+                filter_name: self.format.display_name(),
+                extension: self.format.extension(),
+                bytes,
+            })
+        })
+    }
+}
@@ -0,0 +1,96 @@
+use bevy::{ecs::system::SystemParam, prelude::*, window::PrimaryWindow};
+use bevy_egui::egui;
+use rgis_events::MapImageFormat;
+
+/// Images are measured in logical pixels, which are 1/96th of an inch
+const LOGICAL_DPI: f32 = 96.;
+
+pub struct ExportMapImageWindowState {
+    format: MapImageFormat,
+    dpi: f32,
+}
+
+impl Default for ExportMapImageWindowState {
+    fn default() -> Self {
+        ExportMapImageWindowState {
+            format: if is_png_supported() {
+                MapImageFormat::Png
+            } else {
+                MapImageFormat::Svg
+            },
+            dpi: LOGICAL_DPI,
+        }
+    }
+}
+
+/// Rendering a PNG waits on the GPU, which WebGL doesn't allow
+fn is_png_supported() -> bool {
+    cfg!(not(target_arch = "wasm32"))
+}
+
+#[derive(SystemParam)]
+pub struct ExportMapImageWindow<'w, 's> {
+    state: Local<'s, ExportMapImageWindowState>,
+    camera_query: Query<'w, 's, &'static Transform, With<Camera>>,
+    windows: Query<'w, 's, &'static bevy::window::Window, With<PrimaryWindow>>,
+    ui_margins: crate::UiMargins<'w, 's>,
+    export_map_image_event_writer: EventWriter<'w, rgis_events::ExportMapImageEvent>,
+}
+
+impl<'w, 's> egui::Widget for ExportMapImageWindow<'w, 's> {
+    fn ui(mut self, ui: &mut egui::Ui) -> egui::Response {
+        let (Ok(transform), Ok(window)) =
+            (self.camera_query.get_single(), self.windows.get_single())
+        else {
+            return ui.label("No map to export");
+        };
+        let map_area = rgis_units::MapArea {
+            window,
+            left_offset_px: self.ui_margins.left.0,
+            right_offset_px: 0.,
+            top_offset_px: self.ui_margins.top.0,
+            bottom_offset_px: self.ui_margins.bottom.0,
+        };
+
+        ui.vertical(|ui| {
+            ui.label("Format:");
+            for format in MapImageFormat::ALL {
+                ui.add_enabled_ui(format != MapImageFormat::Png || is_png_supported(), |ui| {
+                    ui.radio_value(&mut self.state.format, format, format.display_name());
+                });
+            }
+            ui.separator();
+
+            ui.add(egui::Slider::new(&mut self.state.dpi, 72.0..=600.).text("DPI"));
+            let size = map_area.size();
+            let scale = self.state.dpi / LOGICAL_DPI;
+            let width_px = (size.width * scale).round() as u32;
+            let height_px = (size.height * scale).round() as u32;
+            ui.label(format!("{width_px} × {height_px} pixels"));
+
+            if ui.button("💾 Export").clicked() {
+                self.export_map_image_event_writer
+                    .send(rgis_events::ExportMapImageEvent {
+                        format: self.state.format,
+                        rect: map_area.projected_geo_rect(transform, window),
+                        width_px,
+                        height_px,
+                        dpi: self.state.dpi,
+                    });
+            }
+        })
+        .response
+    }
+}
+
+impl crate::Window for ExportMapImageWindow<'_, '_> {
+    type Item<'w, 's> = ExportMapImageWindow<'w, 's>;
+
+    fn title(&self) -> &str {
+        "Export Map Image"
+    }
+
+    fn default_width(&self) -> f32 {
+        200.
+    }
+}
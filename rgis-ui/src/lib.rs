@@ -15,10 +15,13 @@ mod bottom_panel;
 mod change_crs_window;
 mod debug_window;
 mod events;
+mod export_layer_window;
+mod export_map_image_window;
 mod feature_properties_window;
 mod manage_layer_window;
 mod message_window;
 mod operation_window;
+mod save_file;
 mod side_panel;
 mod systems;
 mod top_panel;
@@ -63,6 +66,25 @@ pub struct ManageLayerWindowState {
     is_visible: bool,
 }
 
+pub struct ExportLayerWindowState {
+    layer_id: Option<rgis_layer_id::LayerId>,
+    is_visible: bool,
+    format: geo_file_writer::FileFormat,
+    /// Export the layer in the map's CRS rather than in its own
+    use_map_crs: bool,
+}
+
+impl Default for ExportLayerWindowState {
+    fn default() -> Self {
+        ExportLayerWindowState {
+            layer_id: None,
+            is_visible: false,
+            format: geo_file_writer::FileFormat::GeoJson,
+            use_map_crs: false,
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct FeaturePropertiesWindowState {
     properties: Option<geo_features::Properties>,
@@ -83,7 +105,8 @@ impl bevy::app::Plugin for Plugin {
             .insert_resource(TopPanelHeight(0.))
             .insert_resource(BottomPanelHeight(0.))
             .insert_resource(SidePanelWidth(0.))
-            .add_event::<events::OpenOperationWindowEvent>();
+            .add_event::<events::OpenOperationWindowEvent>()
+            .add_event::<events::OpenExportLayerWindowEvent>();
 
         systems::configure(app);
     }
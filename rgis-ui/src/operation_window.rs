@@ -26,8 +26,8 @@ impl<'w> OperationWindow<'w> {
                         self.create_layer_event_writer
                             .send(rgis_events::CreateLayerEvent {
                                 feature_collection,
-                                name: "FOOOOO".into(),      // FIXME
-                                source_crs_epsg_code: 4326, // FIXME
+                                name: "FOOOOO".into(),              // FIXME
+                                source_crs: transform::Crs::WGS_84, // FIXME
                             });
                     }
                     Ok(rgis_geo_ops::Outcome::Text(text)) => {
@@ -0,0 +1,69 @@
+pub(crate) struct ExportedFile {
+    pub file_name: String,
+    /// Name of the file type, shown in the save dialog
+    pub filter_name: &'static str,
+    pub extension: &'static str,
+    pub bytes: Vec<u8>,
+}
+
+/// Asks where to save the exported file, then writes it
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) struct SaveFileJob(pub ExportedFile);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl bevy_jobs::Job for SaveFileJob {
+    type Outcome = std::io::Result<()>;
+    const JOB_TYPE: bevy_jobs::JobType = bevy_jobs::JobType::Io;
+
+    fn name(&self) -> String {
+        format!("Saving '{}'", self.0.file_name)
+    }
+
+    fn perform(self, _: bevy_jobs::Context) -> bevy_jobs::AsyncReturn<Self::Outcome> {
+        Box::pin(async move {
+            let Some(file_handle) = rfd::AsyncFileDialog::new()
+                .set_file_name(&self.0.file_name)
+                .add_filter(self.0.filter_name, &[self.0.extension])
+                .save_file()
+                .await
+            else {
+                return Ok(());
+            };
+            std::fs::write(file_handle.path(), self.0.bytes)
+        })
+    }
+}
+
+/// Browsers start downloads asynchronously, so the object URL is only revoked after this delay
+#[cfg(target_arch = "wasm32")]
+const REVOKE_OBJECT_URL_DELAY_MS: i32 = 60_000;
+
+/// Browsers can't be handed a path to write to, so the file is downloaded instead
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn download(file: &ExportedFile) -> Result<(), wasm_bindgen::JsValue> {
+    use wasm_bindgen::JsCast;
+
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(file.bytes.as_slice()));
+    let blob = web_sys::Blob::new_with_u8_array_sequence(&parts)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+    let window = web_sys::window()
+        .ok_or_else(|| wasm_bindgen::JsValue::from_str("No window to download from"))?;
+    let document = window
+        .document()
+        .ok_or_else(|| wasm_bindgen::JsValue::from_str("No document to download from"))?;
+    let anchor = document
+        .create_element("a")?
+        .dyn_into::<web_sys::HtmlAnchorElement>()
+        .map_err(wasm_bindgen::JsValue::from)?;
+    anchor.set_href(&url);
+    anchor.set_download(&file.file_name);
+    anchor.click();
+    let revoke_object_url = wasm_bindgen::closure::Closure::once_into_js(move || {
+        let _ = web_sys::Url::revoke_object_url(&url);
+    });
+    window.set_timeout_with_callback_and_timeout_and_arguments_0(
+        revoke_object_url.unchecked_ref(),
+        REVOKE_OBJECT_URL_DELAY_MS,
+    )?;
+    Ok(())
+}
@@ -16,13 +16,21 @@ pub struct Events<'w> {
     render_message_event_writer: bevy::ecs::event::EventWriter<'w, rgis_events::RenderMessageEvent>,
     open_operation_window_event_writer:
         bevy::ecs::event::EventWriter<'w, crate::events::OpenOperationWindowEvent>,
+    open_export_layer_window_event_writer:
+        bevy::ecs::event::EventWriter<'w, crate::events::OpenExportLayerWindowEvent>,
     show_manage_layer_window_event_writer:
         bevy::ecs::event::EventWriter<'w, rgis_events::ShowManageLayerWindowEvent>,
+    delete_raster_event_writer: bevy::ecs::event::EventWriter<'w, rgis_events::DeleteRasterEvent>,
+    #[cfg(not(target_arch = "wasm32"))]
+    delete_tileset_event_writer: bevy::ecs::event::EventWriter<'w, rgis_events::DeleteTilesetEvent>,
 }
 
 pub(crate) struct SidePanel<'a, 'w> {
     pub egui_ctx: &'a egui::Context,
     pub layers: &'a rgis_layers::Layers,
+    pub rasters: &'a rgis_rasters::Rasters,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub tilesets: &'a rgis_tilesets::Tilesets,
     pub events: &'a mut Events<'w>,
     pub side_panel_width: &'a mut crate::SidePanelWidth,
 }
@@ -46,6 +54,9 @@ impl<'a, 'w> SidePanel<'a, 'w> {
                     events: self.events,
                 });
                 self.render_layers(ui);
+                self.render_rasters(ui);
+                #[cfg(not(target_arch = "wasm32"))]
+                self.render_tilesets(ui);
             });
         });
     }
@@ -65,6 +76,44 @@ impl<'a, 'w> SidePanel<'a, 'w> {
             ui.separator();
         }
     }
+
+    /// Rasters are drawn below all of the layers, and above tilesets
+    fn render_rasters(&mut self, ui: &mut egui::Ui) {
+        for raster in self.rasters.iter() {
+            egui::CollapsingHeader::new(format!("🖼 {}", raster.name))
+                .id_source(raster.id)
+                .show(ui, |ui| {
+                    ui.label(format!("Size: {}×{} px", raster.width, raster.height));
+                    ui.label(format!("CRS: {}", raster.crs));
+
+                    if ui.button("❌ Remove").clicked() {
+                        self.events
+                            .delete_raster_event_writer
+                            .send(rgis_events::DeleteRasterEvent(raster.id));
+                    }
+                });
+            ui.separator();
+        }
+    }
+
+    /// Tilesets are drawn below all of the layers
+    #[cfg(not(target_arch = "wasm32"))]
+    fn render_tilesets(&mut self, ui: &mut egui::Ui) {
+        for tileset in self.tilesets.iter() {
+            egui::CollapsingHeader::new(format!("🗺 {}", tileset.name))
+                .id_source(tileset.id)
+                .show(ui, |ui| {
+                    ui.label(format!("Loaded tiles: {}", tileset.loaded_tile_count()));
+
+                    if ui.button("❌ Remove").clicked() {
+                        self.events
+                            .delete_tileset_event_writer
+                            .send(rgis_events::DeleteTilesetEvent(tileset.id));
+                    }
+                });
+            ui.separator();
+        }
+    }
 }
 
 struct OperationButton<'a, 'w, Op: rgis_geo_ops::OperationEntry> {
@@ -111,7 +160,7 @@ impl<'a, 'w, Op: rgis_geo_ops::OperationEntry> egui::Widget for OperationButton<
                                 rgis_events::CreateLayerEvent {
                                     feature_collection,
                                     name: Op::NAME.into(),
-                                    source_crs_epsg_code: self.layer.crs_epsg_code,
+                                    source_crs: self.layer.crs.clone(),
                                 },
                             );
                         }
@@ -207,6 +256,12 @@ impl<'a, 'w> Widget for Layer<'a, 'w> {
                             .send(rgis_events::CenterCameraEvent(layer.id));
                     }
 
+                    if ui.button("💾 Export…").clicked() {
+                        self.events
+                            .open_export_layer_window_event_writer
+                            .send(crate::events::OpenExportLayerWindowEvent(layer.id));
+                    }
+
                     if ui.button("❌ Remove").clicked() {
                         self.delete_layer(layer);
                     }
@@ -309,7 +364,7 @@ impl<'a, 'w> egui::Widget for OperationsWidget<'a, 'w> {
                         .send(rgis_events::CreateLayerEvent {
                             feature_collection,           // todo
                             name: "Bounding rect".into(), // todo
-                            source_crs_epsg_code: self.layer.crs_epsg_code,
+                            source_crs: self.layer.crs.clone(),
                         });
                 }
             }
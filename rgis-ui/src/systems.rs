@@ -31,6 +31,8 @@ fn render_bottom_panel(
 fn render_side_panel(
     mut egui_ctx_query: Query<&mut EguiContext, With<PrimaryWindow>>,
     layers: Res<rgis_layers::Layers>,
+    rasters: Res<rgis_rasters::Rasters>,
+    #[cfg(not(target_arch = "wasm32"))] tilesets: Res<rgis_tilesets::Tilesets>,
     mut events: crate::side_panel::Events,
     mut side_panel_width: ResMut<crate::SidePanelWidth>,
 ) {
@@ -41,6 +43,9 @@ fn render_side_panel(
     crate::side_panel::SidePanel {
         egui_ctx: egui_ctx.get_mut(),
         layers: &layers,
+        rasters: &rasters,
+        #[cfg(not(target_arch = "wasm32"))]
+        tilesets: &tilesets,
         events: &mut events,
         side_panel_width: &mut side_panel_width,
     }
@@ -59,6 +64,34 @@ fn handle_open_file_job(
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+fn handle_open_tile_directory_job(
+    mut finished_jobs: bevy_jobs::FinishedJobs,
+    mut selected_file: ResMut<crate::add_layer_window::SelectedFile>,
+) {
+    while let Some(outcome) = finished_jobs
+        .take_next::<crate::add_layer_window::OpenTileDirectoryJob>()
+        .flatten()
+    {
+        selected_file.0 = Some(outcome);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn handle_pick_tileset_job(
+    mut finished_jobs: bevy_jobs::FinishedJobs,
+    mut open_tileset_event_writer: EventWriter<rgis_events::OpenTilesetEvent>,
+    mut hide_add_layer_window_event_writer: EventWriter<rgis_events::HideAddLayerWindow>,
+) {
+    while let Some(path) = finished_jobs
+        .take_next::<crate::add_layer_window::PickTilesetJob>()
+        .flatten()
+    {
+        open_tileset_event_writer.send(rgis_events::OpenTilesetEvent(path));
+        hide_add_layer_window_event_writer.send_default();
+    }
+}
+
 fn render_manage_layer_window(
     mut state: Local<crate::ManageLayerWindowState>,
     mut egui_ctx_query: Query<&mut EguiContext, With<PrimaryWindow>>,
@@ -86,6 +119,89 @@ fn render_manage_layer_window(
     .render();
 }
 
+fn render_export_layer_window(
+    mut state: Local<crate::ExportLayerWindowState>,
+    mut egui_ctx_query: Query<&mut EguiContext, With<PrimaryWindow>>,
+    layers: Res<rgis_layers::Layers>,
+    rgis_settings: Res<rgis_settings::RgisSettings>,
+    mut job_spawner: bevy_jobs::JobSpawner,
+    mut open_export_layer_window_event_reader: bevy::ecs::event::EventReader<
+        crate::events::OpenExportLayerWindowEvent,
+    >,
+) {
+    let Ok(mut egui_ctx) = egui_ctx_query.get_single_mut() else {
+        return;
+    };
+
+    if let Some(event) = open_export_layer_window_event_reader.read().last() {
+        state.is_visible = true;
+        state.layer_id = Some(event.0);
+    }
+
+    crate::export_layer_window::ExportLayerWindow {
+        state: &mut state,
+        layers: &layers,
+        target_crs: &rgis_settings.target_crs,
+        bevy_egui_ctx: &mut egui_ctx,
+        job_spawner: &mut job_spawner,
+    }
+    .render();
+}
+
+fn handle_export_layer_job(
+    mut finished_jobs: bevy_jobs::FinishedJobs,
+    #[cfg(not(target_arch = "wasm32"))] mut job_spawner: bevy_jobs::JobSpawner,
+) {
+    while let Some(outcome) =
+        finished_jobs.take_next::<crate::export_layer_window::ExportLayerJob>()
+    {
+        let exported_file = match outcome {
+            Ok(exported_file) => exported_file,
+            Err(e) => {
+                bevy::log::error!("Could not export layer: {}", e);
+                continue;
+            }
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        job_spawner.spawn(crate::save_file::SaveFileJob(exported_file));
+        #[cfg(target_arch = "wasm32")]
+        if let Err(e) = crate::save_file::download(&exported_file) {
+            bevy::log::error!("Could not download exported layer: {:?}", e);
+        }
+    }
+}
+
+fn handle_map_image_exported_events(
+    mut map_image_exported_events: ResMut<
+        bevy::ecs::event::Events<rgis_events::MapImageExportedEvent>,
+    >,
+    #[cfg(not(target_arch = "wasm32"))] mut job_spawner: bevy_jobs::JobSpawner,
+) {
+    for event in map_image_exported_events.drain() {
+        let exported_file = crate::save_file::ExportedFile {
+            file_name: format!("map.{}", event.format.extension()),
+            filter_name: event.format.display_name(),
+            extension: event.format.extension(),
+            bytes: event.bytes,
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        job_spawner.spawn(crate::save_file::SaveFileJob(exported_file));
+        #[cfg(target_arch = "wasm32")]
+        if let Err(e) = crate::save_file::download(&exported_file) {
+            bevy::log::error!("Could not download exported map image: {:?}", e);
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn handle_save_file_job(mut finished_jobs: bevy_jobs::FinishedJobs) {
+    while let Some(outcome) = finished_jobs.take_next::<crate::save_file::SaveFileJob>() {
+        if let Err(e) = outcome {
+            bevy::log::error!("Could not save exported file: {}", e);
+        }
+    }
+}
+
 struct IsVisible(pub bool);
 
 impl Default for IsVisible {
@@ -99,13 +215,32 @@ fn render_add_layer_window(
     mut selected_file: ResMut<crate::add_layer_window::SelectedFile>,
     mut egui_ctx_query: Query<&mut EguiContext, With<PrimaryWindow>>,
     mut job_spawner: bevy_jobs::JobSpawner,
+    mut finished_jobs: bevy_jobs::FinishedJobs,
     mut state: Local<crate::add_layer_window::State>,
     mut events: crate::add_layer_window::Events,
+    camera_query: Query<&Transform, With<Camera>>,
+    windows: Query<&bevy::window::Window, With<PrimaryWindow>>,
+    ui_margins: crate::UiMargins,
+    rgis_settings: Res<rgis_settings::RgisSettings>,
 ) {
     let Ok(mut egui_ctx) = egui_ctx_query.get_single_mut() else {
         return;
     };
 
+    let map_extent = match (camera_query.get_single(), windows.get_single()) {
+        (Ok(transform), Ok(window)) => Some(
+            rgis_units::MapArea {
+                window,
+                left_offset_px: ui_margins.left.0,
+                right_offset_px: 0.,
+                top_offset_px: ui_margins.top.0,
+                bottom_offset_px: ui_margins.bottom.0,
+            }
+            .projected_geo_rect(transform, window),
+        ),
+        _ => None,
+    };
+
     if !events.show_add_layer_window_event_reader.is_empty() {
         (*is_visible).0 = true;
     }
@@ -115,6 +250,12 @@ fn render_add_layer_window(
         (*is_visible).0 = false;
     }
 
+    while let Some(outcome) =
+        finished_jobs.take_next::<crate::add_layer_window::GeoPackageTablesJob>()
+    {
+        state.set_geopackage_tables(outcome);
+    }
+
     crate::add_layer_window::AddLayerWindow {
         state: &mut state,
         selected_file: &mut selected_file,
@@ -122,6 +263,8 @@ fn render_add_layer_window(
         bevy_egui_ctx: &mut egui_ctx,
         job_spawner: &mut job_spawner,
         events: &mut events,
+        map_extent,
+        target_crs: &rgis_settings.target_crs,
     }
     .render();
 }
@@ -136,9 +279,12 @@ fn render_change_crs_window(
     mut text_field_value: Local<String>,
     mut change_crs_event_writer: bevy::ecs::event::EventWriter<rgis_events::ChangeCrsEvent>,
     mut crs_input_outcome: Local<Option<crate::widgets::crs_input::Outcome>>,
+    mut layers_extent: Local<Option<geo::Rect>>,
+    layers: Res<rgis_layers::Layers>,
 ) {
     if open_change_crs_window_event_reader.read().next().is_some() {
         *is_visible = true;
+        *layers_extent = layers_extent_in_wgs_84(&layers);
     }
 
     let Ok(mut egui_ctx) = egui_ctx_query.get_single_mut() else {
@@ -152,10 +298,44 @@ fn render_change_crs_window(
         change_crs_event_writer: &mut change_crs_event_writer,
         rgis_settings: &rgis_settings,
         crs_input_outcome: &mut crs_input_outcome,
+        layers_extent: *layers_extent,
     }
     .render();
 }
 
+/// The bounding rectangle of all layers, reprojected from each layer's CRS
+fn layers_extent_in_wgs_84(layers: &rgis_layers::Layers) -> Option<geo::Rect> {
+    layers
+        .iter()
+        .filter_map(|layer| {
+            let bounding_rect = layer
+                .unprojected_feature_collection
+                .as_raw()
+                .bounding_rect?;
+            let mut geometry = geo::Geometry::from(bounding_rect.to_polygon());
+            if layer.crs != transform::Crs::WGS_84 {
+                let result = transform::Transformer::setup(&layer.crs, &transform::Crs::WGS_84)
+                    .map_err(|e| e.to_string())
+                    .and_then(|transformer| {
+                        transformer
+                            .transform(&mut geometry)
+                            .map_err(|e| e.to_string())
+                    });
+                if let Err(e) = result {
+                    bevy::log::error!("Could not reproject the extent of '{}': {}", layer.name, e);
+                    return None;
+                }
+            }
+            geo::BoundingRect::bounding_rect(&geometry)
+        })
+        .reduce(|a, b| {
+            geo::Rect::new(
+                geo::coord! { x: a.min().x.min(b.min().x), y: a.min().y.min(b.min().y) },
+                geo::coord! { x: a.max().x.max(b.max().x), y: a.max().y.max(b.max().y) },
+            )
+        })
+}
+
 fn render_feature_properties_window(
     mut state: Local<crate::FeaturePropertiesWindowState>,
     mut egui_ctx_query: Query<&mut EguiContext, With<PrimaryWindow>>,
@@ -287,6 +467,9 @@ fn render_top_panel(
     mut is_debug_window_open: ResMut<
         crate::IsWindowOpen<crate::debug_window::DebugWindow<'static, 'static>>,
     >,
+    mut is_export_map_image_window_open: ResMut<
+        crate::IsWindowOpen<crate::export_map_image_window::ExportMapImageWindow<'static, 'static>>,
+    >,
 ) {
     let Ok(mut window) = windows.get_single_mut() else {
         return;
@@ -303,6 +486,7 @@ fn render_top_panel(
         app_settings: &mut app_settings,
         top_panel_height: &mut top_panel_height,
         is_debug_window_open: &mut is_debug_window_open,
+        is_export_map_image_window_open: &mut is_export_map_image_window_open,
     }
     .render();
 }
@@ -361,6 +545,9 @@ pub fn configure(app: &mut App) {
             render_in_progress.in_set(RenderSystemSet::SideBarProgressBar),
             handle_open_file_job,
             render_manage_layer_window.in_set(RenderSystemSet::Windows),
+            render_export_layer_window.in_set(RenderSystemSet::Windows),
+            handle_export_layer_job,
+            handle_map_image_exported_events,
             render_add_layer_window.in_set(RenderSystemSet::Windows),
             render_change_crs_window.in_set(RenderSystemSet::Windows),
             render_feature_properties_window.in_set(RenderSystemSet::Windows),
@@ -368,8 +555,26 @@ pub fn configure(app: &mut App) {
         ),
     );
 
+    #[cfg(not(target_arch = "wasm32"))]
+    app.add_systems(
+        Update,
+        (
+            handle_open_tile_directory_job,
+            handle_pick_tileset_job,
+            handle_save_file_job,
+        ),
+    );
+
     app.insert_resource(crate::IsWindowOpen::<crate::debug_window::DebugWindow>::closed());
     app.add_systems(Update, render_window::<crate::debug_window::DebugWindow>);
+
+    app.insert_resource(crate::IsWindowOpen::<
+        crate::export_map_image_window::ExportMapImageWindow,
+    >::closed());
+    app.add_systems(
+        Update,
+        render_window::<crate::export_map_image_window::ExportMapImageWindow>,
+    );
 }
 
 fn render_window<W: Window + 'static>(
@@ -8,6 +8,8 @@ pub(crate) struct TopPanel<'a, 'w, 's> {
     pub app_settings: &'a mut rgis_settings::RgisSettings,
     pub top_panel_height: &'a mut crate::TopPanelHeight,
     pub is_debug_window_open: &'a mut crate::IsWindowOpen<crate::debug_window::DebugWindow<'w, 's>>,
+    pub is_export_map_image_window_open:
+        &'a mut crate::IsWindowOpen<crate::export_map_image_window::ExportMapImageWindow<'w, 's>>,
 }
 
 impl<'a, 'w, 's> TopPanel<'a, 'w, 's> {
@@ -19,6 +21,9 @@ impl<'a, 'w, 's> TopPanel<'a, 'w, 's> {
 
                     ui.label("rgis");
                     ui.menu_button("File", |ui| {
+                        if ui.button("🖼 Export map image…").clicked() {
+                            self.is_export_map_image_window_open.0 = true;
+                        }
                         ui.add(ExitButton {
                             app_exit_events: self.app_exit_events,
                         });
@@ -1,10 +1,15 @@
 use bevy_egui::egui;
-use std::str::FromStr;
+use std::{str::FromStr, sync};
 use transform::Context;
 
+/// Search results scroll past this height
+const MAX_SEARCH_RESULTS_HEIGHT: f32 = 150.;
+
 pub struct CrsInput<'a> {
     pub outcome: &'a mut Option<Outcome>,
     text_field_value: &'a mut String,
+    /// The area the CRS should cover, in WGS 84 longitude and latitude
+    extent: Option<geo::Rect>,
 }
 
 pub type Outcome = Result<(transform::Minimal, transform::OpHandle), Error>;
@@ -14,18 +19,36 @@ impl<'a> CrsInput<'a> {
         CrsInput {
             outcome: prev_outcome,
             text_field_value,
+            extent: None,
         }
     }
+
+    /// Mark the search results whose area of use doesn't cover `extent`
+    pub fn with_extent(mut self, extent: Option<geo::Rect>) -> Self {
+        self.extent = extent;
+        self
+    }
 }
 
 impl<'a> egui::Widget for CrsInput<'a> {
     fn ui(self, ui: &mut egui::Ui) -> egui::Response {
         ui.vertical(|ui| {
-            ui.add(EpsgCodeInputFieldWidget {
-                text_field_value: self.text_field_value,
-                outcome: self.outcome,
+            ui.add(CrsInputFieldWidget {
+                text_field_value: &mut *self.text_field_value,
+                outcome: &mut *self.outcome,
             });
 
+            // The text is also searched, so e.g. `LV95` or `switzerland` finds EPSG:2056
+            let search_results = search_results(ui, self.text_field_value);
+            if !search_results.is_empty() {
+                ui.add(SearchResultsWidget {
+                    search_results: &search_results,
+                    extent: self.extent,
+                    text_field_value: &mut *self.text_field_value,
+                    outcome: &mut *self.outcome,
+                });
+            }
+
             let Some(outcome) = self.outcome else { return };
 
             match &outcome {
@@ -39,6 +62,8 @@ impl<'a> egui::Widget for CrsInput<'a> {
                         }
                     });
                 }
+                // The search results explain more than a parse error
+                Err(_) if !search_results.is_empty() => (),
                 Err(e) => {
                     ui.label(format!("{e}"));
                 }
@@ -48,22 +73,113 @@ impl<'a> egui::Widget for CrsInput<'a> {
     }
 }
 
-struct EpsgCodeInputFieldWidget<'a> {
+/// The CRSs matching `query`. They're kept in egui's memory and only searched again when the query
+/// changes, as searching goes through every definition.
+fn search_results(ui: &egui::Ui, query: &str) -> sync::Arc<Vec<&'static transform::CrsDefinition>> {
+    type Cached = (String, sync::Arc<Vec<&'static transform::CrsDefinition>>);
+    let id = ui.id().with("crs_search_results");
+    match ui.data(|data| data.get_temp::<Cached>(id)) {
+        Some((cached_query, search_results)) if cached_query == query => search_results,
+        _ => {
+            let search_results = sync::Arc::new(transform::search_crs_definitions(query));
+            ui.data_mut(|data| {
+                data.insert_temp::<Cached>(id, (query.to_owned(), search_results.clone()))
+            });
+            search_results
+        }
+    }
+}
+
+struct SearchResultsWidget<'a> {
+    search_results: &'a [&'static transform::CrsDefinition],
+    extent: Option<geo::Rect>,
+    text_field_value: &'a mut String,
+    outcome: &'a mut Option<Outcome>,
+}
+
+impl<'a> egui::Widget for SearchResultsWidget<'a> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let row_height = ui.spacing().interact_size.y;
+        let mut selected = None;
+        egui::ScrollArea::vertical()
+            .id_source("crs_search_results")
+            .max_height(MAX_SEARCH_RESULTS_HEIGHT)
+            .show_rows(ui, row_height, self.search_results.len(), |ui, rows| {
+                for definition in self.search_results.get(rows).into_iter().flatten() {
+                    // Only CRSs whose area of use is known can be told not to cover the layers
+                    let misses_extent = match (&definition.area_of_use, self.extent) {
+                        (Some(area_of_use), Some(extent)) => !area_of_use.covers(extent),
+                        _ => false,
+                    };
+                    let mut text = egui::RichText::new(format!(
+                        "{}EPSG:{} {}",
+                        if misses_extent { "⚠ " } else { "" },
+                        definition.epsg_code,
+                        definition.name
+                    ));
+                    if misses_extent {
+                        text = text.color(ui.visuals().warn_fg_color);
+                    }
+                    let hover_text = match (&definition.area_of_use, misses_extent) {
+                        (Some(area_of_use), false) => format!("Area of use: {}", area_of_use.name),
+                        (Some(area_of_use), true) => format!(
+                            "Area of use: {}, which doesn't cover the layers",
+                            area_of_use.name
+                        ),
+                        (None, _) if self.extent.is_some() => {
+                            "Area of use unknown, so it isn't checked against the layers".into()
+                        }
+                        (None, _) => "Area of use unknown".into(),
+                    };
+                    if ui
+                        .selectable_label(false, text)
+                        .on_hover_text(hover_text)
+                        .clicked()
+                    {
+                        selected = Some(definition.epsg_code);
+                    }
+                }
+            });
+
+        if self.extent.is_some() {
+            ui.label(
+                egui::RichText::new(
+                    "Areas of use are only known for UTM zones and some national grids. Other \
+                     CRSs aren't checked against the layers.",
+                )
+                .small()
+                .weak(),
+            );
+        }
+
+        if let Some(epsg_code) = selected {
+            *self.text_field_value = format!("EPSG:{epsg_code}");
+            // Makes the input field parse the new value
+            *self.outcome = None;
+        }
+        ui.separator()
+    }
+}
+
+struct CrsInputFieldWidget<'a> {
     text_field_value: &'a mut String,
     outcome: &'a mut Option<Outcome>,
 }
 
-impl<'a> egui::Widget for EpsgCodeInputFieldWidget<'a> {
+impl<'a> egui::Widget for CrsInputFieldWidget<'a> {
     fn ui(self, ui: &mut egui::Ui) -> egui::Response {
         ui.horizontal(|ui| {
-            ui.label("EPSG:");
-            let edit_field = ui.text_edit_singleline(self.text_field_value);
+            ui.label("CRS:");
+            let edit_field = ui.add(
+                egui::TextEdit::singleline(self.text_field_value)
+                    .hint_text("e.g. EPSG:4326, ESRI:102100, WKT or +proj=…"),
+            );
 
             *self.outcome = if edit_field.changed()
                 || (!self.text_field_value.is_empty() && self.outcome.is_none())
             {
                 ui.add(ValidIconWidget);
-                Some(parse_epsg_input_value(self.text_field_value))
+                Some(parse_crs_input_value(self.text_field_value))
             } else if let Some(n) = self.outcome.take() {
                 if n.is_ok() {
                     ui.add(ValidIconWidget);
@@ -82,15 +198,13 @@ impl<'a> egui::Widget for EpsgCodeInputFieldWidget<'a> {
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
-    #[error("{0}")]
-    ParseIntError(#[from] std::num::ParseIntError),
     #[error("{0}")]
     TransformError(#[from] transform::Error),
 }
 
-fn parse_epsg_input_value(input: &str) -> Outcome {
-    let parsed = u16::from_str(input)?;
-    let outcome = transform::lookup_epsg_code(parsed)?;
+fn parse_crs_input_value(input: &str) -> Outcome {
+    let parsed = transform::Crs::from_str(input)?;
+    let outcome = transform::lookup_crs(&parsed)?;
     Ok(outcome)
 }
 
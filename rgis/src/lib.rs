@@ -12,6 +12,17 @@ use wasm_bindgen::prelude::*;
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 pub fn run() {
+    // Parsed before the app is set up, so subcommands don't initialize a window or the GPU
+    #[cfg(not(target_arch = "wasm32"))]
+    let cli_values = match rgis_cli::run() {
+        Ok(rgis_cli::Action::View(cli_values)) => cli_values,
+        Ok(rgis_cli::Action::Exit) => return,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+
     let mut app = App::new();
 
     app.add_plugins(MinimalPlugins);
@@ -37,6 +48,7 @@ pub fn run() {
     app.add_plugins(rgis_layers::Plugin);
     app.add_plugins(rgis_file_loader::Plugin);
     app.add_plugins(rgis_renderer::Plugin);
+    app.add_plugins(rgis_rasters::Plugin);
     app.add_plugins(rgis_mouse::Plugin);
     app.add_plugins(rgis_keyboard::Plugin);
     app.add_plugins(rgis_network::Plugin);
@@ -50,11 +62,6 @@ pub fn run() {
 
     #[cfg(not(target_arch = "wasm32"))]
     {
-        let cli_values = if let Ok(c) = rgis_cli::run() {
-            c
-        } else {
-            return;
-        };
         let msaa = match cli_values.msaa_sample_count {
             1 => Msaa::Off,
             2 => Msaa::Sample2,
@@ -65,6 +72,7 @@ pub fn run() {
 
         app.insert_resource(msaa);
         app.add_plugins(rgis_cli::Plugin(cli_values));
+        app.add_plugins(rgis_tilesets::Plugin);
     }
 
     app.run();
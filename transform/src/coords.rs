@@ -0,0 +1,56 @@
+/// Calls `f` on each coordinate of `geometry`, always in the same order, so coordinates gathered
+/// in one pass can be written back in another.
+pub(crate) fn for_each_coord_mut(
+    geometry: &mut geo::Geometry,
+    f: &mut impl FnMut(&mut geo::Coord),
+) {
+    match geometry {
+        geo::Geometry::Point(point) => f(&mut point.0),
+        geo::Geometry::Line(line) => {
+            f(&mut line.start);
+            f(&mut line.end);
+        }
+        geo::Geometry::LineString(line_string) => line_string.0.iter_mut().for_each(f),
+        geo::Geometry::Polygon(polygon) => polygon_for_each_coord_mut(polygon, f),
+        geo::Geometry::MultiPoint(multi_point) => {
+            for point in &mut multi_point.0 {
+                f(&mut point.0);
+            }
+        }
+        geo::Geometry::MultiLineString(multi_line_string) => {
+            for line_string in &mut multi_line_string.0 {
+                line_string.0.iter_mut().for_each(&mut *f);
+            }
+        }
+        geo::Geometry::MultiPolygon(multi_polygon) => {
+            for polygon in &mut multi_polygon.0 {
+                polygon_for_each_coord_mut(polygon, f);
+            }
+        }
+        geo::Geometry::GeometryCollection(geometry_collection) => {
+            for geometry in &mut geometry_collection.0 {
+                for_each_coord_mut(geometry, f);
+            }
+        }
+        geo::Geometry::Rect(rect) => {
+            let (mut min, mut max) = (rect.min(), rect.max());
+            f(&mut min);
+            f(&mut max);
+            *rect = geo::Rect::new(min, max);
+        }
+        geo::Geometry::Triangle(triangle) => {
+            f(&mut triangle.0);
+            f(&mut triangle.1);
+            f(&mut triangle.2);
+        }
+    }
+}
+
+fn polygon_for_each_coord_mut(polygon: &mut geo::Polygon, f: &mut impl FnMut(&mut geo::Coord)) {
+    polygon.exterior_mut(|exterior| exterior.0.iter_mut().for_each(&mut *f));
+    polygon.interiors_mut(|interiors| {
+        for interior in interiors {
+            interior.0.iter_mut().for_each(&mut *f);
+        }
+    });
+}
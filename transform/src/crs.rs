@@ -0,0 +1,313 @@
+use std::{borrow, fmt, str};
+
+/// A coordinate reference system
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Crs {
+    /// A code from the EPSG registry, e.g. 4326
+    Epsg(u16),
+    /// A code from ESRI's registry, e.g. 102100. Only a few common ones are known.
+    Esri(u32),
+    /// OGC WKT 1 or 2, or ESRI WKT, e.g. a Shapefile's `.prj`
+    Wkt(String),
+    /// PROJJSON, e.g. from GeoParquet metadata
+    ProjJson(String),
+    /// A PROJ string, e.g. `+proj=tmerc +lat_0=0 +lon_0=9 +k=0.9996`, or a geodesy operator or
+    /// pipeline, e.g. `tmerc lon_0=9 | helmert x=-100 y=250` for a local engineering grid
+    Proj(String),
+}
+
+/// ESRI codes for projections geodesy supports. Codes with an EPSG equivalent are mapped to it.
+const ESRI_CODES: &[(u32, EsriDefinition)] = &[
+    // WGS 1984 Web Mercator (Auxiliary Sphere)
+    (102100, EsriDefinition::Epsg(3857)),
+    // WGS 1984 Web Mercator
+    (102113, EsriDefinition::Epsg(3857)),
+    // USA Contiguous Lambert Conformal Conic
+    (
+        102004,
+        EsriDefinition::Proj("+proj=lcc +lat_1=33 +lat_2=45 +lat_0=39 +lon_0=-96 +x_0=0 +y_0=0 +datum=WGS84 +units=m +no_defs"),
+    ),
+    // North America Lambert Conformal Conic
+    (
+        102009,
+        EsriDefinition::Proj("+proj=lcc +lat_1=20 +lat_2=60 +lat_0=40 +lon_0=-96 +x_0=0 +y_0=0 +datum=NAD83 +units=m +no_defs"),
+    ),
+    // Europe Lambert Conformal Conic
+    (
+        102014,
+        EsriDefinition::Proj("+proj=lcc +lat_1=43 +lat_2=62 +lat_0=30 +lon_0=10 +x_0=0 +y_0=0 +ellps=intl +units=m +no_defs"),
+    ),
+    // North Pole Lambert Azimuthal Equal Area
+    (
+        102017,
+        EsriDefinition::Proj(
+            "+proj=laea +lat_0=90 +lon_0=0 +x_0=0 +y_0=0 +datum=WGS84 +units=m +no_defs",
+        ),
+    ),
+    // South Pole Lambert Azimuthal Equal Area
+    (
+        102020,
+        EsriDefinition::Proj(
+            "+proj=laea +lat_0=-90 +lon_0=0 +x_0=0 +y_0=0 +datum=WGS84 +units=m +no_defs",
+        ),
+    ),
+    // Asia North Lambert Conformal Conic
+    (
+        102027,
+        EsriDefinition::Proj("+proj=lcc +lat_1=15 +lat_2=65 +lat_0=30 +lon_0=95 +x_0=0 +y_0=0 +datum=WGS84 +units=m +no_defs"),
+    ),
+];
+
+/// Mercator stretches to infinity at the poles, so its maps are cut off where they're square
+const MERCATOR_MAX_LATITUDE: f64 = 85.051_128_779_806_59;
+
+enum EsriDefinition {
+    Epsg(u16),
+    Proj(&'static str),
+}
+
+fn esri_definition(code: u32) -> Option<&'static EsriDefinition> {
+    ESRI_CODES
+        .iter()
+        .find(|(esri_code, _)| *esri_code == code)
+        .map(|(_, definition)| definition)
+}
+
+impl Crs {
+    /// WGS 84, the CRS of GeoJSON
+    pub const WGS_84: Crs = Crs::Epsg(4326);
+
+    /// The EPSG code of the CRS, if it has one
+    pub fn epsg_code(&self) -> Option<u16> {
+        match self {
+            Crs::Epsg(epsg_code) => Some(*epsg_code),
+            Crs::Esri(esri_code) => match esri_definition(*esri_code)? {
+                EsriDefinition::Epsg(epsg_code) => Some(*epsg_code),
+                EsriDefinition::Proj(_) => None,
+            },
+            Crs::Wkt(wkt) => crate::wkt::epsg_code_from_wkt(wkt),
+            Crs::ProjJson(projjson) => crate::projjson::epsg_code(projjson),
+            Crs::Proj(_) => None,
+        }
+    }
+
+    /// Whether the CRS's official axis order is northing or latitude first, e.g. EPSG:4326 and
+    /// EPSG:3035. rgis itself always puts easting or longitude first, but some formats, e.g. GML,
+    /// follow the official order.
+    pub fn is_northing_first(&self) -> bool {
+        match self {
+            Crs::Wkt(wkt) => crate::wkt::is_northing_first(wkt),
+            Crs::ProjJson(projjson) => crate::projjson::is_northing_first(projjson),
+            _ => self
+                .epsg_code()
+                .and_then(crs_definitions::from_code)
+                .and_then(|definition| crate::wkt::is_northing_first(definition.wkt)),
+        }
+        .unwrap_or(false)
+    }
+
+    /// The CRS as WKT, e.g. for a Shapefile's `.prj`. `None` for CRSs that are only known as a
+    /// PROJ string, PROJJSON or a geodesy pipeline.
+    pub fn wkt(&self) -> Option<borrow::Cow<'_, str>> {
+        match self {
+            Crs::Wkt(wkt) => Some(wkt.as_str().into()),
+            _ => self
+                .epsg_code()
+                .and_then(crs_definitions::from_code)
+                .map(|definition| definition.wkt.into()),
+        }
+    }
+
+    /// The geodesy operator that projects geographic coordinates to this CRS
+    pub(crate) fn geodesy_definition(&self) -> Result<String, crate::Error> {
+        match self {
+            Crs::Proj(geodesy_definition) if !is_proj_string(geodesy_definition) => {
+                Ok(geodesy_definition.clone())
+            }
+            _ => Ok(geodesy::parse_proj(&self.proj_string()?)?),
+        }
+    }
+
+    /// The CRS as a PROJ string, e.g. `+proj=longlat +datum=WGS84 +no_defs`
+    fn proj_string(&self) -> Result<borrow::Cow<'_, str>, crate::Error> {
+        if let Some(definition) = self.epsg_code().and_then(crs_definitions::from_code) {
+            return Ok(definition.proj4.into());
+        }
+        match self {
+            Crs::Epsg(epsg_code) => Err(crate::Error::UnknownEpsgCode(*epsg_code)),
+            Crs::Esri(esri_code) => match esri_definition(*esri_code) {
+                Some(EsriDefinition::Proj(proj)) => Ok((*proj).into()),
+                // The EPSG equivalents are all in `crs_definitions`
+                Some(EsriDefinition::Epsg(_)) | None => {
+                    Err(crate::Error::UnknownEsriCode(*esri_code))
+                }
+            },
+            Crs::Wkt(wkt) => Ok(crate::wkt::proj_string_from_wkt(wkt)?.into()),
+            Crs::ProjJson(projjson) => {
+                Ok(crate::projjson::proj_string_from_projjson(projjson)?.into())
+            }
+            Crs::Proj(proj) if is_proj_string(proj) => Ok(proj.as_str().into()),
+            Crs::Proj(geodesy_definition) => Err(crate::Error::UnsupportedCrs(format!(
+                "{geodesy_definition} is a geodesy pipeline, not a PROJ string"
+            ))),
+        }
+    }
+
+    /// The value of e.g. `+proj=` in the CRS's PROJ string. `None` for geodesy pipelines, whose
+    /// parameters aren't interpreted.
+    fn proj_parameter(&self, key: &str) -> Option<String> {
+        let proj_string = self.proj_string().ok()?;
+        proj_string
+            .split_whitespace()
+            .find_map(|parameter| {
+                parameter
+                    .strip_prefix('+')?
+                    .strip_prefix(key)?
+                    .strip_prefix('=')
+            })
+            .map(str::to_owned)
+    }
+
+    /// Whether coordinates are longitudes and latitudes, which wrap around at the antimeridian
+    pub fn is_geographic(&self) -> bool {
+        self.proj_parameter("proj").is_some_and(|projection| {
+            matches!(
+                projection.as_str(),
+                "longlat" | "latlong" | "lonlat" | "latlon"
+            )
+        })
+    }
+
+    /// Whether the map is split at the meridian opposite the central one, as for cylindrical,
+    /// pseudocylindrical and conic projections. Azimuthal projections, e.g. polar ones, have no
+    /// such seam.
+    pub fn is_split_at_antimeridian(&self) -> bool {
+        self.proj_parameter("proj").is_some_and(|projection| {
+            matches!(
+                projection.as_str(),
+                "longlat"
+                    | "latlong"
+                    | "lonlat"
+                    | "latlon"
+                    | "merc"
+                    | "webmerc"
+                    | "eqc"
+                    | "cea"
+                    | "mill"
+                    | "gall"
+                    | "robin"
+                    | "moll"
+                    | "sinu"
+                    | "eck4"
+                    | "eck6"
+                    | "natearth"
+                    | "wintri"
+                    | "kav7"
+                    | "lcc"
+                    | "aea"
+                    | "eqdc"
+            )
+        })
+    }
+
+    /// The meridian in the middle of the map, in degrees
+    pub fn central_meridian(&self) -> f64 {
+        self.proj_parameter("lon_0")
+            .and_then(|lon_0| lon_0.parse().ok())
+            .unwrap_or(0.)
+    }
+
+    /// The latitude, in degrees, past which the projection is undefined or stretches to infinity,
+    /// e.g. ±85.05° for Web Mercator. `None` if the whole globe can be projected.
+    pub fn max_latitude(&self) -> Option<f64> {
+        match self.proj_parameter("proj")?.as_str() {
+            "merc" | "webmerc" => Some(MERCATOR_MAX_LATITUDE),
+            _ => None,
+        }
+    }
+}
+
+/// PROJ strings start with a parameter, e.g. `+proj=`, geodesy definitions with an operator
+fn is_proj_string(s: &str) -> bool {
+    s.trim_start().starts_with('+')
+}
+
+impl From<u16> for Crs {
+    fn from(epsg_code: u16) -> Self {
+        Crs::Epsg(epsg_code)
+    }
+}
+
+/// Parses what a user would type to identify a CRS: `4326`, `EPSG:4326`, `ESRI:102100`, WKT,
+/// PROJJSON or a PROJ string.
+impl str::FromStr for Crs {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some((authority, code)) = s.split_once(':') {
+            if authority.eq_ignore_ascii_case("EPSG") {
+                return code
+                    .trim()
+                    .parse()
+                    .map(Crs::Epsg)
+                    .map_err(|_| crate::Error::InvalidCrs(s.to_owned()));
+            }
+            if authority.eq_ignore_ascii_case("ESRI") {
+                return code
+                    .trim()
+                    .parse()
+                    .map(Crs::Esri)
+                    .map_err(|_| crate::Error::InvalidCrs(s.to_owned()));
+            }
+        }
+        if let Ok(code) = s.parse::<u32>() {
+            // ESRI codes are the ones that don't fit in EPSG's range
+            return Ok(match u16::try_from(code) {
+                Ok(epsg_code) => Crs::Epsg(epsg_code),
+                Err(_) => Crs::Esri(code),
+            });
+        }
+        if s.starts_with('{') {
+            return Ok(Crs::ProjJson(s.to_owned()));
+        }
+        if is_wkt(s) {
+            return Ok(Crs::Wkt(s.to_owned()));
+        }
+        if s.contains('=') || s.contains('|') {
+            return Ok(Crs::Proj(s.to_owned()));
+        }
+        Err(crate::Error::InvalidCrs(s.to_owned()))
+    }
+}
+
+/// WKT starts with a keyword directly followed by a bracket, e.g. `PROJCS[`
+fn is_wkt(s: &str) -> bool {
+    s.find(['[', '('])
+        .and_then(|i| s.get(..i))
+        .is_some_and(|keyword| {
+            !keyword.is_empty()
+                && keyword
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+}
+
+/// A short label, e.g. `EPSG:4326` or the name in a WKT definition
+impl fmt::Display for Crs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Crs::Epsg(epsg_code) => write!(f, "EPSG:{epsg_code}"),
+            Crs::Esri(esri_code) => write!(f, "ESRI:{esri_code}"),
+            Crs::Wkt(wkt) => match crate::wkt::crs_name(wkt) {
+                Some(name) => write!(f, "{name} (WKT)"),
+                None => write!(f, "Custom WKT"),
+            },
+            Crs::ProjJson(projjson) => match crate::projjson::crs_name(projjson) {
+                Some(name) => write!(f, "{name} (PROJJSON)"),
+                None => write!(f, "Custom PROJJSON"),
+            },
+            Crs::Proj(proj) => write!(f, "{proj}"),
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! The EPSG CRSs in `crs_definitions`, searchable by name, code and area of use
+
+use std::sync;
+
+/// Areas of use of common national grids, as (EPSG code, area, west, south, east, north). The
+/// definitions in `crs_definitions` don't include them.
+const AREAS_OF_USE: &[(u16, &str, f64, f64, f64, f64)] = &[
+    (2039, "Israel", 34.17, 29.45, 35.69, 33.28),
+    (
+        2056,
+        "Liechtenstein and Switzerland",
+        5.96,
+        45.82,
+        10.49,
+        47.81,
+    ),
+    (2154, "France", -9.86, 41.15, 10.38, 51.56),
+    (2180, "Poland", 14.14, 49.0, 24.15, 55.93),
+    (2193, "New Zealand", 166.37, -47.33, 178.63, -34.1),
+    (3006, "Sweden", 10.03, 54.96, 24.17, 69.07),
+    (3035, "Europe", -35.58, 24.6, 44.83, 84.73),
+    (3347, "Canada", -141.01, 38.21, -40.73, 86.46),
+    (3414, "Singapore", 103.59, 1.13, 104.07, 1.47),
+    (3577, "Australia", 112.85, -43.7, 153.69, -9.86),
+    (
+        3857,
+        "World between 85.06°S and 85.06°N",
+        -180.,
+        -85.06,
+        180.,
+        85.06,
+    ),
+    (4258, "Europe", -16.1, 32.88, 40.18, 84.73),
+    (4326, "World", -180., -90., 180., 90.),
+    (
+        5070,
+        "United States (USA) - CONUS",
+        -124.79,
+        24.41,
+        -66.91,
+        49.38,
+    ),
+    (5514, "Czechia and Slovakia", 12.09, 47.73, 22.56, 51.06),
+    (
+        21781,
+        "Liechtenstein and Switzerland",
+        5.96,
+        45.82,
+        10.49,
+        47.81,
+    ),
+    (27700, "United Kingdom (UK)", -9.01, 49.75, 2.01, 61.01),
+    (28992, "Netherlands", 3.2, 50.75, 7.22, 53.7),
+    (31370, "Belgium", 2.5, 49.5, 6.4, 51.51),
+];
+
+/// A CRS from the EPSG registry
+pub struct CrsDefinition {
+    pub epsg_code: u16,
+    pub name: &'static str,
+    /// `None` if the area isn't known
+    pub area_of_use: Option<AreaOfUse>,
+    /// The lowercase name and area, to match search terms against
+    search_text: String,
+}
+
+pub struct AreaOfUse {
+    pub name: String,
+    /// In WGS 84 longitude and latitude
+    pub bounds: geo::Rect,
+}
+
+impl AreaOfUse {
+    /// Whether `rect`, in WGS 84 longitude and latitude, is entirely within the area
+    pub fn covers(&self, rect: geo::Rect) -> bool {
+        self.bounds.min().x <= rect.min().x
+            && self.bounds.min().y <= rect.min().y
+            && self.bounds.max().x >= rect.max().x
+            && self.bounds.max().y >= rect.max().y
+    }
+}
+
+impl CrsDefinition {
+    fn new(epsg_code: u16, def: &crs_definitions::Def) -> Option<Self> {
+        let name = crate::wkt::crs_name(def.wkt)?;
+        let area_of_use = area_of_use(epsg_code, def);
+        let search_text = match &area_of_use {
+            Some(area_of_use) => format!("{} {}", name, area_of_use.name),
+            None => name.to_owned(),
+        }
+        .to_lowercase();
+        Some(CrsDefinition {
+            epsg_code,
+            name,
+            area_of_use,
+            search_text,
+        })
+    }
+
+    /// `term` is lowercase. Codes match by prefix, so `205` finds 2056.
+    fn matches(&self, term: &str) -> bool {
+        let code = term.strip_prefix("epsg:").unwrap_or(term);
+        self.epsg_code.to_string().starts_with(code) || self.search_text.contains(term)
+    }
+}
+
+fn area_of_use(epsg_code: u16, def: &crs_definitions::Def) -> Option<AreaOfUse> {
+    if let Some((name, bounds)) = crate::wkt::area_of_use(def.wkt) {
+        return Some(AreaOfUse { name, bounds });
+    }
+    if let Some((_, name, west, south, east, north)) =
+        AREAS_OF_USE.iter().find(|(code, ..)| *code == epsg_code)
+    {
+        return Some(AreaOfUse {
+            name: (*name).to_owned(),
+            bounds: geo::Rect::new(
+                geo::coord! { x: *west, y: *south },
+                geo::coord! { x: *east, y: *north },
+            ),
+        });
+    }
+    utm_area_of_use(def.proj4)
+}
+
+/// UTM zones are 6° wide, from the equator to 84°N or 80°S
+fn utm_area_of_use(proj4: &str) -> Option<AreaOfUse> {
+    let mut params = proj4.split_whitespace();
+    if !params.clone().any(|param| param == "+proj=utm") {
+        return None;
+    }
+    let zone = params
+        .clone()
+        .find_map(|param| param.strip_prefix("+zone="))?
+        .parse::<u8>()
+        .ok()
+        .filter(|zone| (1..=60).contains(zone))?;
+    let west = -180. + 6. * f64::from(zone - 1);
+    let east = west + 6.;
+    let (name, south, north) = if params.any(|param| param == "+south") {
+        (format!("UTM zone {zone}S"), -80., 0.)
+    } else {
+        (format!("UTM zone {zone}N"), 0., 84.)
+    };
+    Some(AreaOfUse {
+        name,
+        bounds: geo::Rect::new(
+            geo::coord! { x: west, y: south },
+            geo::coord! { x: east, y: north },
+        ),
+    })
+}
+
+/// All the CRSs in `crs_definitions`, sorted by code
+pub fn all_crs_definitions() -> &'static [CrsDefinition] {
+    static DEFINITIONS: sync::OnceLock<Vec<CrsDefinition>> = sync::OnceLock::new();
+    DEFINITIONS.get_or_init(|| {
+        (0..=u16::MAX)
+            .filter_map(|code| CrsDefinition::new(code, &crs_definitions::from_code(code)?))
+            .collect()
+    })
+}
+
+/// The CRSs matching every whitespace-separated term in `query` by name, code or area of use
+pub fn search_crs_definitions(query: &str) -> Vec<&'static CrsDefinition> {
+    let terms = query
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+    if terms.is_empty() {
+        return vec![];
+    }
+    all_crs_definitions()
+        .iter()
+        .filter(|definition| terms.iter().all(|term| definition.matches(term)))
+        .collect()
+}
@@ -6,9 +6,20 @@
     clippy::expect_used
 )]
 
-use geo::{Coord, MapCoords};
+use geo::Coord;
 
+mod coords;
+mod crs;
+mod definitions;
+mod pipeline;
+mod proj_string;
+mod projjson;
+mod wkt;
+
+pub use crs::Crs;
+pub use definitions::{all_crs_definitions, search_crs_definitions, AreaOfUse, CrsDefinition};
 pub use geodesy::{Context, Minimal, OpHandle};
+pub use wkt::epsg_code_from_wkt;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -17,57 +28,171 @@ pub enum Error {
     Geodesy(#[from] geodesy::Error),
     #[error("Unknown EPSG code: {0}")]
     UnknownEpsgCode(u16),
+    #[error("Unknown ESRI code: {0}")]
+    UnknownEsriCode(u32),
+    #[error("Invalid CRS: {0}")]
+    InvalidCrs(String),
+    #[error("Unsupported CRS: {0}")]
+    UnsupportedCrs(String),
+    #[error("Engineering CRSs aren't tied to the Earth, describe the grid with a geodesy pipeline instead")]
+    EngineeringCrs,
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
 }
 
+/// Coordinates are transformed this many at a time, so the buffer stays in the CPU cache
+const BATCH_SIZE: usize = 4096;
+
+/// Below this many coordinates per thread, starting the threads takes longer than it saves
+const MIN_COORDS_PER_THREAD: usize = 65_536;
+
 pub struct Transformer {
+    /// `None` when the transformation changes nothing, e.g. from a CRS to itself
+    pipeline: Option<Pipeline>,
+}
+
+struct Pipeline {
     ctx: geodesy::Minimal,
-    source: geodesy::OpHandle,
-    target: geodesy::OpHandle,
+    op: geodesy::OpHandle,
+    /// Each thread sets up its own context from this
+    definition: String,
 }
 
-impl Transformer {
-    pub fn setup(source_crs: u16, target_crs: u16) -> Result<Self, Error> {
-        let source =
-            crs_definitions::from_code(source_crs).ok_or(Error::UnknownEpsgCode(source_crs))?;
-        let target =
-            crs_definitions::from_code(target_crs).ok_or(Error::UnknownEpsgCode(target_crs))?;
+impl Pipeline {
+    fn compile(definition: String) -> Result<Self, geodesy::Error> {
         let mut ctx = geodesy_ctx();
-        let source_geodesy_string = geodesy::parse_proj(source.proj4)?;
-        let source_op_handle = ctx.op(&source_geodesy_string)?;
-        let target_geodesy_string = geodesy::parse_proj(target.proj4)?;
-        let target_op_handle = ctx.op(&target_geodesy_string)?;
-        Ok(Transformer {
+        let op = ctx.op(&definition)?;
+        Ok(Pipeline {
             ctx,
-            source: source_op_handle,
-            target: target_op_handle,
+            op,
+            definition,
         })
     }
 
-    pub fn transform(&self, geometry: &mut geo::Geometry) -> Result<(), geodesy::Error> {
-        // FIXME: use try_map_coords_in_place
-        let mut transformed = geometry.try_map_coords::<geodesy::Error>(|coord| {
-            let mut coord = [geodesy::Coor2D::gis(coord.x, coord.y)];
+    fn transform_coords_in_batches(&self, coords: &mut [Coord]) -> Result<(), geodesy::Error> {
+        let mut buffer = Vec::with_capacity(BATCH_SIZE.min(coords.len()));
+        for batch in coords.chunks_mut(BATCH_SIZE) {
+            buffer.clear();
+            buffer.extend(
+                batch
+                    .iter()
+                    .map(|coord| geodesy::Coor2D::gis(coord.x, coord.y)),
+            );
             self.ctx
-                .apply(self.source, geodesy::Direction::Inv, &mut coord)?;
-            self.ctx
-                .apply(self.target, geodesy::Direction::Fwd, &mut coord)?;
-            Ok(Coord {
-                x: coord[0].0[0],
-                y: coord[0].0[1],
-            })
-        })?;
+                .apply(self.op, geodesy::Direction::Fwd, &mut buffer)?;
+            for (coord, transformed) in batch.iter_mut().zip(&buffer) {
+                let [x, y] = transformed.0;
+                *coord = Coord { x, y };
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Transformer {
+    pub fn setup(source_crs: &Crs, target_crs: &Crs) -> Result<Self, Error> {
+        if source_crs == target_crs {
+            return Ok(Transformer { pipeline: None });
+        }
+        let definition = pipeline::compose(
+            &source_crs.geodesy_definition()?,
+            &target_crs.geodesy_definition()?,
+        );
+        Ok(Transformer {
+            pipeline: definition.map(Pipeline::compile).transpose()?,
+        })
+    }
+
+    /// Whether transforming leaves coordinates as they are, e.g. from a CRS to itself
+    pub fn is_identity(&self) -> bool {
+        self.pipeline.is_none()
+    }
 
-        std::mem::swap(&mut transformed, geometry);
+    pub fn transform(&self, geometry: &mut geo::Geometry) -> Result<(), geodesy::Error> {
+        self.transform_geometries(std::iter::once(geometry))
+    }
 
+    /// Gather the coordinates of all `geometries` into one buffer, transform it with
+    /// [`Transformer::transform_coords`] and write the results back in place
+    pub fn transform_geometries<'a>(
+        &self,
+        geometries: impl IntoIterator<Item = &'a mut geo::Geometry>,
+    ) -> Result<(), geodesy::Error> {
+        if self.is_identity() {
+            return Ok(());
+        }
+        let mut geometries = geometries.into_iter().collect::<Vec<_>>();
+        let mut coords = vec![];
+        for geometry in &mut geometries {
+            coords::for_each_coord_mut(geometry, &mut |coord| coords.push(*coord));
+        }
+
+        self.transform_coords(&mut coords)?;
+
+        let mut transformed = coords.into_iter();
+        for geometry in geometries {
+            coords::for_each_coord_mut(geometry, &mut |coord| {
+                if let Some(transformed) = transformed.next() {
+                    *coord = transformed;
+                }
+            });
+        }
         Ok(())
     }
+
+    /// Transform `coords` in place. Large buffers are split across threads, which each set up
+    /// their own geodesy context.
+    pub fn transform_coords(&self, coords: &mut [Coord]) -> Result<(), geodesy::Error> {
+        let Some(pipeline) = &self.pipeline else {
+            return Ok(());
+        };
+        let thread_count = thread_count(coords.len());
+        if thread_count <= 1 {
+            return pipeline.transform_coords_in_batches(coords);
+        }
+        let definition = &pipeline.definition;
+        std::thread::scope(|scope| {
+            let handles = coords
+                .chunks_mut(coords.len().div_ceil(thread_count))
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        Pipeline::compile(definition.clone())?.transform_coords_in_batches(chunk)
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect()
+        })
+    }
+}
+
+/// How many coordinates to give [`Transformer::transform_coords`] at once so that every thread
+/// gets enough of them, e.g. when transforming a layer in steps to report progress
+pub fn parallel_batch_size() -> usize {
+    max_thread_count() * MIN_COORDS_PER_THREAD
+}
+
+fn thread_count(coord_count: usize) -> usize {
+    max_thread_count().min(coord_count / MIN_COORDS_PER_THREAD)
+}
+
+/// Threads aren't available on the web
+fn max_thread_count() -> usize {
+    if cfg!(target_arch = "wasm32") {
+        return 1;
+    }
+    std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get)
 }
 
-pub fn lookup_epsg_code(epsg_code: u16) -> Result<(geodesy::Minimal, geodesy::OpHandle), Error> {
+pub fn lookup_crs(crs: &Crs) -> Result<(geodesy::Minimal, geodesy::OpHandle), Error> {
     let mut ctx = geodesy_ctx();
-    let def = crs_definitions::from_code(epsg_code).ok_or(Error::UnknownEpsgCode(epsg_code))?;
-    let source_geodesy_string = geodesy::parse_proj(def.proj4)?;
-    let op_handle = ctx.op(&source_geodesy_string)?;
+    let op_handle = ctx.op(&crs.geodesy_definition()?)?;
     Ok((ctx, op_handle))
 }
 
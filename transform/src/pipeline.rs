@@ -0,0 +1,94 @@
+//! One geodesy pipeline from the source CRS to the target CRS, instead of the inverse of the
+//! source's operator followed by the target's. Steps that undo each other, e.g. the `cart` steps
+//! around two `helmert` shifts to and from WGS 84, are left out.
+
+struct Step<'a> {
+    operator: &'a str,
+    /// Sorted, so `lat_0=0 lon_0=9` and `lon_0=9 lat_0=0` are the same step
+    parameters: Vec<&'a str>,
+    inverse: bool,
+}
+
+impl<'a> Step<'a> {
+    fn parse(step: &'a str) -> Option<Self> {
+        let mut inverse = false;
+        let mut words = vec![];
+        for word in step.split_whitespace() {
+            if word == "inv" {
+                inverse = !inverse;
+            } else {
+                words.push(word);
+            }
+        }
+        let (operator, parameters) = words.split_first()?;
+        let mut parameters = parameters.to_vec();
+        parameters.sort_unstable();
+        Some(Step {
+            operator,
+            parameters,
+            inverse,
+        })
+    }
+
+    fn inverted(self) -> Self {
+        Step {
+            inverse: !self.inverse,
+            ..self
+        }
+    }
+
+    /// Whether running `self` then `other` changes nothing
+    fn is_undone_by(&self, other: &Step) -> bool {
+        self.operator == other.operator
+            && self.parameters == other.parameters
+            && self.inverse != other.inverse
+            // Steps only run in one direction aren't undone by their inverse
+            && !self
+                .parameters
+                .iter()
+                .any(|parameter| parameter.starts_with("omit_"))
+    }
+}
+
+impl<'a> std::fmt::Display for Step<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.operator)?;
+        if self.inverse {
+            write!(f, " inv")?;
+        }
+        for parameter in &self.parameters {
+            write!(f, " {parameter}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The pipeline running the inverse of `source_definition`, then `target_definition`. `None` if
+/// every step is undone by another, i.e. the transformation changes nothing.
+pub(crate) fn compose(source_definition: &str, target_definition: &str) -> Option<String> {
+    let inverted_source = source_definition
+        .rsplit('|')
+        .filter_map(Step::parse)
+        .map(Step::inverted);
+    let target = target_definition.split('|').filter_map(Step::parse);
+
+    let mut steps = Vec::<Step>::new();
+    for step in inverted_source.chain(target) {
+        if steps.last().is_some_and(|last| last.is_undone_by(&step)) {
+            steps.pop();
+        } else {
+            steps.push(step);
+        }
+    }
+
+    if steps.is_empty() {
+        return None;
+    }
+    Some(
+        steps
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(" | "),
+    )
+}
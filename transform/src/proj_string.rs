@@ -0,0 +1,361 @@
+//! PROJ strings for the CRSs described by WKT or PROJJSON, which geodesy can then parse the same
+//! way as the definitions of EPSG codes.
+
+use std::fmt::Write;
+
+/// Radians per degree, as in WKT's `UNIT["degree",0.0174532925199433]`
+pub(crate) const DEGREE: f64 = std::f64::consts::PI / 180.;
+
+/// Ellipsoids geodesy knows by name: (name, semi-major axis, inverse flattening)
+const NAMED_ELLIPSOIDS: &[(&str, f64, f64)] = &[
+    ("WGS84", 6_378_137., 298.257_223_563),
+    ("GRS80", 6_378_137., 298.257_222_101),
+    ("intl", 6_378_388., 297.),
+    ("clrk66", 6_378_206.4, 294.978_698_2),
+    ("bessel", 6_377_397.155, 299.152_812_8),
+    ("airy", 6_377_563.396, 299.324_964_6),
+];
+
+/// Datums by name, with their shift to WGS 84 as in `+towgs84=`. An empty shift is WGS 84 itself.
+/// Names are normalized, e.g. `D_OSGB_1936` and `OSGB 1936` are both `osgb1936`.
+const KNOWN_DATUMS: &[(&[&str], &[f64])] = &[
+    (&["wgs1984", "wgs84", "worldgeodeticsystem1984"], &[]),
+    (
+        &[
+            "etrs1989",
+            "etrs89",
+            "europeanterrestrialreferencesystem1989",
+            "europeanterrestrialreferenceframe1989",
+        ],
+        &[0., 0., 0.],
+    ),
+    (
+        &["northamerican1983", "northamericandatum1983", "nad83"],
+        &[0., 0., 0.],
+    ),
+    (
+        &["gda1994", "gda94", "geocentricdatumofaustralia1994"],
+        &[0., 0., 0.],
+    ),
+    (
+        &["gda2020", "geocentricdatumofaustralia2020"],
+        &[0., 0., 0.],
+    ),
+    (
+        &["rgf1993", "rgf93", "reseaugeodesiquefrancais1993"],
+        &[0., 0., 0.],
+    ),
+    (
+        &[
+            "sirgas2000",
+            "sistemadereferenciageocentricoparalasamericas2000",
+        ],
+        &[0., 0., 0.],
+    ),
+    (&["nzgd2000", "newzealandgeodeticdatum2000"], &[0., 0., 0.]),
+    (
+        &["osgb1936", "ordnancesurveyofgreatbritain1936"],
+        &[446.448, -125.157, 542.06, 0.15, 0.247, 0.842, -20.489],
+    ),
+    // Both CH1903 and CH1903+, which normalize to the same name
+    (&["ch1903"], &[674.374, 15.056, 405.346]),
+    (
+        &["amersfoort"],
+        &[
+            565.417, 50.3319, 465.552, -0.398957, 0.343988, -1.8774, 4.0725,
+        ],
+    ),
+    (
+        &["dhdn", "deutscheshauptdreiecksnetz"],
+        &[598.1, 73.7, 418.2, 0.202, 0.045, -2.455, 6.7],
+    ),
+    (
+        &["ed50", "european1950", "europeandatum1950"],
+        &[-87., -98., -121.],
+    ),
+    (
+        &["mgi", "militargeographischeinstitut"],
+        &[577.326, 90.129, 463.919, 5.137, 1.474, 5.297, 2.4232],
+    ),
+    (
+        &["pulkovo1942"],
+        &[23.92, -141.27, -80.9, 0., 0.35, 0.82, -0.12],
+    ),
+    (&["tokyo"], &[-146.414, 507.337, 680.507]),
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Method {
+    TransverseMercator,
+    Mercator,
+    WebMercator,
+    LambertConformalConic,
+    LambertAzimuthalEqualArea,
+}
+
+impl Method {
+    /// Matches both the EPSG names, e.g. `Transverse Mercator`, and the WKT 1 and ESRI ones, e.g.
+    /// `Transverse_Mercator`
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        Some(match normalize_name(name).as_str() {
+            "transversemercator" | "gausskruger" => Method::TransverseMercator,
+            "mercator" | "mercator1sp" | "mercator2sp" | "mercatorvarianta"
+            | "mercatorvariantb" => Method::Mercator,
+            "popularvisualisationpseudomercator" | "mercatorauxiliarysphere" => Method::WebMercator,
+            "lambertconformalconic" | "lambertconformalconic1sp" | "lambertconformalconic2sp" => {
+                Method::LambertConformalConic
+            }
+            "lambertazimuthalequalarea" => Method::LambertAzimuthalEqualArea,
+            _ => return None,
+        })
+    }
+
+    fn proj_name(self) -> &'static str {
+        match self {
+            Method::TransverseMercator => "tmerc",
+            Method::Mercator => "merc",
+            Method::WebMercator => "webmerc",
+            Method::LambertConformalConic => "lcc",
+            Method::LambertAzimuthalEqualArea => "laea",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Parameter {
+    LatitudeOfOrigin,
+    CentralMeridian,
+    ScaleFactor,
+    FalseEasting,
+    FalseNorthing,
+    StandardParallel1,
+    StandardParallel2,
+}
+
+impl Parameter {
+    /// `None` for parameters that don't change the projection, e.g. ESRI's
+    /// `Auxiliary_Sphere_Type`
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        Some(match normalize_name(name).as_str() {
+            "latitudeoforigin"
+            | "latitudeofnaturalorigin"
+            | "latitudeofcenter"
+            | "latitudeoffalseorigin" => Parameter::LatitudeOfOrigin,
+            "centralmeridian"
+            | "longitudeofnaturalorigin"
+            | "longitudeofcenter"
+            | "longitudeoffalseorigin"
+            | "longitudeoforigin" => Parameter::CentralMeridian,
+            "scalefactor" | "scalefactoratnaturalorigin" => Parameter::ScaleFactor,
+            "falseeasting" | "eastingatfalseorigin" => Parameter::FalseEasting,
+            "falsenorthing" | "northingatfalseorigin" => Parameter::FalseNorthing,
+            "standardparallel1" | "latitudeof1ststandardparallel" => Parameter::StandardParallel1,
+            "standardparallel2" | "latitudeof2ndstandardparallel" => Parameter::StandardParallel2,
+            _ => return None,
+        })
+    }
+
+    pub(crate) fn unit(self) -> Unit {
+        match self {
+            Parameter::LatitudeOfOrigin
+            | Parameter::CentralMeridian
+            | Parameter::StandardParallel1
+            | Parameter::StandardParallel2 => Unit::Angle,
+            Parameter::FalseEasting | Parameter::FalseNorthing => Unit::Length,
+            Parameter::ScaleFactor => Unit::Scale,
+        }
+    }
+
+    fn proj_key(self, method: Method) -> &'static str {
+        match (self, method) {
+            // Mercator's standard parallel is the latitude of true scale
+            (Parameter::StandardParallel1, Method::Mercator) => "lat_ts",
+            (Parameter::LatitudeOfOrigin, _) => "lat_0",
+            (Parameter::CentralMeridian, _) => "lon_0",
+            (Parameter::ScaleFactor, _) => "k_0",
+            (Parameter::FalseEasting, _) => "x_0",
+            (Parameter::FalseNorthing, _) => "y_0",
+            (Parameter::StandardParallel1, _) => "lat_1",
+            (Parameter::StandardParallel2, _) => "lat_2",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Unit {
+    Angle,
+    Length,
+    Scale,
+}
+
+impl Unit {
+    /// Radians per degree for angles, otherwise 1, used when a value has no unit
+    pub(crate) fn default_factor(self) -> f64 {
+        match self {
+            Unit::Angle => DEGREE,
+            Unit::Length | Unit::Scale => 1.,
+        }
+    }
+
+    /// Converts a value to degrees, metres or unity, given the factor that converts its unit to
+    /// radians, metres or unity
+    pub(crate) fn to_proj_units(self, value: f64, factor: f64) -> f64 {
+        match self {
+            // Avoid rounding errors for the common case, e.g. 8.999999999999998 for 9
+            Unit::Angle if (factor - DEGREE).abs() < 1e-12 => value,
+            Unit::Angle => (value * factor).to_degrees(),
+            Unit::Length | Unit::Scale => value * factor,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct Ellipsoid {
+    /// In metres
+    pub(crate) semi_major_axis: f64,
+    /// 0 for a sphere
+    pub(crate) inverse_flattening: f64,
+}
+
+impl Ellipsoid {
+    /// WGS 84's and GRS 80's differ by a tenth of a millimetre
+    fn is_wgs84_compatible(self) -> bool {
+        (self.semi_major_axis - 6_378_137.).abs() < 1e-3
+            && (self.inverse_flattening - 298.257).abs() < 1e-3
+    }
+}
+
+/// How a datum relates to WGS 84
+pub(crate) enum Datum {
+    /// WGS 84 itself, or a datum within a metre of it that doesn't declare a shift
+    Wgs84,
+    /// The 3 or 7 parameters of `+towgs84=`
+    ToWgs84(Vec<f64>),
+}
+
+impl Datum {
+    /// The datum named `name`, using its `TOWGS84` shift if the CRS declares one. Datums that
+    /// aren't known and aren't on a WGS 84 compatible ellipsoid are unsupported, rather than
+    /// silently placed up to hundreds of metres off.
+    pub(crate) fn resolve(
+        name: Option<&str>,
+        to_wgs84: Option<Vec<f64>>,
+        ellipsoid: Option<Ellipsoid>,
+    ) -> Result<Self, crate::Error> {
+        if let Some(to_wgs84) = to_wgs84.filter(|to_wgs84| matches!(to_wgs84.len(), 3 | 7)) {
+            return Ok(Datum::ToWgs84(to_wgs84));
+        }
+        let Some(name) = name else {
+            return Ok(Datum::Wgs84);
+        };
+        let normalized = normalize_datum_name(name);
+        let known = KNOWN_DATUMS
+            .iter()
+            .find(|(names, _)| names.contains(&normalized.as_str()));
+        match known {
+            Some((_, [])) => Ok(Datum::Wgs84),
+            Some((_, shift)) => Ok(Datum::ToWgs84(shift.to_vec())),
+            // Spheres are only used for e.g. Web Mercator, never for surveying
+            None if ellipsoid.map_or(true, |ellipsoid| {
+                ellipsoid.is_wgs84_compatible() || ellipsoid.inverse_flattening == 0.
+            }) =>
+            {
+                Ok(Datum::Wgs84)
+            }
+            None => Err(crate::Error::UnsupportedCrs(format!(
+                "{name} datum, which has no known shift to WGS 84"
+            ))),
+        }
+    }
+}
+
+pub(crate) fn geographic(ellipsoid: Option<Ellipsoid>, datum: &Datum) -> String {
+    format!(
+        "+proj=longlat {}{} +no_defs",
+        ellipsoid_params(ellipsoid),
+        datum_params(datum)
+    )
+}
+
+/// `parameters` are in degrees, metres or unity
+pub(crate) fn projected(
+    method: Method,
+    ellipsoid: Option<Ellipsoid>,
+    datum: &Datum,
+    parameters: &[(Parameter, f64)],
+) -> String {
+    let mut proj = format!(
+        "+proj={} {}{}",
+        method.proj_name(),
+        ellipsoid_params(ellipsoid),
+        datum_params(datum)
+    );
+    for (parameter, value) in parameters {
+        let _ = write!(proj, " +{}={}", parameter.proj_key(method), value);
+    }
+    // The 1SP variant's only standard parallel is its latitude of origin
+    let has_standard_parallel = parameters
+        .iter()
+        .any(|(parameter, _)| *parameter == Parameter::StandardParallel1);
+    if method == Method::LambertConformalConic && !has_standard_parallel {
+        if let Some((_, latitude_of_origin)) = parameters
+            .iter()
+            .find(|(parameter, _)| *parameter == Parameter::LatitudeOfOrigin)
+        {
+            let _ = write!(proj, " +lat_1={latitude_of_origin}");
+        }
+    }
+    proj.push_str(" +units=m +no_defs");
+    proj
+}
+
+fn ellipsoid_params(ellipsoid: Option<Ellipsoid>) -> String {
+    let Some(ellipsoid) = ellipsoid else {
+        return "+ellps=WGS84".into();
+    };
+    let named = NAMED_ELLIPSOIDS.iter().find(|(_, a, rf)| {
+        (ellipsoid.semi_major_axis - a).abs() < 1e-3
+            && (ellipsoid.inverse_flattening - rf).abs() < 1e-6
+    });
+    match named {
+        Some((name, _, _)) => format!("+ellps={name}"),
+        None if ellipsoid.inverse_flattening == 0. => format!("+R={}", ellipsoid.semi_major_axis),
+        None => format!(
+            "+a={} +rf={}",
+            ellipsoid.semi_major_axis, ellipsoid.inverse_flattening
+        ),
+    }
+}
+
+fn datum_params(datum: &Datum) -> String {
+    match datum {
+        Datum::Wgs84 => String::new(),
+        Datum::ToWgs84(shift) => format!(
+            " +towgs84={}",
+            shift
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+    }
+}
+
+/// ESRI prefixes datum names, e.g. `D_OSGB_1936`, and WKT 2 may name an ensemble, e.g. `World
+/// Geodetic System 1984 ensemble`
+fn normalize_datum_name(name: &str) -> String {
+    let name = name.strip_prefix("D_").unwrap_or(name);
+    let name = normalize_name(name);
+    match name.strip_suffix("ensemble") {
+        Some(name) => name.to_owned(),
+        None => name,
+    }
+}
+
+/// `Latitude_Of_Origin` and `Latitude of origin` are the same parameter
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
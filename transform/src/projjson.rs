@@ -0,0 +1,158 @@
+//! PROJJSON, the JSON encoding of WKT 2 used by e.g. GeoParquet: https://proj.org/specifications/projjson.html
+
+/// The EPSG code from the CRS's `id` (or first of its `ids`), if its authority is EPSG
+pub(crate) fn epsg_code(projjson: &str) -> Option<u16> {
+    let crs = serde_json::from_str::<serde_json::Value>(projjson).ok()?;
+    let id = crs
+        .get("id")
+        .or_else(|| crs.get("ids")?.as_array()?.first())?;
+    if !id.get("authority")?.as_str()?.eq_ignore_ascii_case("EPSG") {
+        return None;
+    }
+    match id.get("code")? {
+        serde_json::Value::Number(code) => code.as_u64()?.try_into().ok(),
+        serde_json::Value::String(code) => code.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Whether the first axis points north or south, e.g. latitude first
+pub(crate) fn is_northing_first(projjson: &str) -> Option<bool> {
+    let crs = serde_json::from_str::<serde_json::Value>(projjson).ok()?;
+    let direction = crs
+        .get("coordinate_system")?
+        .get("axis")?
+        .as_array()?
+        .first()?
+        .get("direction")?
+        .as_str()?;
+    Some(matches!(direction, "north" | "south"))
+}
+
+pub(crate) fn crs_name(projjson: &str) -> Option<String> {
+    let crs = serde_json::from_str::<serde_json::Value>(projjson).ok()?;
+    Some(crs.get("name")?.as_str()?.to_owned())
+}
+
+/// Translate PROJJSON into a PROJ string. Only geographic CRSs and the projections in
+/// [`crate::proj_string::Method`] are supported.
+pub(crate) fn proj_string_from_projjson(projjson: &str) -> Result<String, crate::Error> {
+    let crs = serde_json::from_str::<serde_json::Value>(projjson)?;
+    match crs.get("type").and_then(serde_json::Value::as_str) {
+        Some("GeographicCRS" | "GeodeticCRS") => Ok(crate::proj_string::geographic(
+            ellipsoid(&crs),
+            &datum(&crs)?,
+        )),
+        Some("ProjectedCRS") => projected_proj_string(&crs),
+        Some("EngineeringCRS") => Err(crate::Error::EngineeringCrs),
+        Some(crs_type) => Err(crate::Error::UnsupportedCrs(crs_type.to_owned())),
+        None => Err(crate::Error::InvalidCrs(
+            "PROJJSON is missing a type".into(),
+        )),
+    }
+}
+
+fn projected_proj_string(crs: &serde_json::Value) -> Result<String, crate::Error> {
+    let conversion = crs
+        .get("conversion")
+        .ok_or_else(|| crate::Error::InvalidCrs("PROJJSON is missing a conversion".into()))?;
+    let method_name = conversion
+        .get("method")
+        .and_then(|method| method.get("name")?.as_str())
+        .ok_or_else(|| {
+            crate::Error::InvalidCrs("PROJJSON is missing a projection method".into())
+        })?;
+    let method = crate::proj_string::Method::from_name(method_name)
+        .ok_or_else(|| crate::Error::UnsupportedCrs(format!("{method_name} projection")))?;
+
+    // Projected coordinates are always treated as metres
+    let axis_unit = crs
+        .get("coordinate_system")
+        .and_then(|coordinate_system| coordinate_system.get("axis")?.as_array()?.first())
+        .and_then(|axis| axis.get("unit"));
+    if unit_factor(axis_unit, crate::proj_string::Unit::Length)
+        .is_some_and(|factor| (factor - 1.).abs() > 1e-9)
+    {
+        return Err(crate::Error::UnsupportedCrs(
+            "Non-metre units for a projected CRS".into(),
+        ));
+    }
+
+    let parameters = conversion
+        .get("parameters")
+        .and_then(serde_json::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|parameter| {
+            let name = crate::proj_string::Parameter::from_name(parameter.get("name")?.as_str()?)?;
+            let unit = name.unit();
+            let factor = unit_factor(parameter.get("unit"), unit)?;
+            let value = parameter.get("value")?.as_f64()?;
+            Some((name, unit.to_proj_units(value, factor)))
+        })
+        .collect::<Vec<_>>();
+
+    let base_crs = crs
+        .get("base_crs")
+        .ok_or_else(|| crate::Error::InvalidCrs("PROJJSON is missing a base CRS".into()))?;
+    Ok(crate::proj_string::projected(
+        method,
+        ellipsoid(base_crs),
+        &datum(base_crs)?,
+        &parameters,
+    ))
+}
+
+/// PROJJSON has no `TOWGS84`, so only known datums get a shift
+fn datum(crs: &serde_json::Value) -> Result<crate::proj_string::Datum, crate::Error> {
+    let name = crs
+        .get("datum")
+        .or_else(|| crs.get("datum_ensemble"))
+        .and_then(|datum| datum.get("name")?.as_str());
+    crate::proj_string::Datum::resolve(name, None, ellipsoid(crs))
+}
+
+fn ellipsoid(crs: &serde_json::Value) -> Option<crate::proj_string::Ellipsoid> {
+    let ellipsoid = crs
+        .get("datum")
+        .or_else(|| crs.get("datum_ensemble"))?
+        .get("ellipsoid")?;
+    // Spheres only have a radius
+    if let Some(radius) = ellipsoid.get("radius").and_then(length) {
+        return Some(crate::proj_string::Ellipsoid {
+            semi_major_axis: radius,
+            inverse_flattening: 0.,
+        });
+    }
+    Some(crate::proj_string::Ellipsoid {
+        semi_major_axis: ellipsoid.get("semi_major_axis").and_then(length)?,
+        inverse_flattening: ellipsoid.get("inverse_flattening")?.as_f64()?,
+    })
+}
+
+/// A length in metres, either a number or `{"value": …, "unit": …}`
+fn length(value: &serde_json::Value) -> Option<f64> {
+    match value.as_f64() {
+        Some(metres) => Some(metres),
+        None => Some(
+            value.get("value")?.as_f64()?
+                * unit_factor(value.get("unit"), crate::proj_string::Unit::Length)?,
+        ),
+    }
+}
+
+/// The factor converting `unit` to radians, metres or unity. Units are either one of a few
+/// well-known names or `{"name": …, "conversion_factor": …}`.
+fn unit_factor(unit: Option<&serde_json::Value>, kind: crate::proj_string::Unit) -> Option<f64> {
+    let Some(unit) = unit else {
+        return Some(kind.default_factor());
+    };
+    if let Some(name) = unit.as_str() {
+        return match name {
+            "degree" => Some(crate::proj_string::DEGREE),
+            "metre" | "unity" | "radian" => Some(1.),
+            _ => None,
+        };
+    }
+    unit.get("conversion_factor")?.as_f64()
+}
@@ -0,0 +1,344 @@
+use std::{collections, sync};
+
+/// Determine the EPSG code for a CRS described as OGC or ESRI WKT (e.g. a Shapefile's `.prj`).
+///
+/// The outermost `AUTHORITY["EPSG", …]`/`ID["EPSG", …]` is used if present. ESRI WKT usually
+/// doesn't have one, so fall back to matching the CRS name against the known definitions.
+pub fn epsg_code_from_wkt(wkt: &str) -> Option<u16> {
+    if let Some(code) = top_level_epsg_authority(wkt) {
+        return Some(code);
+    }
+    let name = normalize_name(first_quoted_string(wkt)?);
+    epsg_codes_by_name().get(&name).copied()
+}
+
+fn top_level_epsg_authority(wkt: &str) -> Option<u16> {
+    let mut depth = 0usize;
+    let mut in_quotes = false;
+    let mut authority = None;
+    for (i, c) in wkt.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '[' | '(' if !in_quotes => {
+                depth += 1;
+                // The keyword directly precedes the opening bracket
+                let keyword = wkt
+                    .get(..i)?
+                    .rsplit(|c: char| !c.is_ascii_alphabetic())
+                    .next()?;
+                if depth == 2 && matches!(keyword, "AUTHORITY" | "ID") {
+                    authority = wkt.get(i + 1..);
+                }
+            }
+            ']' | ')' if !in_quotes => depth = depth.saturating_sub(1),
+            _ => (),
+        }
+    }
+    let mut parts = authority?.split(',');
+    let name = parts.next()?.trim().trim_matches('"');
+    if !name.eq_ignore_ascii_case("EPSG") {
+        return None;
+    }
+    parts
+        .next()?
+        .trim()
+        .trim_end_matches([']', ')'])
+        .trim_matches('"')
+        .parse()
+        .ok()
+}
+
+fn first_quoted_string(wkt: &str) -> Option<&str> {
+    let (_, rest) = wkt.split_once('"')?;
+    let (name, _) = rest.split_once('"')?;
+    Some(name)
+}
+
+fn normalize_name(name: &str) -> String {
+    // ESRI prefixes geographic CRS names, e.g. `GCS_WGS_1984`
+    let name = name.strip_prefix("GCS_").unwrap_or(name);
+    name.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn epsg_codes_by_name() -> &'static collections::HashMap<String, u16> {
+    static CODES: sync::OnceLock<collections::HashMap<String, u16>> = sync::OnceLock::new();
+    CODES.get_or_init(|| {
+        let mut codes = collections::HashMap::new();
+        for code in 0..=u16::MAX {
+            let Some(def) = crs_definitions::from_code(code) else {
+                continue;
+            };
+            if let Some(name) = first_quoted_string(def.wkt) {
+                codes.entry(normalize_name(name)).or_insert(code);
+            }
+        }
+        codes
+    })
+}
+
+/// The name of the CRS, e.g. `WGS 84 / UTM zone 32N`
+pub(crate) fn crs_name(wkt: &str) -> Option<&str> {
+    first_quoted_string(wkt)
+}
+
+/// Translate WKT 1, ESRI WKT or WKT 2 into a PROJ string. Only geographic CRSs and the
+/// projections in [`crate::proj_string::Method`] are supported.
+pub(crate) fn proj_string_from_wkt(wkt: &str) -> Result<String, crate::Error> {
+    let (root, _) = parse_node(wkt).ok_or_else(|| crate::Error::InvalidCrs(wkt.to_owned()))?;
+    match root.keyword.to_ascii_uppercase().as_str() {
+        "GEOGCS" | "GEOGCRS" | "GEOGRAPHICCRS" | "GEODCRS" | "GEODETICCRS" => Ok(
+            crate::proj_string::geographic(ellipsoid(&root), &datum(&root)?),
+        ),
+        "PROJCS" => projcs_proj_string(&root),
+        "PROJCRS" | "PROJECTEDCRS" => projcrs_proj_string(&root),
+        "LOCAL_CS" | "ENGCRS" | "ENGINEERINGCRS" => Err(crate::Error::EngineeringCrs),
+        keyword => Err(crate::Error::UnsupportedCrs(format!("{keyword} WKT"))),
+    }
+}
+
+/// WKT 1, e.g. `PROJCS["…",GEOGCS[…],PROJECTION["Transverse_Mercator"],PARAMETER[…],UNIT[…]]`
+fn projcs_proj_string(projcs: &Node) -> Result<String, crate::Error> {
+    let method = method(projcs.child(&["PROJECTION"]))?;
+    check_linear_unit(projcs.child(&["UNIT"]))?;
+    // Angular parameters are in the geographic CRS's unit
+    let angle_factor = projcs
+        .child(&["GEOGCS"])
+        .and_then(|geogcs| geogcs.child(&["UNIT"]))
+        .and_then(|unit| unit.number(0));
+    let parameters = projcs
+        .children(&["PARAMETER"])
+        .filter_map(|parameter| {
+            let name = crate::proj_string::Parameter::from_name(parameter.text()?)?;
+            let unit = name.unit();
+            let factor = match unit {
+                crate::proj_string::Unit::Angle => angle_factor,
+                crate::proj_string::Unit::Length | crate::proj_string::Unit::Scale => None,
+            }
+            .unwrap_or_else(|| unit.default_factor());
+            Some((name, unit.to_proj_units(parameter.number(0)?, factor)))
+        })
+        .collect::<Vec<_>>();
+    Ok(crate::proj_string::projected(
+        method,
+        ellipsoid(projcs),
+        &datum(projcs)?,
+        &parameters,
+    ))
+}
+
+/// WKT 2, e.g. `PROJCRS["…",BASEGEOGCRS[…],CONVERSION["…",METHOD["Transverse Mercator"],
+/// PARAMETER["…",0,ANGLEUNIT[…]],…],CS[…],AXIS[…,LENGTHUNIT[…]]]`
+fn projcrs_proj_string(projcrs: &Node) -> Result<String, crate::Error> {
+    let conversion = projcrs
+        .child(&["CONVERSION"])
+        .ok_or_else(|| crate::Error::InvalidCrs("WKT is missing a CONVERSION".into()))?;
+    let method = method(conversion.child(&["METHOD", "PROJECTION"]))?;
+    check_linear_unit(
+        projcrs
+            .child(&["LENGTHUNIT", "UNIT"])
+            .or_else(|| projcrs.child(&["AXIS"])?.child(&["LENGTHUNIT", "UNIT"])),
+    )?;
+    let parameters = conversion
+        .children(&["PARAMETER"])
+        .filter_map(|parameter| {
+            let name = crate::proj_string::Parameter::from_name(parameter.text()?)?;
+            let unit = name.unit();
+            let factor = parameter
+                .child(&["ANGLEUNIT", "LENGTHUNIT", "SCALEUNIT", "UNIT"])
+                .and_then(|unit| unit.number(0))
+                .unwrap_or_else(|| unit.default_factor());
+            Some((name, unit.to_proj_units(parameter.number(0)?, factor)))
+        })
+        .collect::<Vec<_>>();
+    Ok(crate::proj_string::projected(
+        method,
+        ellipsoid(projcrs),
+        &datum(projcrs)?,
+        &parameters,
+    ))
+}
+
+fn method(node: Option<&Node>) -> Result<crate::proj_string::Method, crate::Error> {
+    let name = node
+        .and_then(Node::text)
+        .ok_or_else(|| crate::Error::InvalidCrs("WKT is missing a projection method".into()))?;
+    crate::proj_string::Method::from_name(name)
+        .ok_or_else(|| crate::Error::UnsupportedCrs(format!("{name} projection")))
+}
+
+/// Projected coordinates are always treated as metres
+fn check_linear_unit(unit: Option<&Node>) -> Result<(), crate::Error> {
+    match unit {
+        Some(unit)
+            if unit
+                .number(0)
+                .is_some_and(|factor| (factor - 1.).abs() > 1e-9) =>
+        {
+            Err(crate::Error::UnsupportedCrs(format!(
+                "{} as the unit of a projected CRS",
+                unit.text().unwrap_or("Non-metre units")
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn ellipsoid(crs: &Node) -> Option<crate::proj_string::Ellipsoid> {
+    let ellipsoid = crs.find(&["SPHEROID", "ELLIPSOID"])?;
+    let factor = ellipsoid
+        .child(&["LENGTHUNIT", "UNIT"])
+        .and_then(|unit| unit.number(0))
+        .unwrap_or(1.);
+    Some(crate::proj_string::Ellipsoid {
+        semi_major_axis: ellipsoid.number(0)? * factor,
+        inverse_flattening: ellipsoid.number(1)?,
+    })
+}
+
+/// WKT 1's `DATUM["…",SPHEROID[…],TOWGS84[…]]`, or WKT 2's `DATUM`, `TRF` or `ENSEMBLE`
+fn datum(crs: &Node) -> Result<crate::proj_string::Datum, crate::Error> {
+    let datum = crs.find(&["DATUM", "GEODETICDATUM", "TRF", "ENSEMBLE"]);
+    let to_wgs84 = datum
+        .and_then(|datum| datum.child(&["TOWGS84"]))
+        .map(|to_wgs84| (0..7).map_while(|n| to_wgs84.number(n)).collect());
+    crate::proj_string::Datum::resolve(datum.and_then(Node::text), to_wgs84, ellipsoid(crs))
+}
+
+/// A WKT keyword and what's between its brackets, e.g. `UNIT["metre",1]`
+struct Node<'a> {
+    keyword: &'a str,
+    values: Vec<Value<'a>>,
+}
+
+enum Value<'a> {
+    Text(&'a str),
+    Number(f64),
+    /// An unquoted enumeration value, e.g. `east` in `AXIS["easting",east]`
+    Keyword(&'a str),
+    Node(Node<'a>),
+}
+
+impl<'a> Node<'a> {
+    fn child(&self, keywords: &[&str]) -> Option<&Node<'a>> {
+        self.values.iter().find_map(|value| match value {
+            Value::Node(node) if node.is(keywords) => Some(node),
+            _ => None,
+        })
+    }
+
+    fn children<'b>(&'b self, keywords: &'b [&str]) -> impl Iterator<Item = &'b Node<'a>> {
+        self.values.iter().filter_map(move |value| match value {
+            Value::Node(node) if node.is(keywords) => Some(node),
+            _ => None,
+        })
+    }
+
+    /// The first matching node, depth first
+    fn find(&self, keywords: &[&str]) -> Option<&Node<'a>> {
+        self.values.iter().find_map(|value| match value {
+            Value::Node(node) if node.is(keywords) => Some(node),
+            Value::Node(node) => node.find(keywords),
+            _ => None,
+        })
+    }
+
+    fn is(&self, keywords: &[&str]) -> bool {
+        keywords
+            .iter()
+            .any(|keyword| self.keyword.eq_ignore_ascii_case(keyword))
+    }
+
+    fn text(&self) -> Option<&'a str> {
+        self.values.iter().find_map(|value| match value {
+            Value::Text(text) => Some(*text),
+            _ => None,
+        })
+    }
+
+    fn keyword_value(&self) -> Option<&'a str> {
+        self.values.iter().find_map(|value| match value {
+            Value::Keyword(keyword) => Some(*keyword),
+            _ => None,
+        })
+    }
+
+    fn number(&self, n: usize) -> Option<f64> {
+        self.values
+            .iter()
+            .filter_map(|value| match value {
+                Value::Number(number) => Some(*number),
+                _ => None,
+            })
+            .nth(n)
+    }
+}
+
+/// Parses `KEYWORD[value,…]` (or with parentheses) and returns what's left after it
+fn parse_node(s: &str) -> Option<(Node<'_>, &str)> {
+    let s = s.trim_start();
+    let keyword_len = s.find(|c: char| !c.is_ascii_alphanumeric() && c != '_')?;
+    let (keyword, rest) = s.split_at(keyword_len);
+    let mut rest = rest.trim_start().strip_prefix(['[', '('])?;
+    let mut values = vec![];
+    loop {
+        rest = rest.trim_start();
+        if let Some(after) = rest.strip_prefix([']', ')']) {
+            return Some((Node { keyword, values }, after));
+        }
+        let (value, after) = parse_value(rest)?;
+        values.push(value);
+        rest = after.trim_start();
+        rest = rest.strip_prefix(',').unwrap_or(rest);
+    }
+}
+
+fn parse_value(s: &str) -> Option<(Value<'_>, &str)> {
+    if let Some(quoted) = s.strip_prefix('"') {
+        let (text, rest) = quoted.split_once('"')?;
+        return Some((Value::Text(text), rest));
+    }
+    let end = s
+        .find([',', ']', ')'])
+        .unwrap_or(s.len())
+        .min(s.find(['[', '(']).unwrap_or(s.len()));
+    let (token, rest) = s.split_at(end);
+    if token.trim().is_empty() && !rest.starts_with(['[', '(']) {
+        return None;
+    }
+    if rest.starts_with(['[', '(']) {
+        let (node, rest) = parse_node(s)?;
+        return Some((Value::Node(node), rest));
+    }
+    match token.trim().parse() {
+        Ok(number) => Some((Value::Number(number), rest)),
+        Err(_) => Some((Value::Keyword(token.trim()), rest)),
+    }
+}
+
+/// Whether the first `AXIS` points north or south, e.g. latitude first for EPSG:4326 or northing
+/// first for EPSG:3035. `None` if the WKT doesn't declare its axes.
+pub(crate) fn is_northing_first(wkt: &str) -> Option<bool> {
+    let (root, _) = parse_node(wkt)?;
+    let direction = root.child(&["AXIS"])?.keyword_value()?;
+    Some(direction.eq_ignore_ascii_case("north") || direction.eq_ignore_ascii_case("south"))
+}
+
+/// The name and `BBOX[south,west,north,east]` of the first `USAGE` in WKT 2. WKT 1 has neither.
+pub(crate) fn area_of_use(wkt: &str) -> Option<(String, geo::Rect)> {
+    let (root, _) = parse_node(wkt)?;
+    let bbox = root.find(&["BBOX"])?;
+    let name = root
+        .find(&["AREA"])
+        .and_then(Node::text)
+        .unwrap_or_default();
+    Some((
+        name.to_owned(),
+        geo::Rect::new(
+            geo::coord! { x: bbox.number(1)?, y: bbox.number(0)? },
+            geo::coord! { x: bbox.number(3)?, y: bbox.number(2)? },
+        ),
+    ))
+}